@@ -5,6 +5,12 @@
 use owiwi::Owiwi;
 use owiwi::TraceExporter;
 
+#[cfg(not(feature = "console"))]
+fn main() {
+    eprintln!("this example needs the `console` feature");
+}
+
+#[cfg(feature = "console")]
 fn main() -> owiwi::Result<()> {
     let guard = Owiwi::builder()
         .service_name("console-example")
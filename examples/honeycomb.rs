@@ -9,10 +9,17 @@
 #![allow(warnings)]
 use std::time::Duration;
 
+#[cfg(feature = "honeycomb")]
 use owiwi::HoneycombConfig;
 use owiwi::Owiwi;
 use owiwi::TraceExporter;
 
+#[cfg(not(feature = "honeycomb"))]
+fn main() {
+    eprintln!("this example needs the `honeycomb` feature");
+}
+
+#[cfg(feature = "honeycomb")]
 fn main() -> owiwi::Result<()> {
     let api_key = std::env::var("HONEYCOMB_API_KEY").expect("HONEYCOMB_API_KEY must be set");
 
@@ -0,0 +1,348 @@
+//! File-based configuration.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+#[cfg(feature = "toml")]
+use std::path::Path;
+use std::path::PathBuf;
+
+use jiff::SignedDuration;
+use tracing_subscriber::filter::Directive;
+use url::Url;
+
+use crate::EventFormat;
+use crate::KeepaliveConfig;
+use crate::OutputTarget;
+use crate::Owiwi;
+use crate::Propagator;
+use crate::RetryConfig;
+#[cfg(feature = "sentry")]
+use crate::SentryConfig;
+use crate::SpanEvent;
+use crate::TimeFormat;
+use crate::TlsConfig;
+use crate::error::Error;
+#[cfg(feature = "toml")]
+use crate::error::ErrorKind;
+use crate::trace::BatchConfig;
+use crate::trace::SpanLimits;
+use crate::trace::TraceExporter;
+
+/// Deserializable [`Owiwi`] configuration, for example a section of an
+/// application configuration file.
+///
+/// Unset values take the same defaults as [`Owiwi::default`]. Serializing
+/// dumps the configuration with secrets and header values redacted.
+///
+/// ```toml
+/// service_name = "checkout"
+/// tracing_directives = ["info", "checkout=debug"]
+///
+/// [resource_attributes]
+/// "deployment.environment" = "production"
+///
+/// [traces.otlp]
+/// endpoint = "http://collector:4317"
+/// timeout = "10s"
+/// ```
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OwiwiConfig {
+    /// Service name.
+    pub service_name: Option<String>,
+    /// Service namespace.
+    pub service_namespace: Option<String>,
+    /// Service version.
+    pub service_version: Option<String>,
+    /// Service instance ID.
+    pub service_instance_id: Option<String>,
+    /// Generates a service instance ID when none is set.
+    pub auto_instance_id: bool,
+    /// Resource attributes.
+    pub resource_attributes: BTreeMap<String, String>,
+    /// Attributes set on every span.
+    pub default_span_attributes: BTreeMap<String, String>,
+    /// Trace backend.
+    pub traces: Option<TraceExporter>,
+    /// Endpoint of the OTLP trace backend, overriding the configured one.
+    pub traces_endpoint: Option<Url>,
+    /// TLS files for gRPC exporters.
+    pub tls: TlsConfig,
+    /// Sampler name as defined by `OTEL_TRACES_SAMPLER`.
+    pub traces_sampler: Option<String>,
+    /// Root span sampling ratio of the parent-based sampler.
+    pub sample_parent_based: Option<f64>,
+    /// Sampler argument as defined by `OTEL_TRACES_SAMPLER_ARG`.
+    pub traces_sampler_arg: Option<String>,
+    /// Span attribute that keeps a span regardless of sampling when `true`.
+    pub always_sample_attribute: Option<String>,
+    /// Spans shorter than this are not exported.
+    pub min_span_duration: Option<std::time::Duration>,
+    /// Context propagators.
+    pub propagators: Vec<Propagator>,
+    /// Generates AWS X-Ray trace IDs and adds the X-Ray propagator.
+    #[cfg(feature = "xray")]
+    pub xray: bool,
+    /// Batch span processor tuning.
+    pub batch: BatchConfig,
+    /// Caps on the attributes, events and links per span.
+    pub span_limits: SpanLimits,
+    /// Retry policy for failed span exports.
+    pub retry: Option<RetryConfig>,
+    /// HTTP/2 keepalive of the gRPC OTLP connections.
+    pub keepalive: KeepaliveConfig,
+    /// Sentry forwarding.
+    #[cfg(feature = "sentry")]
+    pub sentry: Option<SentryConfig>,
+    /// Fails initialization when the trace collector is unreachable.
+    pub validate_endpoint: bool,
+    /// Exports spans to the console when the trace backend cannot be built.
+    #[cfg(feature = "console")]
+    pub fallback_to_console: bool,
+    /// Fails initialization when the trace backend cannot be built.
+    pub strict: Option<bool>,
+    /// Records panics and flushes telemetry before the panic hook runs.
+    pub install_panic_hook: bool,
+    /// Captures span traces for errors. Defaults to `true`.
+    pub error_layer: Option<bool>,
+    /// Logs the effective telemetry settings on startup.
+    pub log_config: bool,
+    /// Maximum time allowed to flush and shut down the providers.
+    pub shutdown_timeout: Option<SignedDuration>,
+    /// Trace filter directives.
+    pub tracing_directives: Vec<String>,
+    /// File of trace filter directives, one per line.
+    pub tracing_directives_file: Option<PathBuf>,
+    /// Targets shown at the current level, hiding every other target.
+    pub trace_include: Vec<String>,
+    /// Targets hidden from the formatted output.
+    pub trace_exclude: Vec<String>,
+    /// Fails initialization when `RUST_LOG` cannot be parsed.
+    pub strict_env_filter: Option<bool>,
+    /// Filter directives for the OpenTelemetry export layer.
+    pub export_directives: Vec<String>,
+    /// Event output format.
+    pub event_format: Option<EventFormat>,
+    /// Timestamps of the formatted output.
+    pub time: TimeFormat,
+    /// Span lifecycle events of the formatted output.
+    pub span_events: Vec<SpanEvent>,
+    /// Field name patterns redacted from the output, exported spans and logs,
+    /// and Sentry events.
+    pub redact_fields: Vec<String>,
+    /// Forces ANSI colors on or off.
+    pub ansi: Option<bool>,
+    /// Shows or hides the thread names.
+    pub thread_names: Option<bool>,
+    /// Shows or hides the thread IDs.
+    pub thread_ids: Option<bool>,
+    /// Shows or hides the source file.
+    pub file: Option<bool>,
+    /// Shows or hides the source line number.
+    pub line_number: Option<bool>,
+    /// Destination of the formatted output.
+    pub output: Option<OutputTarget>,
+    /// Maximum number of events exported per second.
+    pub max_events_per_second: Option<NonZeroU32>,
+    /// Maximum number of spans exported per second.
+    pub max_spans_per_second: Option<NonZeroU32>,
+    /// Disables all telemetry when `true`.
+    pub no_telemetry: bool,
+    /// Disables tracing entirely when `true`, including the formatted output.
+    pub disabled: bool,
+}
+
+impl OwiwiConfig {
+    /// Reads and parses a TOML configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid configuration.
+    #[cfg(feature = "toml")]
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path).map_err(|source| ErrorKind::ReadConfig {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|source| {
+            ErrorKind::ParseConfig {
+                path: path.to_path_buf(),
+                source,
+            }
+            .into()
+        })
+    }
+}
+
+impl TryFrom<OwiwiConfig> for Owiwi {
+    type Error = Error;
+
+    fn try_from(config: OwiwiConfig) -> Result<Self, Error> {
+        let mut owiwi = Self {
+            resource_attrs: config.resource_attributes.into_iter().collect(),
+            default_span_attributes: config.default_span_attributes.into_iter().collect(),
+            service_namespace: config.service_namespace,
+            service_version: config.service_version,
+            service_instance_id: config.service_instance_id,
+            auto_instance_id: config.auto_instance_id,
+            traces_endpoint: config.traces_endpoint,
+            tls: config.tls,
+            traces_sampler: config.traces_sampler,
+            sample_parent_based: config.sample_parent_based,
+            traces_sampler_arg: config.traces_sampler_arg,
+            always_sample_attribute: config.always_sample_attribute,
+            min_span_duration: config.min_span_duration,
+            propagators: config.propagators,
+            #[cfg(feature = "xray")]
+            xray: config.xray,
+            batch: config.batch,
+            span_limits: config.span_limits,
+            retry: config.retry,
+            keepalive: config.keepalive,
+            #[cfg(feature = "sentry")]
+            sentry: config.sentry,
+            validate_endpoint: config.validate_endpoint,
+            #[cfg(feature = "console")]
+            fallback_to_console: config.fallback_to_console,
+            install_panic_hook: config.install_panic_hook,
+            error_layer: config.error_layer.unwrap_or(true),
+            log_config: config.log_config,
+            shutdown_timeout: config.shutdown_timeout,
+            tracing_directives: parse_directives(&config.tracing_directives)?,
+            tracing_directives_file: config.tracing_directives_file,
+            trace_include: config.trace_include,
+            trace_exclude: config.trace_exclude,
+            export_directives: parse_directives(&config.export_directives)?,
+            time: config.time,
+            span_events: config.span_events,
+            redact_fields: config.redact_fields,
+            ansi: config.ansi,
+            thread_names: config.thread_names,
+            thread_ids: config.thread_ids,
+            file: config.file,
+            line_number: config.line_number,
+            max_events_per_second: config.max_events_per_second,
+            max_spans_per_second: config.max_spans_per_second,
+            no_telemetry: config.no_telemetry,
+            disabled: config.disabled,
+            ..Self::default()
+        };
+        if let Some(strict) = config.strict {
+            owiwi.strict = strict;
+        }
+        if let Some(strict) = config.strict_env_filter {
+            owiwi.strict_env_filter = strict;
+        }
+        if let Some(service_name) = config.service_name {
+            owiwi.service_name = service_name;
+        }
+        if let Some(traces) = config.traces {
+            owiwi.traces = traces;
+        }
+        if let Some(event_format) = config.event_format {
+            owiwi.event_format = event_format;
+        }
+        if let Some(output) = config.output {
+            owiwi.output = output;
+        }
+        Ok(owiwi)
+    }
+}
+
+/// Parses filter directives.
+fn parse_directives(directives: &[String]) -> Result<Vec<Directive>, Error> {
+    directives
+        .iter()
+        .map(|directive| directive.parse().map_err(Error::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    #[cfg(feature = "toml")]
+    use googletest::matchers::ok;
+
+    use super::*;
+
+    #[gtest]
+    fn config_converts_to_owiwi() {
+        let config = OwiwiConfig {
+            service_name: Some(String::from("checkout")),
+            resource_attributes: BTreeMap::from([(
+                String::from("deployment.environment"),
+                String::from("production"),
+            )]),
+            tracing_directives: vec![String::from("checkout=debug")],
+            ..OwiwiConfig::default()
+        };
+        let owiwi = Owiwi::try_from(config).expect("valid configuration");
+        expect_that!(owiwi.service_name, eq("checkout"));
+        expect_that!(owiwi.resource_attrs.len(), eq(1));
+        expect_that!(owiwi.tracing_directives.len(), eq(1));
+        expect_that!(owiwi.json_current_span, eq(true));
+        expect_that!(owiwi.error_layer, eq(true));
+    }
+
+    #[gtest]
+    fn dumped_config_redacts_secrets() {
+        let otlp = crate::OtlpConfig::builder()
+            .endpoint("http://collector:4317".parse().expect("valid URL"))
+            .timeout(std::time::Duration::from_secs(10))
+            .headers(vec![("x-api-key".to_owned(), "s3cr3t".to_owned())])
+            .bearer_token(secrecy::SecretString::from("t0k3n"))
+            .build();
+        let config = OwiwiConfig {
+            traces: Some(TraceExporter::Otlp(otlp)),
+            ..OwiwiConfig::default()
+        };
+        let dump = serde_json::to_value(&config).expect("serializable config");
+        expect_that!(dump["traces"]["type"], eq("otlp"));
+        expect_that!(dump["traces"]["bearer_token"], eq("[REDACTED]"));
+        expect_that!(
+            dump["traces"]["headers"],
+            eq(&serde_json::json!([["x-api-key", "[REDACTED]"]]))
+        );
+    }
+
+    #[gtest]
+    fn invalid_directive_is_an_error() {
+        let config = OwiwiConfig {
+            tracing_directives: vec![String::from("[=")],
+            ..OwiwiConfig::default()
+        };
+        expect_that!(Owiwi::try_from(config), err(anything()));
+    }
+
+    #[cfg(feature = "toml")]
+    #[gtest]
+    fn toml_file_is_parsed() {
+        let path = std::env::temp_dir().join(format!("owiwi-config-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+service_name = "checkout"
+tracing_directives = ["info"]
+
+[traces.otlp]
+endpoint = "http://collector:4317"
+timeout = { secs = 10, nanos = 0 }
+"#,
+        )
+        .expect("writable temp dir");
+        let config = OwiwiConfig::from_path(&path);
+        let _ = std::fs::remove_file(&path);
+        expect_that!(config, ok(anything()));
+    }
+
+    #[cfg(feature = "toml")]
+    #[gtest]
+    fn missing_file_is_an_error() {
+        let config = OwiwiConfig::from_path(Path::new("/nonexistent/owiwi.toml"));
+        expect_that!(config, err(anything()));
+    }
+}
@@ -1,17 +1,78 @@
 //! OpenTelemetry environment variables.
 
+use std::time::Duration;
+
+use jiff::SignedDuration;
+use secrecy::SecretString;
+
+/// Disables ANSI colors in terminal output when set to a non-empty value.
+/// See <https://no-color.org>.
+pub const NO_COLOR: &str = "NO_COLOR";
+/// Maximum time allowed for a batch span export, in milliseconds.
+pub const OTEL_BSP_EXPORT_TIMEOUT: &str = "OTEL_BSP_EXPORT_TIMEOUT";
+/// Maximum number of spans per batch export. Defaults to 512.
+pub const OTEL_BSP_MAX_EXPORT_BATCH_SIZE: &str = "OTEL_BSP_MAX_EXPORT_BATCH_SIZE";
+/// Maximum number of spans buffered for batch export. Defaults to 2048.
+pub const OTEL_BSP_MAX_QUEUE_SIZE: &str = "OTEL_BSP_MAX_QUEUE_SIZE";
+/// Delay between two batch span exports, in milliseconds. Defaults to 5000.
+pub const OTEL_BSP_SCHEDULE_DELAY: &str = "OTEL_BSP_SCHEDULE_DELAY";
+/// Maximum number of attributes per span event.
+pub const OTEL_EVENT_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_EVENT_ATTRIBUTE_COUNT_LIMIT";
+/// PEM CA certificate used to verify the OTLP collector.
+pub const OTEL_EXPORTER_OTLP_CERTIFICATE: &str = "OTEL_EXPORTER_OTLP_CERTIFICATE";
+/// PEM client certificate for OTLP mutual TLS.
+pub const OTEL_EXPORTER_OTLP_CLIENT_CERTIFICATE: &str = "OTEL_EXPORTER_OTLP_CLIENT_CERTIFICATE";
+/// PEM client private key for OTLP mutual TLS.
+pub const OTEL_EXPORTER_OTLP_CLIENT_KEY: &str = "OTEL_EXPORTER_OTLP_CLIENT_KEY";
+/// OTLP exporter payload compression: `gzip` or `none`. Defaults to `none`.
+pub const OTEL_EXPORTER_OTLP_COMPRESSION: &str = "OTEL_EXPORTER_OTLP_COMPRESSION";
 /// OTLP exporter endpoint. Defaults to `http://localhost:4317`.
 pub const OTEL_EXPORTER_OTLP_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
 /// Additional headers for OTLP exporter requests.
 pub const OTEL_EXPORTER_OTLP_HEADERS: &str = "OTEL_EXPORTER_OTLP_HEADERS";
-/// OTLP exporter timeout.
+/// Forces plaintext (`true`) or TLS (`false`) for OTLP exporters, whatever the
+/// endpoint scheme.
+pub const OTEL_EXPORTER_OTLP_INSECURE: &str = "OTEL_EXPORTER_OTLP_INSECURE";
+/// OTLP metrics exporter endpoint. Takes precedence over `OTEL_EXPORTER_OTLP_ENDPOINT`.
+pub const OTEL_EXPORTER_OTLP_METRICS_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT";
+/// OTLP metrics exporter transport protocol. Takes precedence over
+/// `OTEL_EXPORTER_OTLP_PROTOCOL`.
+pub const OTEL_EXPORTER_OTLP_METRICS_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_METRICS_PROTOCOL";
+/// Aggregation temporality of OTLP metrics: `cumulative`, `delta` or
+/// `lowmemory`. Defaults to `cumulative`.
+pub const OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE: &str =
+    "OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE";
+/// OTLP exporter transport protocol: `grpc`, `http/protobuf` or `http/json`.
+/// Defaults to `grpc`.
+pub const OTEL_EXPORTER_OTLP_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_PROTOCOL";
+/// OTLP exporter timeout, in milliseconds.
 pub const OTEL_EXPORTER_OTLP_TIMEOUT: &str = "OTEL_EXPORTER_OTLP_TIMEOUT";
+/// OTLP traces exporter endpoint. Takes precedence over `OTEL_EXPORTER_OTLP_ENDPOINT`.
+pub const OTEL_EXPORTER_OTLP_TRACES_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+/// Maximum number of attributes per span link.
+pub const OTEL_LINK_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_LINK_ATTRIBUTE_COUNT_LIMIT";
+/// Level of the OpenTelemetry SDK's internal logs.
+pub const OTEL_LOG_LEVEL: &str = "OTEL_LOG_LEVEL";
+/// Comma-separated list of context propagators. Defaults to `tracecontext,baggage`.
+pub const OTEL_PROPAGATORS: &str = "OTEL_PROPAGATORS";
 /// Additional resource attributes as comma-separated `key=value` pairs.
 pub const OTEL_RESOURCE_ATTRIBUTES: &str = "OTEL_RESOURCE_ATTRIBUTES";
 /// Disables all telemetry when set to `"true"`. Defaults to `"false"`.
 pub const OTEL_SDK_DISABLED: &str = "OTEL_SDK_DISABLED";
 /// Service name for telemetry identification.
 pub const OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+/// Service namespace, recorded as `service.namespace`. Not defined by the
+/// spec, which sets it through `OTEL_RESOURCE_ATTRIBUTES`.
+pub const OTEL_SERVICE_NAMESPACE: &str = "OTEL_SERVICE_NAMESPACE";
+/// Service version, recorded as `service.version`. Not defined by the spec,
+/// which sets it through `OTEL_RESOURCE_ATTRIBUTES`.
+pub const OTEL_SERVICE_VERSION: &str = "OTEL_SERVICE_VERSION";
+/// Maximum number of attributes per span. Defaults to 128.
+pub const OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT";
+/// Maximum number of events per span. Defaults to 128.
+pub const OTEL_SPAN_EVENT_COUNT_LIMIT: &str = "OTEL_SPAN_EVENT_COUNT_LIMIT";
+/// Maximum number of links per span. Defaults to 128.
+pub const OTEL_SPAN_LINK_COUNT_LIMIT: &str = "OTEL_SPAN_LINK_COUNT_LIMIT";
 /// Sampler type
 pub const OTEL_TRACES_SAMPLER: &str = "OTEL_TRACES_SAMPLER";
 /// Sampler argument. For `traceidratio`, this is the ratio.
@@ -20,22 +81,67 @@ pub const OTEL_TRACES_SAMPLER_ARG: &str = "OTEL_TRACES_SAMPLER_ARG";
 /// OpenTelemetry collector, independent of terminal output `RUST_LOG`
 /// Accepts the same syntax as `RUST_LOG` and default to `info`
 pub const OWIWI_EXPORT_LOG: &str = "OWIWI_EXPORT_LOG";
+/// Disables all tracing output and export.
+pub const OWIWI_DISABLED: &str = "OWIWI_DISABLED";
 /// Metrics export interval
 pub const OWIWI_METRICS_INTERVAL: &str = "OWIWI_METRICS_INTERVAL";
+/// Reports process memory, CPU time and thread count with the metrics.
+#[cfg(feature = "process-metrics")]
+pub const OWIWI_METRICS_PROCESS: &str = "OWIWI_METRICS_PROCESS";
+/// Maximum time allowed to flush and shut down the providers.
+pub const OWIWI_SHUTDOWN_TIMEOUT: &str = "OWIWI_SHUTDOWN_TIMEOUT";
+/// Event output format: `compact`, `full`, `pretty`, `json` or `logfmt`.
+pub const OWIWI_TRACE_FORMAT: &str = "OWIWI_TRACE_FORMAT";
 
 /// Parses a comma-separated list of `key=value` entries.
 ///
 /// Returns an error if any entry is missing `=`.
 pub(super) fn parse_key_values(header: &str) -> Result<Vec<(String, String)>, ParseKeyValueError> {
-    header
-        .split(',')
-        .map(|entry| {
-            let (key, val) = entry.split_once('=').ok_or_else(|| ParseKeyValueError {
-                entry: entry.to_owned(),
-            })?;
-            Ok((key.trim().to_owned(), val.trim().to_owned()))
+    header.split(',').map(parse_key_value).collect()
+}
+
+/// Parses a single `key=value` entry.
+///
+/// Returns an error if the entry is missing `=`.
+pub(super) fn parse_key_value(entry: &str) -> Result<(String, String), ParseKeyValueError> {
+    let (key, val) = entry.split_once('=').ok_or_else(|| ParseKeyValueError {
+        entry: entry.to_owned(),
+    })?;
+    Ok((key.trim().to_owned(), val.trim().to_owned()))
+}
+
+/// Parses a single `key=value` entry whose value is a secret.
+///
+/// Returns an error if the entry is missing `=`.
+pub(super) fn parse_secret_key_value(
+    entry: &str,
+) -> Result<(String, SecretString), ParseKeyValueError> {
+    parse_key_value(entry).map(|(key, val)| (key, SecretString::from(val)))
+}
+
+/// Parses an exporter timeout, either integer milliseconds as defined by the
+/// spec or a duration such as `10s`.
+///
+/// Returns an error for negative or unparsable values.
+pub(super) fn parse_timeout(value: &str) -> Result<Duration, ParseTimeoutError> {
+    let value = value.trim();
+    if let Ok(millis) = value.parse::<u64>() {
+        return Ok(Duration::from_millis(millis));
+    }
+    value
+        .parse::<SignedDuration>()
+        .ok()
+        .and_then(|duration| Duration::try_from(duration).ok())
+        .ok_or_else(|| ParseTimeoutError {
+            value: value.to_owned(),
         })
-        .collect()
+}
+
+/// Error parsing a timeout
+#[derive(Debug, thiserror::Error)]
+#[error("invalid timeout: expected milliseconds or a duration such as `10s`, got `{value}`")]
+pub(super) struct ParseTimeoutError {
+    value: String,
 }
 
 /// Error parsing a key=value list
@@ -47,12 +153,15 @@ pub(super) struct ParseKeyValueError {
 
 #[cfg(test)]
 mod tests {
+    use googletest::assert_that;
     use googletest::expect_that;
     use googletest::gtest;
     use googletest::matchers::anything;
     use googletest::matchers::elements_are;
     use googletest::matchers::eq;
     use googletest::matchers::err;
+    use googletest::matchers::ok;
+    use rstest::rstest;
 
     use super::*;
 
@@ -80,6 +189,26 @@ mod tests {
         expect_that!(value, err(anything()));
     }
 
+    #[rstest]
+    #[case("10000", Duration::from_secs(10))]
+    #[case(" 250 ", Duration::from_millis(250))]
+    #[case("0", Duration::ZERO)]
+    #[case("10s", Duration::from_secs(10))]
+    #[case("500ms", Duration::from_millis(500))]
+    #[case("PT1M", Duration::from_secs(60))]
+    fn parse_timeout_accepts_millis_and_durations(#[case] value: &str, #[case] expected: Duration) {
+        assert_that!(parse_timeout(value), ok(eq(&expected)));
+    }
+
+    #[rstest]
+    #[case("-5")]
+    #[case("-5s")]
+    #[case("soon")]
+    #[case("")]
+    fn parse_timeout_rejects_invalid_values(#[case] value: &str) {
+        assert_that!(parse_timeout(value), err(anything()));
+    }
+
     #[gtest]
     fn parse_key_values_empty_string() {
         let value = parse_key_values("");
@@ -11,6 +11,20 @@ pub struct Error {
     kind: ErrorKind,
 }
 
+impl Error {
+    /// Returns `true` if a global subscriber was already installed.
+    #[must_use]
+    pub const fn is_already_initialized(&self) -> bool {
+        matches!(self.kind, ErrorKind::AlreadyInitialized)
+    }
+
+    /// Returns `true` if the endpoint check found the trace collector unreachable.
+    #[must_use]
+    pub const fn is_exporter_unreachable(&self) -> bool {
+        matches!(self.kind, ErrorKind::ExporterUnreachable { .. })
+    }
+}
+
 impl<E: Into<ErrorKind>> From<E> for Error {
     fn from(err: E) -> Self {
         Self { kind: err.into() }
@@ -23,9 +37,9 @@ pub(crate) enum ErrorKind {
     /// Failed to build exporter.
     #[error(transparent)]
     BuildTraceExporter(#[from] opentelemetry_otlp::ExporterBuildError),
-    /// Subscriber initialization failed.
-    #[error(transparent)]
-    InitSubscriber(#[from] tracing_subscriber::util::TryInitError),
+    /// A global subscriber is already installed.
+    #[error("a global tracing subscriber is already installed")]
+    AlreadyInitialized,
     /// Invalid trace directive.
     #[error("parsing RUST_LOG directives: {source}")]
     ParseDirective {
@@ -35,20 +49,115 @@ pub(crate) enum ErrorKind {
     /// Invalid filter.
     #[error(transparent)]
     ParseFilter(#[from] tracing_subscriber::filter::ParseError),
-    /// Invalid URL.
-    #[error(transparent)]
-    ParseUrl(#[from] url::ParseError),
-    #[error("unexpected error parsing env filter: {0}")]
-    UnexpectedFilter(String),
+    /// Invalid exporter endpoint URL.
+    #[error("invalid {context} endpoint: {source}")]
+    InvalidEndpoint {
+        context: &'static str,
+        source: url::ParseError,
+    },
     /// Failed to shut down a provider.
     #[error(transparent)]
     Shutdown(#[from] opentelemetry_sdk::error::OTelSdkError),
+    /// Failed to flush a provider.
+    #[error("flushing telemetry: {0}")]
+    Flush(opentelemetry_sdk::error::OTelSdkError),
+    /// The trace collector did not accept a TCP connection.
+    #[error("trace exporter endpoint `{endpoint}` is unreachable: {source}")]
+    ExporterUnreachable {
+        endpoint: url::Url,
+        source: std::io::Error,
+    },
+    /// The exporter endpoint scheme is not an OTLP transport.
+    #[error(
+        "unsupported scheme `{scheme}` in endpoint `{endpoint}`: expected http, https, grpc, grpcs or unix"
+    )]
+    UnsupportedEndpointScheme { endpoint: url::Url, scheme: String },
+    /// The backend only accepts TLS connections.
+    #[error("{backend} requires TLS, but endpoint `{endpoint}` is plaintext: use https")]
+    PlaintextEndpoint {
+        backend: &'static str,
+        endpoint: url::Url,
+    },
     /// Invalid span exporter configuration.
     #[error("invalid span exporter configuration: {reason}")]
     ExporterConfig { reason: String },
+    /// Sampling ratio outside of `0.0..=1.0`.
+    #[error("invalid sampler ratio {ratio}: expected a value between 0.0 and 1.0")]
+    InvalidSamplerRatio { ratio: f64 },
+    /// Retry initial backoff exceeds the maximum backoff.
+    #[error(
+        "invalid retry configuration: initial backoff {initial_backoff:?} exceeds maximum backoff {max_backoff:?}"
+    )]
+    InvalidRetryConfig {
+        initial_backoff: std::time::Duration,
+        max_backoff: std::time::Duration,
+    },
+    /// Histogram bucket boundaries are not finite and strictly increasing.
+    #[cfg(feature = "metrics")]
+    #[error(
+        "invalid histogram buckets {boundaries:?}: expected finite, strictly increasing values"
+    )]
+    InvalidHistogramBuckets { boundaries: Vec<f64> },
     /// Failed to replace active filter
     #[error(transparent)]
     FilterReload(#[from] tracing_subscriber::reload::Error),
+    /// Invalid Sentry DSN. The DSN itself is not included.
+    #[cfg(feature = "sentry")]
+    #[error("invalid Sentry DSN: {source}")]
+    InvalidSentryDsn {
+        source: sentry::types::ParseDsnError,
+    },
+    /// Unknown event format name.
+    #[error("unsupported event format: {0} (expected compact, full, pretty, json or logfmt)")]
+    UnsupportedEventFormat(String),
+    /// Unknown backend name or URL scheme in a trace backend shorthand.
+    #[error("unsupported trace collector `{0}`")]
+    UnsupportedTracesCollector(String),
+    /// Invalid timestamp format.
+    #[error("invalid time format: {reason}")]
+    InvalidTimeFormat { reason: String },
+    /// Failed to open the trace output file.
+    #[error("opening trace output `{}`: {source}", path.display())]
+    OpenOutput {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    /// Failed to read a TLS PEM file.
+    #[error("reading TLS file `{}`: {source}", path.display())]
+    ReadTlsFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    /// Failed to read a directives file.
+    #[error("reading directives `{}`: {source}", path.display())]
+    ReadDirectives {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    /// Invalid directive in a directives file.
+    #[error("parsing directives `{}` at line {line}: {source}", path.display())]
+    ParseDirectivesFile {
+        path: std::path::PathBuf,
+        line: usize,
+        source: tracing_subscriber::filter::ParseError,
+    },
+    /// Failed to read a configuration file.
+    #[cfg(feature = "toml")]
+    #[error("reading configuration `{}`: {source}", path.display())]
+    ReadConfig {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    /// Invalid configuration file.
+    #[cfg(feature = "toml")]
+    #[error("parsing configuration `{}`: {source}", path.display())]
+    ParseConfig {
+        path: std::path::PathBuf,
+        source: toml::de::Error,
+    },
+    /// Telemetry was initialized without a reloadable filter.
+    #[error("the tracing filter is not reloadable")]
+    FilterUnavailable,
     #[error("no tokio runtime found. owiwi requires a running tokio runtime for batch export")]
     NoTokioRuntime,
 }
@@ -1,20 +1,37 @@
 //! Trace output formatting styles.
 
+mod logfmt;
+mod span_events;
+mod time;
+
+use std::ffi::OsStr;
 use std::fmt;
 use std::io::IsTerminal;
 use std::io::{self};
 use std::str::FromStr;
 
+pub(crate) use logfmt::Logfmt;
+pub(crate) use logfmt::LogfmtFields;
+#[doc(inline)]
+pub use span_events::SpanEvent;
+pub(crate) use span_events::fmt_span;
+#[doc(inline)]
+pub use time::TimeFormat;
 use tracing_subscriber::fmt::format::Compact;
 use tracing_subscriber::fmt::format::Format;
 use tracing_subscriber::fmt::format::Full;
+use tracing_subscriber::fmt::format::Json;
 use tracing_subscriber::fmt::format::Pretty;
 use tracing_subscriber::fmt::time::SystemTime;
 
+use crate::env_vars;
+use crate::error::Error;
+use crate::error::ErrorKind;
+
 /// Trace event output format.
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug, Default)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum EventFormat {
     /// Compact, single-line output.
@@ -24,6 +41,10 @@ pub enum EventFormat {
     Full,
     /// Multi-line, indented output.
     Pretty,
+    /// Newline-delimited JSON, one object per event.
+    Json,
+    /// Single-line `key=value` pairs, with span fields as `span.field=value`.
+    Logfmt,
 }
 
 impl EventFormat {
@@ -41,9 +62,11 @@ impl EventFormat {
     }
 
     /// Returns a full verbose formatter with timestamps.
+    ///
+    /// ANSI colors are enabled when stderr is a terminal and `NO_COLOR` is unset.
     #[must_use]
     pub fn full(&self) -> Format<Full, SystemTime> {
-        Format::default().with_ansi(io::stderr().is_terminal())
+        Format::default().with_ansi(resolve_ansi(None, io::stderr().is_terminal()))
     }
 
     /// Returns a multi-line, indented formatter for local development.
@@ -51,16 +74,27 @@ impl EventFormat {
     pub fn pretty(&self) -> Format<Pretty, SystemTime> {
         self.full().pretty()
     }
+
+    /// Returns a machine-readable formatter emitting one JSON object per event.
+    ///
+    /// Event fields are nested under `fields` and the current span is included.
+    #[must_use]
+    pub fn json(&self) -> Format<Json, SystemTime> {
+        Format::default().json().with_current_span(true)
+    }
 }
 
 impl EventFormat {
-    /// String literals for each variant.
-    const LITERALS: &[&str] = &["compact", "full", "pretty"];
-
     /// Returns the string representation of this format.
     #[must_use]
     pub const fn as_str(&self) -> &str {
-        Self::LITERALS[*self as usize]
+        match self {
+            Self::Compact => "compact",
+            Self::Full => "full",
+            Self::Pretty => "pretty",
+            Self::Json => "json",
+            Self::Logfmt => "logfmt",
+        }
     }
 }
 
@@ -71,21 +105,55 @@ impl fmt::Display for EventFormat {
 }
 
 impl FromStr for EventFormat {
-    type Err = ParseEventFormatError;
+    type Err = Error;
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         let trace_fmt = match value {
             "compact" => Self::Compact,
             "full" => Self::Full,
             "pretty" => Self::Pretty,
-            other => return Err(ParseEventFormatError(other.to_string())),
+            "json" => Self::Json,
+            "logfmt" => Self::Logfmt,
+            other => return Err(ErrorKind::UnsupportedEventFormat(other.to_owned()).into()),
         };
         Ok(trace_fmt)
     }
 }
 
-#[derive(Debug, thiserror::Error)]
-#[error("invalid event format: {0} (expected compact, full or pretty)")]
-pub struct ParseEventFormatError(String);
+impl TryFrom<&str> for EventFormat {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl EventFormat {
+    /// Returns the format named by `OWIWI_TRACE_FORMAT`, or the default one
+    /// when it's unset or invalid.
+    pub(crate) fn from_env() -> Self {
+        format_from_env(std::env::var(env_vars::OWIWI_TRACE_FORMAT).ok().as_deref())
+    }
+}
+
+fn format_from_env(value: Option<&str>) -> EventFormat {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Resolves whether ANSI colors are used.
+///
+/// An explicit choice wins, then a non-empty `NO_COLOR` disables colors,
+/// otherwise colors follow terminal detection of the output.
+pub(crate) fn resolve_ansi(explicit: Option<bool>, is_terminal: bool) -> bool {
+    explicit.unwrap_or_else(|| {
+        ansi_from_env(std::env::var_os(env_vars::NO_COLOR).as_deref(), is_terminal)
+    })
+}
+
+/// Applies the `NO_COLOR` convention to terminal detection.
+fn ansi_from_env(no_color: Option<&OsStr>, is_terminal: bool) -> bool {
+    no_color.is_none_or(OsStr::is_empty) && is_terminal
+}
 
 #[cfg(test)]
 mod tests {
@@ -100,18 +168,69 @@ mod tests {
     use rstest::rstest;
 
     use super::EventFormat;
+    use super::ansi_from_env;
+    use super::format_from_env;
+    use super::resolve_ansi;
 
     #[rstest]
     #[case(EventFormat::Compact, "compact")]
     #[case(EventFormat::Full, "full")]
     #[case(EventFormat::Pretty, "pretty")]
+    #[case(EventFormat::Json, "json")]
+    #[case(EventFormat::Logfmt, "logfmt")]
     fn display_correct_trace_format(#[case] event_format: EventFormat, #[case] display: &str) {
         assert_that!(event_format.to_string(), eq(display));
     }
 
+    #[rstest]
+    #[case(None, true, true)]
+    #[case(None, false, false)]
+    #[case(Some("1"), true, false)]
+    #[case(Some(""), true, true)]
+    fn ansi_follows_no_color_and_terminal(
+        #[case] no_color: Option<&str>,
+        #[case] is_terminal: bool,
+        #[case] expected: bool,
+    ) {
+        let no_color = no_color.map(std::ffi::OsStr::new);
+        assert_that!(ansi_from_env(no_color, is_terminal), eq(expected));
+    }
+
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    fn explicit_ansi_overrides_detection(#[case] ansi: bool) {
+        assert_that!(resolve_ansi(Some(ansi), !ansi), eq(ansi));
+    }
+
+    #[rstest]
+    #[case(None, "full")]
+    #[case(Some("json"), "json")]
+    #[case(Some("logfmt"), "logfmt")]
+    #[case(Some("yaml"), "full")]
+    fn env_format_falls_back_to_the_default(#[case] value: Option<&str>, #[case] expected: &str) {
+        assert_that!(format_from_env(value).as_str(), eq(expected));
+    }
+
     proptest! {
         #[test]
-        fn parse_valid_event_format_successfully(fmt in "compact|full|pretty") {
+        fn event_format_round_trips(
+            event_format in proptest::sample::select(vec![
+                EventFormat::Compact,
+                EventFormat::Full,
+                EventFormat::Pretty,
+                EventFormat::Json,
+                EventFormat::Logfmt,
+            ])
+        ) {
+            let parsed: EventFormat = event_format.to_string().parse().expect("displayed format");
+            assert_that!(parsed.as_str(), eq(event_format.as_str()));
+            let converted = EventFormat::try_from(event_format.as_str()).expect("displayed format");
+            assert_that!(converted.as_str(), eq(event_format.as_str()));
+        }
+
+        #[test]
+        fn parse_valid_event_format_successfully(fmt in "compact|full|pretty|json|logfmt") {
             let result: Result<EventFormat,_> = fmt.parse();
             assert_that!(result, ok(anything()));
         }
@@ -120,7 +239,7 @@ mod tests {
         fn parsing_invalid_event_format_fails(
             fmt in "[a-zA-Z]*"
             .prop_filter("Values must not be in enumerated values",
-                |fmt| !["compact", "full", "pretty"].contains(&fmt.as_str()))) {
+                |fmt| !["compact", "full", "pretty", "json", "logfmt"].contains(&fmt.as_str()))) {
                 let result: Result<EventFormat, _> = fmt.parse();
                 assert_that!(result, err(anything()));
         }
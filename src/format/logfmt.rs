@@ -0,0 +1,289 @@
+//! `key=value` logfmt output.
+
+use std::fmt;
+
+use tracing::Event;
+use tracing::Level;
+use tracing::Subscriber;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing_subscriber::field::MakeVisitor;
+use tracing_subscriber::field::VisitFmt;
+use tracing_subscriber::field::VisitOutput;
+use tracing_subscriber::fmt::FmtContext;
+use tracing_subscriber::fmt::FormatEvent;
+use tracing_subscriber::fmt::FormatFields;
+use tracing_subscriber::fmt::FormattedFields;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::registry::LookupSpan;
+
+use super::TimeFormat;
+use crate::redact::FieldRedaction;
+use crate::redact::RedactFields;
+
+/// Event formatter writing one logfmt line per event.
+///
+/// The line holds `ts`, `level`, `target`, the current `span` name and `msg`,
+/// followed by the event fields and the fields of every span in scope,
+/// outermost first, as `span.field=value`.
+pub(crate) struct Logfmt {
+    time: Option<TimeFormat>,
+    fields: RedactFields<LogfmtFields>,
+}
+
+impl Logfmt {
+    /// Creates a formatter, without timestamps when `time` is `None`.
+    pub(crate) const fn new(time: Option<TimeFormat>, redaction: FieldRedaction) -> Self {
+        Self {
+            time,
+            fields: RedactFields::new(LogfmtFields::EVENT, redaction),
+        }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for Logfmt
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        if let Some(time) = &self.time {
+            let mut timestamp = String::new();
+            time.format_time(&mut Writer::new(&mut timestamp))?;
+            writer.write_str("ts=")?;
+            write_value(&mut writer, &timestamp)?;
+            writer.write_char(' ')?;
+        }
+        let metadata = event.metadata();
+        write!(writer, "level={} target=", level(metadata.level()))?;
+        write_value(&mut writer, metadata.target())?;
+
+        let span = event
+            .parent()
+            .and_then(|id| ctx.span(id))
+            .or_else(|| ctx.lookup_current());
+        if let Some(span) = &span {
+            writer.write_str(" span=")?;
+            write_value(&mut writer, span.name())?;
+        }
+
+        let mut visitor = self.fields.make_visitor(writer.by_ref());
+        event.record(&mut visitor);
+        visitor.finish()?;
+
+        for span in span.iter().flat_map(|span| span.scope().from_root()) {
+            let extensions = span.extensions();
+            if let Some(fields) = extensions.get::<FormattedFields<N>>()
+                && !fields.is_empty()
+            {
+                write!(writer, " {fields}")?;
+            }
+        }
+        writeln!(writer)
+    }
+}
+
+/// Lowercase level name.
+const fn level(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "error",
+        Level::WARN => "warn",
+        Level::INFO => "info",
+        Level::DEBUG => "debug",
+        Level::TRACE => "trace",
+    }
+}
+
+/// Field formatter writing `key=value` pairs.
+#[derive(Debug)]
+pub(crate) struct LogfmtFields {
+    /// Prepended to every key.
+    prefix: &'static str,
+    /// Whether a space precedes the first pair.
+    leading_space: bool,
+}
+
+impl LogfmtFields {
+    /// Span fields, stored as `span.field=value`.
+    pub(crate) const SPAN: Self = Self {
+        prefix: "span.",
+        leading_space: false,
+    };
+    /// Event fields, appended to the event line.
+    const EVENT: Self = Self {
+        prefix: "",
+        leading_space: true,
+    };
+}
+
+impl<'writer> MakeVisitor<Writer<'writer>> for LogfmtFields {
+    type Visitor = LogfmtVisitor<'writer>;
+
+    fn make_visitor(&self, writer: Writer<'writer>) -> Self::Visitor {
+        LogfmtVisitor {
+            writer,
+            prefix: self.prefix,
+            separate: self.leading_space,
+            result: Ok(()),
+        }
+    }
+}
+
+/// Visitor writing each field as a `key=value` pair.
+pub(crate) struct LogfmtVisitor<'writer> {
+    writer: Writer<'writer>,
+    prefix: &'static str,
+    separate: bool,
+    result: fmt::Result,
+}
+
+impl LogfmtVisitor<'_> {
+    fn write_pair(&mut self, field: &Field, value: &str) {
+        if self.result.is_err() {
+            return;
+        }
+        let key = match field.name() {
+            "message" if self.prefix.is_empty() => "msg",
+            name => name,
+        };
+        let separator = if self.separate { " " } else { "" };
+        self.separate = true;
+        self.result = write!(self.writer, "{separator}{}{key}=", self.prefix)
+            .and_then(|()| write_value(&mut self.writer, value));
+    }
+}
+
+impl Visit for LogfmtVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.write_pair(field, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.write_pair(field, &format!("{value:?}"));
+    }
+}
+
+impl VisitOutput<fmt::Result> for LogfmtVisitor<'_> {
+    fn finish(self) -> fmt::Result {
+        self.result
+    }
+}
+
+impl VisitFmt for LogfmtVisitor<'_> {
+    fn writer(&mut self) -> &mut dyn fmt::Write {
+        &mut self.writer
+    }
+}
+
+/// Writes `value`, quoted and escaped when it is empty or holds spaces,
+/// quotes, `=` or control characters.
+fn write_value(writer: &mut impl fmt::Write, value: &str) -> fmt::Result {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control());
+    if !needs_quotes {
+        return writer.write_str(value);
+    }
+    writer.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            '\n' => writer.write_str("\\n")?,
+            '\r' => writer.write_str("\\r")?,
+            '\t' => writer.write_str("\\t")?,
+            c => writer.write_char(c)?,
+        }
+    }
+    writer.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::eq;
+    use rstest::rstest;
+    use tracing_subscriber::fmt::MakeWriter;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    use super::*;
+
+    #[rstest]
+    #[case("plain", "plain")]
+    #[case("", "\"\"")]
+    #[case("two words", "\"two words\"")]
+    #[case("a=b", "\"a=b\"")]
+    #[case("say \"hi\"", "\"say \\\"hi\\\"\"")]
+    #[case("line\nbreak", "\"line\\nbreak\"")]
+    fn values_are_quoted_when_needed(#[case] value: &str, #[case] expected: &str) {
+        let mut out = String::new();
+        write_value(&mut out, value).expect("written");
+        assert_that!(out, eq(expected));
+    }
+
+    /// Writer shared with the test.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().expect("not poisoned").clone()).expect("utf-8")
+        }
+    }
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().expect("not poisoned").write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[gtest]
+    fn events_are_written_as_logfmt() {
+        let buffer = Buffer::default();
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(buffer.clone())
+            .with_ansi(false)
+            .fmt_fields(RedactFields::new(
+                LogfmtFields::SPAN,
+                FieldRedaction::new(&["token"]),
+            ))
+            .event_format(Logfmt::new(None, FieldRedaction::new(&["token"])));
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("request", user = "ada", token = "s3cr3t").entered();
+            tracing::info!(target: "app", status = 200, token = "s3cr3t", "handled request");
+        });
+        expect_that!(
+            buffer.contents(),
+            eq(
+                "level=info target=app span=request msg=\"handled request\" status=200 \
+                token=[REDACTED] span.user=ada span.token=[REDACTED]\n"
+            )
+        );
+    }
+}
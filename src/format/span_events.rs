@@ -0,0 +1,127 @@
+//! Span lifecycle events.
+
+use std::fmt;
+use std::str::FromStr;
+
+use tracing_subscriber::fmt::format::FmtSpan;
+
+/// Span lifecycle event printed by the formatting layer.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SpanEvent {
+    /// Span creation.
+    New,
+    /// Each time the span is entered.
+    Enter,
+    /// Each time the span is exited.
+    Exit,
+    /// Span closing, with its busy and idle times.
+    Close,
+    /// Both [`Self::Enter`] and [`Self::Exit`].
+    Active,
+    /// Every lifecycle event.
+    Full,
+}
+
+impl SpanEvent {
+    /// Returns the name of this event.
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::New => "new",
+            Self::Enter => "enter",
+            Self::Exit => "exit",
+            Self::Close => "close",
+            Self::Active => "active",
+            Self::Full => "full",
+        }
+    }
+
+    /// Returns the matching [`FmtSpan`] flags.
+    const fn fmt_span(self) -> FmtSpan {
+        match self {
+            Self::New => FmtSpan::NEW,
+            Self::Enter => FmtSpan::ENTER,
+            Self::Exit => FmtSpan::EXIT,
+            Self::Close => FmtSpan::CLOSE,
+            Self::Active => FmtSpan::ACTIVE,
+            Self::Full => FmtSpan::FULL,
+        }
+    }
+}
+
+impl fmt::Display for SpanEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for SpanEvent {
+    type Err = ParseSpanEventError;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let event = match value {
+            "new" => Self::New,
+            "enter" => Self::Enter,
+            "exit" => Self::Exit,
+            "close" => Self::Close,
+            "active" => Self::Active,
+            "full" => Self::Full,
+            other => return Err(ParseSpanEventError(other.to_owned())),
+        };
+        Ok(event)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid span event: {0} (expected new, enter, exit, close, active or full)")]
+pub struct ParseSpanEventError(String);
+
+/// Combines span events into [`FmtSpan`] flags, [`FmtSpan::NONE`] when empty.
+pub(crate) fn fmt_span(events: &[SpanEvent]) -> FmtSpan {
+    events
+        .iter()
+        .fold(FmtSpan::NONE, |flags, event| flags | event.fmt_span())
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("new")]
+    #[case("enter")]
+    #[case("exit")]
+    #[case("close")]
+    #[case("active")]
+    #[case("full")]
+    fn names_round_trip(#[case] name: &str) {
+        let event: SpanEvent = name.parse().expect("known span event");
+        assert_that!(event.to_string(), eq(name));
+    }
+
+    #[gtest]
+    fn unknown_name_is_rejected() {
+        expect_that!("drop".parse::<SpanEvent>(), err(anything()));
+    }
+
+    #[gtest]
+    fn no_events_is_none() {
+        expect_that!(fmt_span(&[]), eq(&FmtSpan::NONE));
+    }
+
+    #[gtest]
+    fn events_are_combined() {
+        let flags = fmt_span(&[SpanEvent::Enter, SpanEvent::Close]);
+        expect_that!(flags, eq(&(FmtSpan::ENTER | FmtSpan::CLOSE)));
+    }
+}
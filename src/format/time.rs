@@ -0,0 +1,202 @@
+//! Timestamp formatting.
+
+use std::fmt;
+
+use bon::Builder;
+#[cfg(feature = "clap")]
+use clap::Args;
+use jiff::Timestamp;
+use jiff::tz::TimeZone;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::FormatTime;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+
+/// Default number of fractional second digits, matching
+/// [`SystemTime`](tracing_subscriber::fmt::time::SystemTime).
+const DEFAULT_PRECISION: u8 = 6;
+
+/// Timestamps printed by the formatting layer.
+///
+/// Defaults to RFC 3339 in UTC with microseconds, shown by every format except
+/// [`EventFormat::Compact`](crate::EventFormat::Compact).
+#[must_use]
+#[derive(Clone, Debug, Default, Builder)]
+#[cfg_attr(feature = "clap", derive(Args))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TimeFormat {
+    /// Shows or hides timestamps, overriding the format default.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-time",
+            long,
+            help = "Show or hide timestamps (defaults to hidden for compact)"
+        )
+    )]
+    pub enabled: Option<bool>,
+
+    /// `strftime`-style pattern, replacing RFC 3339.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-time-format",
+            long,
+            help = "strftime-style timestamp pattern (e.g. %H:%M:%S%.3f)"
+        )
+    )]
+    #[builder(into)]
+    pub pattern: Option<String>,
+
+    /// Fractional second digits of RFC 3339 timestamps, up to 9. Defaults to 6.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-time-precision",
+            long,
+            help = "Fractional second digits of RFC 3339 timestamps (0-9)"
+        )
+    )]
+    pub precision: Option<u8>,
+
+    /// Uses the local time zone instead of UTC.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-time-local",
+            long,
+            help = "Print timestamps in the local time zone"
+        )
+    )]
+    #[builder(default)]
+    pub local: bool,
+}
+
+impl TimeFormat {
+    /// Returns whether timestamps are printed, given the format default.
+    pub(crate) fn is_enabled(&self, default: bool) -> bool {
+        self.enabled.unwrap_or(default)
+    }
+
+    /// Checks the precision range and the pattern.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.precision.is_some_and(|precision| precision > 9) {
+            return Err(ErrorKind::InvalidTimeFormat {
+                reason: String::from("precision must be between 0 and 9"),
+            }
+            .into());
+        }
+        if let Some(pattern) = &self.pattern {
+            let now = Timestamp::now().to_zoned(TimeZone::UTC);
+            jiff::fmt::strtime::format(pattern, &now).map_err(|err| {
+                ErrorKind::InvalidTimeFormat {
+                    reason: format!("invalid pattern `{pattern}`: {err}"),
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Writes the current time.
+    fn write_time(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        let now = Timestamp::now();
+        let zone = if self.local {
+            TimeZone::system()
+        } else {
+            TimeZone::UTC
+        };
+        if let Some(pattern) = &self.pattern {
+            return write!(w, "{}", now.to_zoned(zone).strftime(pattern));
+        }
+        let precision = usize::from(self.precision.unwrap_or(DEFAULT_PRECISION));
+        if self.local {
+            write!(
+                w,
+                "{:.precision$}",
+                now.display_with_offset(zone.to_offset(now))
+            )
+        } else {
+            write!(w, "{now:.precision$}")
+        }
+    }
+}
+
+impl FormatTime for TimeFormat {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        self.write_time(w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::ends_with;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::ok;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn render(format: &TimeFormat) -> String {
+        let mut out = String::new();
+        format.write_time(&mut out).expect("writable string");
+        out
+    }
+
+    #[rstest]
+    #[case(None, 27)]
+    #[case(Some(0), 20)]
+    #[case(Some(3), 24)]
+    #[case(Some(9), 30)]
+    fn rfc3339_utc_honors_precision(#[case] precision: Option<u8>, #[case] len: usize) {
+        let format = TimeFormat::builder().maybe_precision(precision).build();
+        let out = render(&format);
+        assert_that!(out.len(), eq(len));
+        assert_that!(out, ends_with("Z"));
+    }
+
+    #[gtest]
+    fn pattern_replaces_rfc3339() {
+        let format = TimeFormat::builder().pattern("%Y|%H").build();
+        expect_that!(render(&format), contains_substring("|"));
+        expect_that!(render(&format).len(), eq(7));
+    }
+
+    #[gtest]
+    fn out_of_range_precision_is_rejected() {
+        let format = TimeFormat::builder().precision(10).build();
+        expect_that!(format.validate(), err(anything()));
+    }
+
+    #[gtest]
+    fn invalid_pattern_is_rejected() {
+        let format = TimeFormat::builder().pattern("%!").build();
+        expect_that!(format.validate(), err(anything()));
+    }
+
+    #[gtest]
+    fn default_is_valid() {
+        expect_that!(TimeFormat::default().validate(), ok(anything()));
+    }
+
+    #[rstest]
+    #[case(None, true, true)]
+    #[case(None, false, false)]
+    #[case(Some(true), false, true)]
+    #[case(Some(false), true, false)]
+    fn explicit_choice_overrides_format_default(
+        #[case] enabled: Option<bool>,
+        #[case] default: bool,
+        #[case] expected: bool,
+    ) {
+        let format = TimeFormat::builder().maybe_enabled(enabled).build();
+        assert_that!(format.is_enabled(default), eq(expected));
+    }
+}
@@ -1,25 +1,43 @@
 //! RAII guard for the tracing and telemetry providers.
 
 use std::fmt;
+use std::time::Duration;
 
+use opentelemetry::trace::TraceContextExt as _;
+use opentelemetry::trace::TraceId;
+use opentelemetry_sdk::error::OTelSdkError;
 use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_opentelemetry::OpenTelemetrySpanExt as _;
 use tracing_subscriber::filter::EnvFilter;
+use tracing_subscriber::reload;
 
 use crate::error::ErrorKind;
 use crate::error::Result;
 
+/// Default time allowed for the providers to shut down.
+pub(crate) const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Guard returned by [`Owiwi::try_init`](crate::Owiwi::try_init) and
 /// [`Owiwi::try_init_console`](crate::Owiwi::try_init_console).
 ///
 /// Flushes buffered spans and shuts down the underlying [`SdkTracerProvider`]
 /// when dropped. Must be held for the lifetime of the program; dropping it
 /// early stops telemetry export.
+///
+/// Shutdown is bounded by the configured shutdown timeout, so an unreachable
+/// collector cannot block the process from exiting.
 #[derive(Debug)]
 pub struct OwiwiGuard {
     pub(crate) tracer_provider: SdkTracerProvider,
+    #[cfg(feature = "logs")]
+    pub(crate) logger_provider: Option<opentelemetry_sdk::logs::SdkLoggerProvider>,
     #[cfg(feature = "metrics")]
     pub(crate) meter_provider: Option<opentelemetry_sdk::metrics::SdkMeterProvider>,
+    #[cfg(feature = "sentry")]
+    pub(crate) sentry: Option<crate::sentry::SentryGuard>,
     pub(crate) filter_handle: Option<FilterHandle>,
+    pub(crate) shutdown_timeout: Duration,
+    pub(crate) service_name: String,
 }
 
 /// Handle for changing the tracing filter at runtime.
@@ -30,25 +48,130 @@ pub struct FilterHandle {
 impl OwiwiGuard {
     /// Shuts down all providers.
     pub fn shutdown(mut self) -> Result<()> {
-        let tracer_provider = std::mem::take(&mut self.tracer_provider);
-        tracer_provider.shutdown().map_err(ErrorKind::Shutdown)?;
+        let mut failures = self.shutdown_providers().into_iter();
+        let Some((_, err)) = failures.next() else {
+            return Ok(());
+        };
+        for (provider, err) in failures {
+            report_shutdown_error(provider, &err);
+        }
+        Err(ErrorKind::Shutdown(err).into())
+    }
+
+    /// Exports all buffered telemetry without shutting down the providers.
+    ///
+    /// Use it at checkpoints where pending spans must reach the backend
+    /// while the guard stays alive.
+    pub fn flush(&self) -> Result<()> {
+        self.tracer_provider
+            .force_flush()
+            .map_err(ErrorKind::Flush)?;
+
+        #[cfg(feature = "logs")]
+        {
+            if let Some(logger_provider) = &self.logger_provider {
+                logger_provider.force_flush().map_err(ErrorKind::Flush)?;
+            }
+        }
 
         #[cfg(feature = "metrics")]
         {
-            if let Some(meter_provider) = self.meter_provider.take() {
-                meter_provider.shutdown().map_err(ErrorKind::Shutdown)?;
+            if let Some(meter_provider) = &self.meter_provider {
+                meter_provider.force_flush().map_err(ErrorKind::Flush)?;
             }
         }
-        std::mem::forget(self);
+
+        #[cfg(feature = "sentry")]
+        if let Some(sentry) = &self.sentry {
+            sentry.flush(self.shutdown_timeout);
+        }
         Ok(())
     }
 
+    /// Replaces the terminal output filter with the given directives,
+    /// using the `RUST_LOG` syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directives are invalid, leaving the active
+    /// filter unchanged, or if telemetry was initialized without a
+    /// reloadable filter.
+    pub fn set_filter(&self, directives: &str) -> Result<()> {
+        let filter = EnvFilter::try_new(directives)?;
+        self.filter_handle
+            .as_ref()
+            .ok_or(ErrorKind::FilterUnavailable)?
+            .reload(filter)
+    }
+
+    /// Returns the service name telemetry was initialized with.
+    #[must_use]
+    pub fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    /// Returns a [`Meter`](opentelemetry::metrics::Meter) named `name` from the
+    /// guarded meter provider, or `None` without a metric backend.
+    ///
+    /// Instruments stay usable until the guard is shut down or dropped, which
+    /// flushes pending measurements.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn meter(&self, name: &'static str) -> Option<opentelemetry::metrics::Meter> {
+        use opentelemetry::metrics::MeterProvider as _;
+
+        self.meter_provider
+            .as_ref()
+            .map(|provider| provider.meter(name))
+    }
+
+    /// Returns the trace ID of the current span, if it is being traced.
+    #[must_use]
+    pub fn current_trace_id() -> Option<TraceId> {
+        let context = tracing::Span::current().context();
+        let span_context = context.span().span_context().clone();
+        span_context.is_valid().then(|| span_context.trace_id())
+    }
+
     /// Returns a handle for replacing active filter
     #[must_use]
     pub const fn filter_handle(&self) -> Option<&FilterHandle> {
         self.filter_handle.as_ref()
     }
 
+    /// Shuts down every provider and closes Sentry, returning the failures
+    /// by provider name.
+    ///
+    /// The providers are taken, so a later call, such as the one on drop after
+    /// [`Self::shutdown`], has nothing left to shut down.
+    fn shutdown_providers(&mut self) -> Vec<(&'static str, OTelSdkError)> {
+        let mut failures = Vec::new();
+        let tracer_provider = std::mem::take(&mut self.tracer_provider);
+        if let Err(err) = tracer_provider.shutdown_with_timeout(self.shutdown_timeout) {
+            failures.push(("tracer", err));
+        }
+
+        #[cfg(feature = "logs")]
+        if let Some(logger_provider) = self.logger_provider.take()
+            && let Err(err) = logger_provider.shutdown_with_timeout(self.shutdown_timeout)
+        {
+            failures.push(("logger", err));
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(meter_provider) = self.meter_provider.take()
+            && let Err(err) = meter_provider.shutdown_with_timeout(self.shutdown_timeout)
+        {
+            failures.push(("meter", err));
+        }
+
+        #[cfg(feature = "sentry")]
+        if let Some(sentry) = self.sentry.take() {
+            sentry.close(self.shutdown_timeout);
+        }
+        failures
+    }
+
     #[allow(
         dead_code,
         reason = "Only called when SDK is disabled and cannot be behind a cfg"
@@ -56,9 +179,15 @@ impl OwiwiGuard {
     pub(crate) fn noop() -> Self {
         Self {
             tracer_provider: SdkTracerProvider::default(),
+            #[cfg(feature = "logs")]
+            logger_provider: None,
             #[cfg(feature = "metrics")]
             meter_provider: None,
+            #[cfg(feature = "sentry")]
+            sentry: None,
             filter_handle: None,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            service_name: String::new(),
         }
     }
 }
@@ -71,22 +200,38 @@ impl fmt::Debug for FilterHandle {
 
 impl Drop for OwiwiGuard {
     fn drop(&mut self) {
-        if let Err(err) = self.tracer_provider.shutdown() {
-            eprintln!("failed to shutdown tracer provider {err}");
+        for (provider, err) in self.shutdown_providers() {
+            report_shutdown_error(provider, &err);
         }
+    }
+}
 
-        #[cfg(feature = "metrics")]
-        {
-            if let Some(meter_provider) = &self.meter_provider
-                && let Err(err) = meter_provider.shutdown()
-            {
-                eprintln!("failed to shutdown meter provider {err}");
-            }
+/// Reports a provider shutdown failure on stderr, since the subscriber may
+/// already be gone.
+fn report_shutdown_error(provider: &str, err: &OTelSdkError) {
+    match err {
+        OTelSdkError::Timeout(timeout) => {
+            eprintln!(
+                "warning: {provider} provider shutdown timed out after {timeout:?}, \
+                 buffered telemetry may be lost"
+            );
         }
+        err => eprintln!("failed to shutdown {provider} provider {err}"),
     }
 }
 
 impl FilterHandle {
+    /// Wraps a reload handle of the filter layer.
+    pub(crate) fn new<S: 'static>(handle: reload::Handle<EnvFilter, S>) -> Self {
+        Self {
+            inner: Box::new(move |filter| {
+                handle
+                    .reload(filter)
+                    .map_err(|err| ErrorKind::FilterReload(err).into())
+            }),
+        }
+    }
+
     /// Replaces active filter.
     pub fn reload(&self, new_filter: EnvFilter) -> Result<()> {
         (self.inner)(new_filter)
@@ -95,19 +240,160 @@ impl FilterHandle {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
     use googletest::expect_that;
     use googletest::gtest;
     use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::len;
+    use googletest::matchers::none;
     use googletest::matchers::ok;
+    use googletest::matchers::pat;
+    use googletest::matchers::some;
+    use opentelemetry::trace::Tracer as _;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::layer::Layer;
+    use tracing_subscriber::layer::SubscriberExt as _;
 
     use super::*;
 
+    #[cfg(feature = "metrics")]
+    #[gtest]
+    fn meter_requires_a_metric_backend() {
+        expect_that!(OwiwiGuard::noop().meter("test"), none());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[gtest]
+    fn meter_measurements_are_flushed() {
+        use opentelemetry_sdk::metrics::InMemoryMetricExporter;
+        use opentelemetry_sdk::metrics::SdkMeterProvider;
+
+        let exporter = InMemoryMetricExporter::default();
+        let mut guard = OwiwiGuard::noop();
+        guard.meter_provider = Some(
+            SdkMeterProvider::builder()
+                .with_periodic_exporter(exporter.clone())
+                .build(),
+        );
+        let meter = guard.meter("test").expect("a metric backend");
+        meter.u64_counter("requests").build().add(1, &[]);
+
+        expect_that!(guard.flush(), ok(anything()));
+        expect_that!(
+            exporter.get_finished_metrics().expect("exported metrics"),
+            len(eq(1))
+        );
+    }
+
     #[gtest]
     fn shutdown_returns_ok() {
         let guard = OwiwiGuard::noop();
         expect_that!(guard.shutdown(), ok(anything()));
     }
 
+    #[gtest]
+    fn flush_exports_buffered_spans() {
+        let exporter = InMemorySpanExporter::default();
+        let mut guard = OwiwiGuard::noop();
+        guard.tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter.clone())
+            .build();
+
+        guard
+            .tracer_provider
+            .tracer("test")
+            .in_span("checkpoint", |_cx| {});
+        expect_that!(exporter.get_finished_spans().expect("spans"), len(eq(0)));
+
+        expect_that!(guard.flush(), ok(anything()));
+        expect_that!(exporter.get_finished_spans().expect("spans"), len(eq(1)));
+    }
+
+    #[gtest]
+    fn drop_shuts_the_providers_down() {
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(InMemorySpanExporter::default())
+            .build();
+        let mut guard = OwiwiGuard::noop();
+        guard.tracer_provider = provider.clone();
+
+        drop(guard);
+        expect_that!(
+            provider.shutdown(),
+            err(pat!(OTelSdkError::AlreadyShutdown))
+        );
+    }
+
+    /// Counts the events reaching it.
+    struct CountingLayer(Arc<AtomicUsize>);
+
+    impl<S: Subscriber> Layer<S> for CountingLayer {
+        fn on_event(&self, _event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn reloadable_guard() -> (OwiwiGuard, Arc<AtomicUsize>, impl Subscriber) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let (filter, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let subscriber = tracing_subscriber::registry()
+            .with(CountingLayer(Arc::clone(&count)).with_filter(filter));
+        let mut guard = OwiwiGuard::noop();
+        guard.filter_handle = Some(FilterHandle::new(handle));
+        (guard, count, subscriber)
+    }
+
+    #[gtest]
+    fn set_filter_changes_filtered_events() {
+        let (guard, count, subscriber) = reloadable_guard();
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("filtered out");
+            expect_that!(count.load(Ordering::SeqCst), eq(0));
+
+            expect_that!(guard.set_filter("debug"), ok(anything()));
+            tracing::debug!("let through");
+            expect_that!(count.load(Ordering::SeqCst), eq(1));
+        });
+    }
+
+    #[gtest]
+    fn set_filter_keeps_active_filter_on_invalid_directives() {
+        let (guard, count, subscriber) = reloadable_guard();
+        tracing::subscriber::with_default(subscriber, || {
+            expect_that!(guard.set_filter("debug,=[bad"), err(anything()));
+            tracing::debug!("still filtered out");
+            tracing::info!("still let through");
+            expect_that!(count.load(Ordering::SeqCst), eq(1));
+        });
+    }
+
+    #[gtest]
+    fn current_trace_id_follows_the_active_span() {
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        tracing::subscriber::with_default(subscriber, || {
+            expect_that!(OwiwiGuard::current_trace_id(), none());
+            tracing::info_span!("traced").in_scope(|| {
+                expect_that!(OwiwiGuard::current_trace_id(), some(anything()));
+            });
+        });
+    }
+
+    #[gtest]
+    fn set_filter_without_handle_fails() {
+        let guard = OwiwiGuard::noop();
+        expect_that!(guard.set_filter("debug"), err(anything()));
+    }
+
     #[cfg(feature = "metrics")]
     #[gtest]
     fn noop_guard_has_no_meter_provider() {
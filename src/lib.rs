@@ -3,16 +3,20 @@
 //! Sends traces to any of the exporters defined in the [`trace`] module.
 //!
 //! ## Decisions
-//! - **Transport:** gRPC is the only supported transport protocol.
-//!   `OTEL_EXPORTER_OTLP_PROTOCOL` is not read.
+//! - **Transport:** gRPC by default. OTLP over HTTP (`http/protobuf` or `http/json`) is
+//!   available with the `http` feature and selected through `OTEL_EXPORTER_OTLP_PROTOCOL`
+//!   or [`OtlpConfig::protocol`].
 //! - **Export strategy:** Batch export for OTLP backends. The console exporter
 //!   uses synchronous export for immediate output.
 //!   periodically.
-//! - **Subscriber layers** bottom to top: OpenTelemetry with export filter, `ErrorLayer`,
-//!   fmt with env filter.
+//! - **Subscriber layers** bottom to top: OpenTelemetry with export filter, logs bridge
+//!   with export filter (`logs` feature), `ErrorLayer` (unless disabled), fmt with env filter.
+//! - **`log` records:** With the `log` feature, `log` records are bridged into the subscriber
+//!   as events, unless the application already set a `log` logger.
 //! - **Backend selection** This is determined by which initialization method you call, not
 //!   by the `OTEL_TRACES_EXPORTER`.
-//! - **TLS:** It's auto-enabled for HTTPS endpoints using system roots but can be configured.
+//! - **TLS:** It's auto-enabled for HTTPS endpoints using system roots but can be configured,
+//!   including mutual TLS through [`TlsConfig`].
 //! - **Ignored environment variables:** `OTEL_TRACES_EXPORTER` and `OTEL_METRICS_EXPORTER` are
 //!   spec-defined but not read. The backend selection is determined by which initialization method
 //!   you call, not by environment.
@@ -20,15 +24,37 @@
 #![cfg_attr(test, deny(warnings))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "serde")]
+mod config;
 pub mod env_vars;
 mod error;
 mod format;
 mod guard;
+#[cfg(feature = "log")]
+mod log_bridge;
+#[cfg(feature = "logs")]
+mod logs;
 #[cfg(feature = "metrics")]
 mod metrics;
+mod output;
 mod owiwi;
+mod panic;
+mod propagation;
+mod protocol;
+mod rate_limit;
+mod redact;
+#[cfg(feature = "sentry")]
+mod sentry;
+mod span_ext;
+mod startup;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod tls;
 mod trace;
 
+#[cfg(feature = "serde")]
+#[doc(inline)]
+pub use config::OwiwiConfig;
 #[doc(inline)]
 pub use error::Error;
 #[doc(inline)]
@@ -36,22 +62,79 @@ pub use error::Result;
 #[doc(inline)]
 pub use format::EventFormat;
 #[doc(inline)]
+pub use format::SpanEvent;
+#[doc(inline)]
+pub use format::TimeFormat;
+#[doc(inline)]
 pub use guard::FilterHandle;
 #[doc(inline)]
 pub use guard::OwiwiGuard;
 #[doc(inline)]
+#[cfg(feature = "logs")]
+pub use logs::LogExporter;
+#[doc(inline)]
 #[cfg(feature = "metrics")]
 pub use metrics::MetricExporter;
 #[cfg(feature = "prometheus")]
+#[allow(
+    deprecated,
+    reason = "the Prometheus variant is kept for compatibility"
+)]
 pub use metrics::PrometheusConfig;
 #[doc(inline)]
+#[cfg(feature = "metrics")]
+pub use metrics::Temporality;
+#[doc(inline)]
+pub use output::OutputTarget;
+#[doc(inline)]
 pub use owiwi::Owiwi;
+#[doc(inline)]
+pub use propagation::Propagator;
+#[doc(inline)]
+pub use protocol::Compression;
+#[doc(inline)]
+pub use protocol::Protocol;
+#[doc(inline)]
+pub use span_ext::SpanExt;
+#[doc(inline)]
+pub use tls::TlsConfig;
+#[cfg(feature = "axiom")]
+#[doc(inline)]
+pub use trace::AxiomConfig;
+#[doc(inline)]
+pub use trace::BatchConfig;
+#[doc(inline)]
+pub use trace::BatchRuntime;
+#[cfg(feature = "datadog")]
+#[doc(inline)]
+pub use trace::DatadogConfig;
 #[cfg(feature = "honeycomb")]
 #[doc(inline)]
 pub use trace::HoneycombConfig;
 #[doc(inline)]
+pub use trace::KeepaliveConfig;
+#[doc(inline)]
 pub use trace::OtlpConfig;
 #[doc(inline)]
+pub use trace::OtlpTokenConfig;
+#[doc(inline)]
+pub use trace::RetryConfig;
+#[cfg(feature = "signoz")]
+#[doc(inline)]
+pub use trace::SigNozConfig;
+#[doc(inline)]
+pub use trace::SpanLimits;
+#[cfg(feature = "tempo")]
+#[doc(inline)]
+pub use trace::TempoConfig;
+#[doc(inline)]
 pub use trace::TraceExporter;
+#[cfg(feature = "uptrace")]
+#[doc(inline)]
+pub use trace::UptraceConfig;
+
+#[cfg(feature = "sentry")]
+#[doc(inline)]
+pub use self::sentry::SentryConfig;
 /// Help heading for telemetry options.
 pub const HELP_HEADING: &str = "Telemetry";
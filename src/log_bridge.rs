@@ -0,0 +1,19 @@
+//! Bridge from `log` records to `tracing` events.
+
+use tracing_log::LogTracer;
+
+/// Target prefix of the records `tracing` emits for span lifecycle when its
+/// `log` feature is enabled.
+const TRACING_SPAN_TARGET: &str = "tracing::span";
+
+/// Installs [`LogTracer`] as the global `log` logger, so `log` records reach
+/// the installed subscriber as events.
+///
+/// Does nothing when a logger is already set, whether an earlier bridge or an
+/// application logger. The span lifecycle records of `tracing` are ignored,
+/// since the spans themselves already reach the subscriber.
+pub(crate) fn install() {
+    let _ = LogTracer::builder()
+        .ignore_crate(TRACING_SPAN_TARGET)
+        .init();
+}
@@ -0,0 +1,162 @@
+//! OpenTelemetry logs export.
+
+use opentelemetry_otlp::tonic_types::transport::ClientTlsConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::logs::BatchLogProcessor;
+use opentelemetry_sdk::logs::SdkLoggerProvider;
+#[cfg(feature = "console")]
+use opentelemetry_sdk::logs::SimpleLogProcessor;
+use secrecy::SecretString;
+
+use crate::Error;
+use crate::OtlpConfig;
+use crate::redact::FieldRedaction;
+use crate::redact::RedactingLogProcessor;
+
+/// Targets excluded from log export to avoid feedback loops through the
+/// exporter's own instrumentation.
+pub(crate) const SUPPRESSED_TARGETS: &[&str] =
+    &["h2", "hyper", "opentelemetry", "reqwest", "tonic"];
+
+/// Log backend selection.
+///
+/// Exported log records carry the trace and span IDs of the span they were
+/// emitted in.
+#[allow(
+    clippy::large_enum_variant,
+    reason = "short-lived init value, not stored"
+)]
+#[derive(Clone, Debug, Default)]
+pub enum LogExporter {
+    /// Export log records to stdout.
+    #[cfg(feature = "console")]
+    Console,
+    /// No logs export.
+    #[default]
+    None,
+    /// Export via OTLP to a collector.
+    Otlp(OtlpConfig),
+}
+
+impl LogExporter {
+    /// Adds headers to OTLP-based backends, replacing entries with the same key.
+    pub(crate) fn extend_headers(&mut self, headers: &[(String, SecretString)]) {
+        if let Self::Otlp(config) = self {
+            crate::trace::otlp::merge_headers(&mut config.headers, headers);
+        }
+    }
+
+    /// Replaces the TLS configuration of OTLP-based backends.
+    pub(crate) fn set_tls_config(&mut self, tls: ClientTlsConfig) {
+        if let Self::Otlp(config) = self {
+            config.tls_config = Some(tls);
+        }
+    }
+
+    /// Forces plaintext or TLS for OTLP-based backends, whatever their
+    /// endpoint scheme.
+    pub(crate) const fn set_insecure(&mut self, insecure: bool) {
+        if let Self::Otlp(config) = self {
+            config.insecure = Some(insecure);
+        }
+    }
+
+    /// Replaces the gRPC keepalive of OTLP-based backends.
+    pub(crate) const fn set_keepalive(&mut self, keepalive: crate::KeepaliveConfig) {
+        if let Self::Otlp(config) = self {
+            config.keepalive = keepalive;
+        }
+    }
+
+    /// Replaces the export timeout of OTLP-based backends.
+    pub(crate) const fn set_timeout(&mut self, timeout: std::time::Duration) {
+        if let Self::Otlp(config) = self {
+            config.timeout = timeout;
+        }
+    }
+
+    /// Replaces the transport protocol of OTLP-based backends.
+    pub(crate) const fn set_protocol(&mut self, protocol: crate::Protocol) {
+        if let Self::Otlp(config) = self {
+            config.protocol = protocol;
+        }
+    }
+
+    /// Replaces the payload compression of OTLP-based backends.
+    pub(crate) const fn set_compression(&mut self, compression: Option<crate::Compression>) {
+        if let Self::Otlp(config) = self {
+            config.compression = compression;
+        }
+    }
+
+    /// Builds the logger provider for this backend.
+    ///
+    /// It returns `None` when no backend is configured.
+    pub fn build_provider(self, resource: Resource) -> Result<Option<SdkLoggerProvider>, Error> {
+        self.build_provider_with(resource, FieldRedaction::default())
+    }
+
+    /// Builds the logger provider for this backend, redacting matching
+    /// attributes before export.
+    pub(crate) fn build_provider_with(
+        self,
+        resource: Resource,
+        redaction: FieldRedaction,
+    ) -> Result<Option<SdkLoggerProvider>, Error> {
+        match self {
+            #[cfg(feature = "console")]
+            Self::Console => {
+                let exporter = opentelemetry_stdout::LogExporter::default();
+                let provider = SdkLoggerProvider::builder()
+                    .with_resource(resource)
+                    .with_log_processor(RedactingLogProcessor::new(
+                        SimpleLogProcessor::new(exporter),
+                        redaction,
+                    ))
+                    .build();
+                Ok(Some(provider))
+            }
+            Self::None => Ok(None),
+            Self::Otlp(config) => {
+                let exporter = config.build_log_exporter()?;
+                let provider = SdkLoggerProvider::builder()
+                    .with_resource(resource)
+                    .with_log_processor(RedactingLogProcessor::new(
+                        BatchLogProcessor::builder(exporter).build(),
+                        redaction,
+                    ))
+                    .build();
+                Ok(Some(provider))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::none;
+    use googletest::matchers::ok;
+    use googletest::matchers::some;
+
+    use super::*;
+
+    #[gtest]
+    fn none_builds_no_provider() {
+        let provider = LogExporter::None.build_provider(Resource::builder().build());
+        expect_that!(provider, ok(none()));
+    }
+
+    #[tokio::test]
+    #[gtest]
+    async fn otlp_builds_a_provider() {
+        let config = OtlpConfig::builder()
+            .endpoint("http://test.example".parse().expect("to be valid"))
+            .timeout(std::time::Duration::ZERO)
+            .build();
+        let provider = LogExporter::Otlp(config).build_provider(Resource::builder().build());
+        expect_that!(provider, ok(some(anything())));
+    }
+}
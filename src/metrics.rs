@@ -1,24 +1,46 @@
 //! Metrics export.
+#[cfg(feature = "process-metrics")]
+pub(crate) mod process;
 #[cfg(feature = "prometheus")]
 mod prometheus;
+mod temporality;
 
 use std::time::Duration;
 
 use opentelemetry_sdk::Resource;
-#[cfg(any(feature = "console", feature = "prometheus"))]
+use opentelemetry_sdk::metrics::Aggregation;
+use opentelemetry_sdk::metrics::Instrument;
+use opentelemetry_sdk::metrics::InstrumentKind;
+use opentelemetry_sdk::metrics::MeterProviderBuilder;
 use opentelemetry_sdk::metrics::PeriodicReader;
 use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::metrics::Stream;
 #[doc(inline)]
 #[cfg(feature = "prometheus")]
+#[allow(
+    deprecated,
+    reason = "the Prometheus variant is kept for compatibility"
+)]
 pub use prometheus::PrometheusConfig;
+#[doc(inline)]
+pub use temporality::Temporality;
+use tracing::Subscriber;
+use tracing_opentelemetry::MetricsLayer;
+use tracing_subscriber::registry::LookupSpan;
 
 use crate::Error;
+use crate::OtlpConfig;
+use crate::error::ErrorKind;
 
-/// Metric backend selectionn.
+/// Metric backend selection.
 #[allow(
     clippy::large_enum_variant,
     reason = "short-lived init value, not stored"
 )]
+#[allow(
+    deprecated,
+    reason = "the Prometheus variant is kept for compatibility"
+)]
 #[derive(Clone, Debug, Default)]
 pub enum MetricExporter {
     /// Export metrics to stdout on a periodical interval.
@@ -27,22 +49,33 @@ pub enum MetricExporter {
     /// No metrics export.
     #[default]
     None,
-    /// Export metrics via OTLP to a Prometheus-compatible endpoint
+    /// Export metrics via OTLP to a collector.
+    Otlp(OtlpConfig),
+    /// Export metrics via OTLP/gRPC to a collector.
+    ///
+    /// Despite its name, this pushes OTLP rather than serving a Prometheus
+    /// scrape endpoint.
     #[cfg(feature = "prometheus")]
+    #[deprecated(note = "pushes OTLP, not Prometheus; use `MetricExporter::Otlp` instead")]
     Prometheus(PrometheusConfig),
 }
 
+#[allow(
+    deprecated,
+    reason = "the Prometheus variant is kept for compatibility"
+)]
 impl MetricExporter {
     /// Builds the meter provider for this backend.
     ///
-    /// It returns `None` when no backend is configured.
+    /// Histograms use the explicit bucket boundaries when given, the SDK
+    /// defaults otherwise. It returns `None` when no backend is configured.
     pub fn build_provider(
         self,
         resource: Resource,
         interval: Option<Duration>,
+        histogram_buckets: &[f64],
     ) -> Result<Option<SdkMeterProvider>, Error> {
-        #[cfg(not(any(feature = "console", feature = "prometheus")))]
-        let _ = (resource, interval);
+        validate_buckets(histogram_buckets)?;
         match self {
             #[cfg(feature = "console")]
             Self::Console => {
@@ -51,36 +84,263 @@ impl MetricExporter {
                 if let Some(interval) = interval {
                     builder = builder.with_interval(interval);
                 }
-                let provider = SdkMeterProvider::builder()
-                    .with_resource(resource)
+                let provider = provider_builder(resource, histogram_buckets)?
                     .with_reader(builder.build())
                     .build();
                 Ok(Some(provider))
             }
 
             Self::None => Ok(None),
+            Self::Otlp(config) => {
+                let exporter = config.build_metric_exporter()?;
+                let provider = meter_provider(exporter, resource, interval, histogram_buckets)?;
+                Ok(Some(provider))
+            }
             #[cfg(feature = "prometheus")]
             Self::Prometheus(config) => {
                 let exporter = config.try_into()?;
-                let provider = meter_provider(exporter, resource, interval);
+                let provider = meter_provider(exporter, resource, interval, histogram_buckets)?;
                 Ok(Some(provider))
             }
         }
     }
 }
 
-#[cfg(feature = "prometheus")]
+#[allow(
+    deprecated,
+    reason = "the Prometheus variant is kept for compatibility"
+)]
+impl MetricExporter {
+    /// Replaces the endpoint of OTLP-based backends.
+    pub(crate) fn set_endpoint(&mut self, endpoint: url::Url) {
+        match self {
+            Self::Otlp(config) => config.endpoint = endpoint,
+            #[cfg(feature = "prometheus")]
+            Self::Prometheus(config) => config.endpoint = endpoint,
+            _ => {}
+        }
+    }
+
+    /// Replaces the export timeout of OTLP-based backends.
+    pub(crate) const fn set_timeout(&mut self, timeout: Duration) {
+        match self {
+            Self::Otlp(config) => config.timeout = timeout,
+            #[cfg(feature = "prometheus")]
+            Self::Prometheus(config) => config.timeout = Some(timeout),
+            _ => {}
+        }
+    }
+
+    /// Forces plaintext or TLS for OTLP backends, whatever their endpoint
+    /// scheme.
+    pub(crate) const fn set_insecure(&mut self, insecure: bool) {
+        if let Self::Otlp(config) = self {
+            config.insecure = Some(insecure);
+        }
+    }
+
+    /// Replaces the gRPC keepalive of OTLP backends.
+    pub(crate) const fn set_keepalive(&mut self, keepalive: crate::KeepaliveConfig) {
+        if let Self::Otlp(config) = self {
+            config.keepalive = keepalive;
+        }
+    }
+
+    /// Replaces the payload compression of OTLP backends.
+    pub(crate) const fn set_compression(&mut self, compression: Option<crate::Compression>) {
+        if let Self::Otlp(config) = self {
+            config.compression = compression;
+        }
+    }
+
+    /// Replaces the transport protocol of OTLP backends.
+    pub(crate) const fn set_protocol(&mut self, protocol: crate::Protocol) {
+        if let Self::Otlp(config) = self {
+            config.protocol = protocol;
+        }
+    }
+
+    /// Replaces the aggregation temporality of OTLP backends.
+    pub(crate) const fn set_temporality(&mut self, temporality: Temporality) {
+        if let Self::Otlp(config) = self {
+            config.temporality = temporality;
+        }
+    }
+
+    /// Replaces the TLS configuration of OTLP-based backends.
+    pub(crate) fn set_tls_config(
+        &mut self,
+        tls: opentelemetry_otlp::tonic_types::transport::ClientTlsConfig,
+    ) {
+        match self {
+            Self::Otlp(config) => config.tls_config = Some(tls),
+            #[cfg(feature = "prometheus")]
+            Self::Prometheus(config) => config.tls_config = Some(tls),
+            _ => {}
+        }
+    }
+
+    /// Adds headers to OTLP-based backends, replacing entries with the same key.
+    pub(crate) fn extend_headers(&mut self, headers: &[(String, secrecy::SecretString)]) {
+        match self {
+            Self::Otlp(config) => crate::trace::otlp::merge_headers(&mut config.headers, headers),
+            #[cfg(feature = "prometheus")]
+            Self::Prometheus(config) => {
+                crate::trace::otlp::merge_headers(&mut config.headers, headers);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Builds a meter provider periodically pushing to an OTLP exporter.
 fn meter_provider(
     exporter: opentelemetry_otlp::MetricExporter,
     resource: Resource,
     interval: Option<Duration>,
-) -> SdkMeterProvider {
+    histogram_buckets: &[f64],
+) -> Result<SdkMeterProvider, Error> {
     let mut builder = PeriodicReader::builder(exporter);
     if let Some(interval) = interval {
         builder = builder.with_interval(interval);
     }
-    SdkMeterProvider::builder()
-        .with_resource(resource)
+    Ok(provider_builder(resource, histogram_buckets)?
         .with_reader(builder.build())
-        .build()
+        .build())
+}
+
+/// Creates a meter provider builder, adding a histogram view for explicit buckets.
+fn provider_builder(
+    resource: Resource,
+    histogram_buckets: &[f64],
+) -> Result<MeterProviderBuilder, Error> {
+    let builder = SdkMeterProvider::builder().with_resource(resource);
+    if histogram_buckets.is_empty() {
+        return Ok(builder);
+    }
+
+    let boundaries = histogram_buckets.to_vec();
+    Ok(builder.with_view(move |instrument: &Instrument| {
+        if instrument.kind() != InstrumentKind::Histogram {
+            return None;
+        }
+        Stream::builder()
+            .with_aggregation(Aggregation::ExplicitBucketHistogram {
+                boundaries: boundaries.clone(),
+                record_min_max: true,
+            })
+            .build()
+            .ok()
+    }))
+}
+
+/// Creates a layer recording metrics from `tracing` events.
+///
+/// Event fields prefixed with `monotonic_counter.`, `counter.`, `histogram.`
+/// or `gauge.` are recorded on the instrument named after the rest of the
+/// field, with the other event fields as attributes.
+pub(crate) fn event_layer<S>(provider: &SdkMeterProvider) -> MetricsLayer<S, SdkMeterProvider>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    MetricsLayer::new(provider.clone())
+}
+
+/// Checks that histogram boundaries are finite and strictly increasing.
+fn validate_buckets(boundaries: &[f64]) -> Result<(), Error> {
+    let finite = boundaries.iter().all(|bound| bound.is_finite());
+    let increasing = boundaries.windows(2).all(|pair| pair[0] < pair[1]);
+    if finite && increasing {
+        Ok(())
+    } else {
+        Err(ErrorKind::InvalidHistogramBuckets {
+            boundaries: boundaries.to_vec(),
+        }
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::ok;
+    use googletest::matchers::some;
+    use googletest::matchers::unordered_elements_are;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(&[])]
+    #[case(&[5.0])]
+    #[case(&[0.0, 5.0, 10.0, 25.0])]
+    fn valid_buckets_are_accepted(#[case] boundaries: &[f64]) {
+        assert_that!(validate_buckets(boundaries), ok(anything()));
+    }
+
+    #[rstest]
+    #[case(&[10.0, 5.0])]
+    #[case(&[5.0, 5.0])]
+    #[case(&[0.0, f64::NAN])]
+    #[case(&[0.0, f64::INFINITY])]
+    fn invalid_buckets_are_rejected(#[case] boundaries: &[f64]) {
+        assert_that!(validate_buckets(boundaries), err(anything()));
+    }
+
+    #[tokio::test]
+    #[gtest]
+    async fn otlp_builds_a_provider() {
+        let config = OtlpConfig::builder()
+            .endpoint("http://metrics.example:4317".parse().expect("to be valid"))
+            .timeout(Duration::ZERO)
+            .build();
+        let provider =
+            MetricExporter::Otlp(config).build_provider(Resource::builder().build(), None, &[]);
+        expect_that!(provider, ok(some(anything())));
+    }
+
+    #[gtest]
+    fn otlp_endpoint_is_replaced() {
+        let mut exporter = MetricExporter::Otlp(OtlpConfig::default());
+        exporter.set_endpoint("http://metrics.example:4317".parse().expect("to be valid"));
+        let MetricExporter::Otlp(config) = exporter else {
+            unreachable!("variant is preserved")
+        };
+        expect_that!(config.endpoint.as_str(), eq("http://metrics.example:4317/"));
+    }
+
+    #[gtest]
+    fn events_are_recorded_as_metrics() {
+        use opentelemetry_sdk::metrics::InMemoryMetricExporter;
+        use tracing_subscriber::layer::SubscriberExt as _;
+
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(event_layer(&provider));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(monotonic_counter.requests = 1_u64, route = "/");
+            tracing::info!(histogram.latency_ms = 12.5_f64);
+        });
+        provider.force_flush().expect("metrics flushed");
+
+        let names: Vec<String> = exporter
+            .get_finished_metrics()
+            .expect("exported metrics")
+            .iter()
+            .flat_map(|metrics| metrics.scope_metrics())
+            .flat_map(|scope| scope.metrics())
+            .map(|metric| metric.name().to_owned())
+            .collect();
+        expect_that!(
+            names,
+            unordered_elements_are![eq("requests"), eq("latency_ms")]
+        );
+    }
 }
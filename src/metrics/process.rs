@@ -0,0 +1,187 @@
+//! Process metrics.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use opentelemetry::metrics::Meter;
+use sysinfo::Pid;
+use sysinfo::Process;
+use sysinfo::ProcessRefreshKind;
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+
+/// Registers resident memory, CPU time and thread count instruments on `meter`,
+/// following the `process.*` semantic conventions.
+///
+/// Nothing is registered on platforms where the current process cannot be
+/// inspected. The thread count is only reported on Linux.
+pub(crate) fn register(meter: &Meter) {
+    let Ok(pid) = sysinfo::get_current_pid() else {
+        return;
+    };
+    let sampler = Sampler::new(pid);
+
+    let memory = sampler.clone();
+    meter
+        .i64_observable_up_down_counter("process.memory.usage")
+        .with_description("The amount of physical memory in use.")
+        .with_unit("By")
+        .with_callback(move |observer| {
+            if let Some(bytes) = memory.sample(ProcessRefreshKind::nothing().with_memory(), |p| {
+                i64::try_from(p.memory()).ok()
+            }) {
+                observer.observe(bytes, &[]);
+            }
+        })
+        .build();
+
+    let cpu = sampler.clone();
+    meter
+        .f64_observable_counter("process.cpu.time")
+        .with_description("Total CPU seconds consumed by the process.")
+        .with_unit("s")
+        .with_callback(move |observer| {
+            if let Some(seconds) = cpu.sample(ProcessRefreshKind::nothing().with_cpu(), |p| {
+                Some(cpu_seconds(p.accumulated_cpu_time()))
+            }) {
+                observer.observe(seconds, &[]);
+            }
+        })
+        .build();
+
+    meter
+        .i64_observable_up_down_counter("process.thread.count")
+        .with_description("Process threads count.")
+        .with_unit("{thread}")
+        .with_callback(move |observer| {
+            if let Some(threads) = sampler.sample(ProcessRefreshKind::nothing(), |p| {
+                p.tasks().and_then(|tasks| i64::try_from(tasks.len()).ok())
+            }) {
+                observer.observe(threads, &[]);
+            }
+        })
+        .build();
+}
+
+/// Converts CPU-milliseconds to seconds.
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "exact below 2^53 ms, about 285,000 CPU-years"
+)]
+fn cpu_seconds(millis: u64) -> f64 {
+    millis as f64 / 1000.0
+}
+
+/// Shared handle refreshing the current process on demand.
+#[derive(Clone)]
+struct Sampler {
+    system: Arc<Mutex<System>>,
+    pid: Pid,
+}
+
+impl Sampler {
+    fn new(pid: Pid) -> Self {
+        Self {
+            system: Arc::new(Mutex::new(System::new())),
+            pid,
+        }
+    }
+
+    /// Refreshes `kind` for the current process and reads a value from it.
+    fn sample<T>(
+        &self,
+        kind: ProcessRefreshKind,
+        read: impl FnOnce(&Process) -> Option<T>,
+    ) -> Option<T> {
+        let mut system = self.system.lock().ok()?;
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&[self.pid]), true, kind);
+        system.process(self.pid).and_then(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains;
+    use googletest::matchers::eq;
+    use googletest::matchers::gt;
+    use googletest::matchers::some;
+    use opentelemetry::metrics::MeterProvider as _;
+    use opentelemetry_sdk::error::OTelSdkResult;
+    use opentelemetry_sdk::metrics::PeriodicReader;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::metrics::Temporality;
+    use opentelemetry_sdk::metrics::data::ResourceMetrics;
+    use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+
+    use super::*;
+
+    /// Records the names of exported metrics.
+    #[derive(Clone, Default)]
+    struct NameExporter(Arc<Mutex<Vec<String>>>);
+
+    impl PushMetricExporter for NameExporter {
+        async fn export(&self, metrics: &ResourceMetrics) -> OTelSdkResult {
+            let names = metrics
+                .scope_metrics()
+                .flat_map(|scope| scope.metrics())
+                .map(|metric| metric.name().to_owned());
+            self.0.lock().expect("not poisoned").extend(names);
+            Ok(())
+        }
+
+        fn force_flush(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn temporality(&self) -> Temporality {
+            Temporality::Cumulative
+        }
+    }
+
+    fn current() -> Sampler {
+        Sampler::new(sysinfo::get_current_pid().expect("supported platform"))
+    }
+
+    #[gtest]
+    fn memory_usage_is_sampled() {
+        let bytes = current().sample(ProcessRefreshKind::nothing().with_memory(), |p| {
+            Some(p.memory())
+        });
+        expect_that!(bytes, some(gt(0)));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[gtest]
+    fn thread_count_is_sampled() {
+        let threads = current().sample(ProcessRefreshKind::nothing(), |p| {
+            p.tasks().map(|tasks| tasks.len())
+        });
+        expect_that!(threads, some(gt(0)));
+    }
+
+    #[gtest]
+    fn instruments_are_exported() {
+        let exporter = NameExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        register(&provider.meter("test"));
+        provider.force_flush().expect("metrics flushed");
+        let names = exporter.0.lock().expect("not poisoned").clone();
+        expect_that!(names, contains(eq("process.memory.usage")));
+        expect_that!(names, contains(eq("process.cpu.time")));
+    }
+
+    #[gtest]
+    fn cpu_time_is_reported_in_seconds() {
+        expect_that!(cpu_seconds(1500), eq(1.5));
+    }
+}
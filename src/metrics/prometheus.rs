@@ -1,4 +1,8 @@
 //! Prometheus configuration.
+#![allow(
+    deprecated,
+    reason = "the deprecated configuration is implemented here"
+)]
 
 use std::time::Duration;
 
@@ -14,6 +18,11 @@ use crate::error::ErrorKind;
 use crate::error::Result;
 
 /// Configuration for a Prometheus OTLP metrics exporter.
+///
+/// The exporter pushes OTLP/gRPC, which Prometheus itself does not accept.
+#[deprecated(
+    note = "pushes OTLP, not Prometheus; use `OtlpConfig` with `MetricExporter::Otlp` instead"
+)]
 #[derive(Debug, Clone, Builder)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct PrometheusConfig {
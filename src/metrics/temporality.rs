@@ -0,0 +1,103 @@
+//! Metric aggregation temporality.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Aggregation temporality requested from the OTLP metric exporter, as
+/// defined by `OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE`.
+///
+/// Delta-only backends such as Datadog need [`Self::Delta`].
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Temporality {
+    /// Measurements accumulate from a fixed start time.
+    #[default]
+    Cumulative,
+    /// Measurements reset after each export.
+    Delta,
+    /// Delta for synchronous counters and histograms, cumulative otherwise,
+    /// which bounds memory use.
+    #[cfg_attr(feature = "clap", value(name = "lowmemory"))]
+    LowMemory,
+}
+
+impl Temporality {
+    /// Returns the spec string representation of this temporality.
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::Cumulative => "cumulative",
+            Self::Delta => "delta",
+            Self::LowMemory => "lowmemory",
+        }
+    }
+}
+
+impl fmt::Display for Temporality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for Temporality {
+    type Err = ParseTemporalityError;
+
+    /// Parses a temporality case-insensitively, as the specification requires.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "cumulative" => Ok(Self::Cumulative),
+            "delta" => Ok(Self::Delta),
+            "lowmemory" => Ok(Self::LowMemory),
+            _ => Err(ParseTemporalityError(value.to_owned())),
+        }
+    }
+}
+
+impl From<Temporality> for opentelemetry_sdk::metrics::Temporality {
+    fn from(temporality: Temporality) -> Self {
+        match temporality {
+            Temporality::Cumulative => Self::Cumulative,
+            Temporality::Delta => Self::Delta,
+            Temporality::LowMemory => Self::LowMemory,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid metrics temporality: {0} (expected cumulative, delta or lowmemory)")]
+pub struct ParseTemporalityError(String);
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::ok;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("cumulative", Temporality::Cumulative)]
+    #[case("Delta", Temporality::Delta)]
+    #[case("LOWMEMORY", Temporality::LowMemory)]
+    fn spec_values_are_parsed(#[case] value: &str, #[case] expected: Temporality) {
+        assert_that!(value.parse::<Temporality>(), ok(eq(&expected)));
+    }
+
+    #[gtest]
+    fn unknown_value_is_rejected() {
+        expect_that!("sometimes".parse::<Temporality>(), err(anything()));
+    }
+
+    #[gtest]
+    fn default_is_cumulative() {
+        expect_that!(Temporality::default().as_str(), eq("cumulative"));
+    }
+}
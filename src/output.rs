@@ -0,0 +1,131 @@
+//! Destination of the formatted trace output.
+
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+
+/// Output sink of the formatting layer.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum OutputTarget {
+    /// Standard error.
+    #[default]
+    Stderr,
+    /// Standard output.
+    Stdout,
+    /// File opened in append mode, created if missing.
+    File(PathBuf),
+}
+
+impl OutputTarget {
+    /// Returns whether the target is an interactive terminal.
+    pub(crate) fn is_terminal(&self) -> bool {
+        match self {
+            Self::Stderr => io::stderr().is_terminal(),
+            Self::Stdout => io::stdout().is_terminal(),
+            Self::File(_) => false,
+        }
+    }
+
+    /// Builds the writer for the formatting layer.
+    pub(crate) fn make_writer(&self) -> Result<BoxMakeWriter, Error> {
+        match self {
+            Self::Stderr => Ok(BoxMakeWriter::new(io::stderr)),
+            Self::Stdout => Ok(BoxMakeWriter::new(io::stdout)),
+            Self::File(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|source| ErrorKind::OpenOutput {
+                        path: path.clone(),
+                        source,
+                    })?;
+                Ok(BoxMakeWriter::new(Arc::new(file)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for OutputTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stderr => "stderr".fmt(f),
+            Self::Stdout => "stdout".fmt(f),
+            Self::File(path) => path.display().fmt(f),
+        }
+    }
+}
+
+impl FromStr for OutputTarget {
+    type Err = ParseOutputTargetError;
+
+    /// Parses `stderr`, `stdout`, or any other value as a file path.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let target = match value {
+            "" => return Err(ParseOutputTargetError),
+            "stderr" => Self::Stderr,
+            "stdout" => Self::Stdout,
+            path => Self::File(PathBuf::from(path)),
+        };
+        Ok(target)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid output target: expected stderr, stdout or a file path")]
+pub struct ParseOutputTargetError;
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::ok;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("stderr", OutputTarget::Stderr)]
+    #[case("stdout", OutputTarget::Stdout)]
+    #[case("logs/app.log", OutputTarget::File(PathBuf::from("logs/app.log")))]
+    fn output_target_parses(#[case] value: &str, #[case] expected: OutputTarget) {
+        assert_that!(value.parse::<OutputTarget>(), ok(eq(&expected)));
+        assert_that!(expected.to_string(), eq(value));
+    }
+
+    #[gtest]
+    fn empty_output_target_is_rejected() {
+        expect_that!("".parse::<OutputTarget>(), err(anything()));
+    }
+
+    #[gtest]
+    fn file_target_is_created_in_append_mode() {
+        let path = std::env::temp_dir().join(format!("owiwi-output-{}.log", std::process::id()));
+        let target = OutputTarget::File(path.clone());
+        expect_that!(target.make_writer(), ok(anything()));
+        expect_that!(path.exists(), eq(true));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[gtest]
+    fn missing_parent_directory_is_an_error() {
+        let target = OutputTarget::File(PathBuf::from("/nonexistent/owiwi/app.log"));
+        expect_that!(target.make_writer(), err(anything()));
+    }
+}
@@ -1,40 +1,169 @@
 //! Tracing and telemetry initialization.
 
 use std::env::VarError;
-use std::error::Error as _;
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use bon::Builder;
 #[cfg(feature = "clap")]
 use clap_verbosity_flag::Verbosity;
 use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::tonic_types::transport::ClientTlsConfig;
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::trace::Sampler;
+use opentelemetry_sdk::trace::SdkTracer;
 use opentelemetry_sdk::trace::SdkTracerProvider;
+#[cfg(feature = "testing")]
+use opentelemetry_sdk::trace::SimpleSpanProcessor;
+use secrecy::SecretString;
 use tracing::Subscriber;
+use tracing::subscriber::NoSubscriber;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::filter::Directive;
 use tracing_subscriber::filter::EnvFilter;
+use tracing_subscriber::filter::FilterExt as _;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::DefaultFields;
+use tracing_subscriber::fmt::format::Format;
+use tracing_subscriber::fmt::format::FormatEvent;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::layer::Layer;
 use tracing_subscriber::layer::SubscriberExt as _;
 use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::registry::Registry;
 use tracing_subscriber::reload;
-use tracing_subscriber::util::SubscriberInitExt as _;
+use url::Url;
 
-use super::FilterHandle;
-#[cfg(feature = "clap")]
-use super::HELP_HEADING;
-use super::OwiwiGuard;
-use super::env_vars;
-use super::error::ErrorKind;
-use super::error::Result;
-use super::trace::TraceExporter;
+use crate::Compression;
 use crate::EventFormat;
+use crate::FilterHandle;
+#[cfg(feature = "clap")]
+use crate::HELP_HEADING;
+use crate::KeepaliveConfig;
+#[cfg(feature = "logs")]
+use crate::LogExporter;
+#[cfg(feature = "metrics")]
+use crate::MetricExporter;
+use crate::OutputTarget;
+use crate::OwiwiGuard;
+use crate::Propagator;
+use crate::Protocol;
+use crate::RetryConfig;
+#[cfg(feature = "sentry")]
+use crate::SentryConfig;
+use crate::SpanEvent;
+#[cfg(feature = "metrics")]
+use crate::Temporality;
+use crate::TimeFormat;
+use crate::TlsConfig;
+use crate::env_vars;
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use crate::format::Logfmt;
+use crate::format::LogfmtFields;
+use crate::format::fmt_span;
+use crate::format::resolve_ansi;
+use crate::guard::DEFAULT_SHUTDOWN_TIMEOUT;
+#[cfg(feature = "logs")]
+use crate::logs::SUPPRESSED_TARGETS;
+use crate::panic::PanicFlush;
+use crate::propagation;
+use crate::rate_limit::RateLimitFilter;
+use crate::redact::FieldRedaction;
+use crate::redact::RedactFields;
+use crate::redact::RedactJson;
+use crate::redact::RedactJsonFields;
+#[cfg(feature = "testing")]
+use crate::redact::RedactingExporter;
+use crate::startup::StartupConfig;
+use crate::trace::BatchConfig;
+use crate::trace::IdGenerator;
+use crate::trace::SpanLimits;
+use crate::trace::TraceExporter;
+use crate::trace::TracerSetup;
+use crate::trace::otlp;
+use crate::trace::otlp::endpoint_from_env;
+use crate::trace::parse_sampler;
+#[cfg(feature = "testing")]
+use crate::trace::with_exporting_processor;
+
+/// Caller-provided layers, applied directly on the registry.
+type UserLayers = Vec<Box<dyn Layer<Registry> + Send + Sync>>;
+
+/// Payload compression override, where `None` disables compression.
+///
+/// Aliased so that clap parses it as a single value rather than as a flag with
+/// an optional value.
+type CompressionOverride = Option<Compression>;
+
+/// Providers built from the configuration, before installation.
+struct Providers {
+    tracer_provider: SdkTracerProvider,
+    fallback: Option<TraceFallback>,
+    startup: StartupConfig,
+    #[cfg(feature = "logs")]
+    logger_provider: Option<opentelemetry_sdk::logs::SdkLoggerProvider>,
+    #[cfg(feature = "metrics")]
+    meter_provider: Option<opentelemetry_sdk::metrics::SdkMeterProvider>,
+}
+
+/// Fallback used when the trace backend cannot be built.
+#[derive(Debug)]
+enum TraceFallback {
+    /// Spans are exported to the console.
+    #[cfg(feature = "console")]
+    Console(Error),
+    /// Spans are not exported.
+    Disabled(Error),
+}
+
+impl TraceFallback {
+    /// Reports the fallback, if any, through `tracing`.
+    fn log(fallback: Option<Self>) {
+        match fallback {
+            #[cfg(feature = "console")]
+            Some(Self::Console(err)) => tracing::warn!(
+                error = %err,
+                "trace backend could not be built, exporting spans to the console instead"
+            ),
+            Some(Self::Disabled(err)) => tracing::error!(
+                error = %err,
+                "trace backend could not be built, spans are not exported"
+            ),
+            None => {}
+        }
+    }
+
+    /// Returns the error that caused the fallback.
+    fn into_error(self) -> Error {
+        match self {
+            #[cfg(feature = "console")]
+            Self::Console(err) => err,
+            Self::Disabled(err) => err,
+        }
+    }
+}
+
+/// Target prefix of the OpenTelemetry crates' internal logs.
+const SDK_LOG_TARGET: &str = "opentelemetry";
 
 /// Default service name
 const DEFAULT_SERVICE_NAME: &str = "unknown_service";
+/// Resource attribute key for the service namespace
+const SERVICE_NAMESPACE_KEY: &str = "service.namespace";
+/// Resource attribute key for the service version
+const SERVICE_VERSION_KEY: &str = "service.version";
+/// Resource attribute key for the service instance
+const SERVICE_INSTANCE_ID_KEY: &str = "service.instance.id";
 
 /// Configuration for initializing a [`tracing`] subscriber with OpenTelemetry.
 ///
+/// Use [`Owiwi::builder`] for programmatic configuration. Unset builder fields
+/// take the same values as [`Owiwi::default`].
+///
 /// When the `clap` feature is enabled, this type can be flattened into a CLI parser.
 #[must_use]
 #[derive(Clone, Debug, Builder)]
@@ -62,29 +191,280 @@ pub struct Owiwi {
         arg(
             name = "resource-attributes",
             long = "resource-attrs",
+            alias = "otel-resource-attributes",
             help = "Resource attributes (key=value,key=value)",
-            value_parser = env_vars::parse_key_values,
+            value_parser = env_vars::parse_key_value,
+            value_delimiter = ',',
             env = env_vars::OTEL_RESOURCE_ATTRIBUTES,
         )
     )]
     #[builder(default)]
     pub resource_attrs: Vec<(String, String)>,
 
+    /// Attributes set on every span, unlike resource attributes which
+    /// describe the process once.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "default-span-attributes",
+            long = "span-attrs",
+            help = "Attributes added to every span (key=value,key=value)",
+            value_parser = env_vars::parse_key_value,
+            value_delimiter = ',',
+        )
+    )]
+    #[builder(default)]
+    pub default_span_attributes: Vec<(String, String)>,
+
+    /// Service namespace, recorded as the `service.namespace` resource attribute.
+    /// Defaults to `OTEL_SERVICE_NAMESPACE`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long,
+            help = "Service namespace for telemetry",
+            env = env_vars::OTEL_SERVICE_NAMESPACE,
+        )
+    )]
+    #[builder(into)]
+    pub service_namespace: Option<String>,
+
+    /// Service version, recorded as the `service.version` resource attribute.
+    /// Defaults to `OTEL_SERVICE_VERSION`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long,
+            help = "Service version for telemetry",
+            env = env_vars::OTEL_SERVICE_VERSION,
+        )
+    )]
+    #[builder(into)]
+    pub service_version: Option<String>,
+
+    /// Service instance, recorded as the `service.instance.id` resource attribute.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, help = "Unique service instance ID for telemetry")
+    )]
+    #[builder(into)]
+    pub service_instance_id: Option<String>,
+
+    /// Generates a random UUID as `service.instance.id` when no instance ID
+    /// is set, either explicitly or through the resource attributes.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, help = "Generate a UUID as service instance ID when unset")
+    )]
+    #[builder(default)]
+    pub auto_instance_id: bool,
+
     /// Trace backend. Defaults to OTLP with spec values.
     #[cfg_attr(feature = "clap", arg(skip))]
     #[builder(default)]
     pub traces: TraceExporter,
 
+    /// Endpoint of the OTLP trace backend, overriding the configured one.
+    ///
+    /// Endpoint precedence is this value or `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`,
+    /// then `OTEL_EXPORTER_OTLP_ENDPOINT`, then the backend configuration.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-traces-endpoint",
+            long,
+            help = "OTLP traces endpoint",
+            env = env_vars::OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
+        )
+    )]
+    pub traces_endpoint: Option<Url>,
+
+    /// Headers added to every OTLP exporter, replacing backend headers with the same key.
+    /// Defaults to `OTEL_EXPORTER_OTLP_HEADERS`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-exporter-header",
+            long,
+            help = "OTLP exporter header (key=value, repeatable)",
+            value_parser = env_vars::parse_secret_key_value,
+            value_delimiter = ',',
+            env = env_vars::OTEL_EXPORTER_OTLP_HEADERS,
+            hide_env_values = true,
+        )
+    )]
+    #[builder(default)]
+    pub exporter_headers: Vec<(String, SecretString)>,
+
+    /// TLS files for gRPC exporters, replacing the backend TLS configuration.
+    #[cfg_attr(feature = "clap", command(flatten))]
+    #[builder(default)]
+    pub tls: TlsConfig,
+
     /// Span sampler. Defaults to the SDK default value
     /// when not set and `OTEL_TRACES_SAMPLER` is absent.
+    ///
+    /// Takes precedence over [`Self::traces_sampler`].
     #[cfg_attr(feature = "clap", arg(skip))]
     pub sampler: Option<Sampler>,
 
+    /// Samples root spans at this ratio, between `0.0` and `1.0`, and follows
+    /// the sampling decision of the parent for the others.
+    ///
+    /// This is `parentbased_traceidratio`, the usual choice in distributed
+    /// systems: remote parents are extracted by the
+    /// [`Self::propagators`](Owiwi#structfield.propagators), so downstream
+    /// services keep the traces sampled upstream. With the `none` propagator,
+    /// every incoming request starts a new root span.
+    ///
+    /// Takes precedence over [`Self::traces_sampler`].
+    #[cfg_attr(feature = "clap", arg(skip))]
+    pub sample_parent_based: Option<f64>,
+
+    /// Sampler name as defined by `OTEL_TRACES_SAMPLER`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-traces-sampler",
+            long,
+            help = "Trace sampler",
+            value_parser = clap::builder::PossibleValuesParser::new(crate::trace::SAMPLER_NAMES),
+            env = env_vars::OTEL_TRACES_SAMPLER,
+        )
+    )]
+    #[builder(into)]
+    pub traces_sampler: Option<String>,
+
+    /// Sampler argument as defined by `OTEL_TRACES_SAMPLER_ARG`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-traces-sampler-arg",
+            long,
+            help = "Trace sampler ratio between 0.0 and 1.0 (e.g. 0.1 for traceidratio)",
+            value_parser = crate::trace::parse_sampler_arg,
+            env = env_vars::OTEL_TRACES_SAMPLER_ARG,
+        )
+    )]
+    #[builder(into)]
+    pub traces_sampler_arg: Option<String>,
+
+    /// Span attribute that keeps a span whatever the sampler decides, when
+    /// `true`. Use `error` to keep failed requests at any sampling ratio.
+    ///
+    /// The attribute must be set when the span is created, or recorded
+    /// before the span is first entered.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-always-sample-attribute",
+            long,
+            help = "Span attribute that keeps a span regardless of sampling when true (e.g. error)"
+        )
+    )]
+    #[builder(into)]
+    pub always_sample_attribute: Option<String>,
+
+    /// Spans shorter than this are not exported. Each span is checked on its
+    /// own, so the children of a dropped span are kept when long enough.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-min-span-duration",
+            long,
+            help = "Drop spans shorter than this, in milliseconds or as a duration (e.g. 1ms)",
+            value_parser = env_vars::parse_timeout,
+        )
+    )]
+    pub min_span_duration: Option<Duration>,
+
+    /// Context propagators installed globally. Defaults to `OTEL_PROPAGATORS`,
+    /// then `tracecontext,baggage` as per the specification.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-propagators",
+            long,
+            help = "Context propagators (tracecontext, baggage, b3, b3multi, jaeger, xray, none)",
+            value_delimiter = ',',
+            env = env_vars::OTEL_PROPAGATORS,
+        )
+    )]
+    #[builder(default)]
+    pub propagators: Vec<Propagator>,
+
+    /// Forwards spans and events to Sentry alongside the OpenTelemetry export,
+    /// through the export filter.
+    #[cfg(feature = "sentry")]
+    #[cfg_attr(feature = "clap", arg(skip))]
+    pub sentry: Option<SentryConfig>,
+
+    /// Generates AWS X-Ray trace IDs and adds the X-Ray propagator, for export
+    /// through the AWS Distro for OpenTelemetry collector.
+    #[cfg(feature = "xray")]
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-xray",
+            long,
+            help = "Generate AWS X-Ray trace IDs and propagate X-Amzn-Trace-Id"
+        )
+    )]
+    #[builder(default)]
+    pub xray: bool,
+
+    /// Batch span processor tuning. Defaults to the SDK values.
+    #[cfg_attr(feature = "clap", command(flatten))]
+    #[builder(default)]
+    pub batch: BatchConfig,
+
+    /// Caps on the attributes, events and links per span. Defaults to the
+    /// SDK values.
+    #[cfg_attr(feature = "clap", command(flatten))]
+    #[builder(default)]
+    pub span_limits: SpanLimits,
+
+    /// Retry policy for failed span exports, replacing the backend policy.
+    /// No retry when unset.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    pub retry: Option<RetryConfig>,
+
+    /// HTTP/2 keepalive of the gRPC OTLP connections, replacing the backend
+    /// settings when any value is set.
+    #[cfg_attr(feature = "clap", command(flatten))]
+    #[builder(default)]
+    pub keepalive: KeepaliveConfig,
+
+    /// Log backend for `tracing` events. Defaults to no logs export.
+    #[cfg(feature = "logs")]
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[builder(default)]
+    pub logs: LogExporter,
+
     /// Metric backend. Defaults to no metrics export.
+    ///
+    /// With a backend, events with `monotonic_counter.*`, `counter.*`,
+    /// `histogram.*` or `gauge.*` fields are also recorded as metrics.
     #[cfg(feature = "metrics")]
     #[cfg_attr(feature = "clap", arg(skip))]
     #[builder(default)]
-    pub metrics: super::metrics::MetricExporter,
+    pub metrics: MetricExporter,
+
+    /// Endpoint of the OTLP metric backend, overriding the configured one.
+    ///
+    /// Endpoint precedence is this value or `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT`,
+    /// then `OTEL_EXPORTER_OTLP_ENDPOINT`, then the backend configuration.
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-metrics-endpoint",
+            long,
+            help = "OTLP metrics endpoint",
+            env = env_vars::OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
+        )
+    )]
+    pub metrics_endpoint: Option<Url>,
 
     /// Metrics exports interval
     #[cfg(feature = "metrics")]
@@ -99,369 +479,2584 @@ pub struct Owiwi {
 )]
     pub metrics_interval: Option<jiff::SignedDuration>,
 
-    /// Trace filter directives to overwrite the default level and `RUST_LOG`.
+    /// Explicit histogram bucket boundaries. Defaults to the SDK boundaries.
+    #[cfg(feature = "metrics")]
     #[cfg_attr(
         feature = "clap",
         arg(
-            long = "trace-directive",
-            help = "Trace filter",
+            name = "metrics-histogram-buckets",
+            long,
+            help = "Histogram bucket boundaries, strictly increasing (e.g. 0.005,0.01,0.1,1)",
             value_delimiter = ',',
-            num_args = 1..,
         )
     )]
     #[builder(default)]
-    pub tracing_directives: Vec<Directive>,
-    /// Filter directives for the OpenTelemetry export layer
-    /// Defaults to `info`.
+    pub metrics_histogram_buckets: Vec<f64>,
+
+    /// Transport protocol of the OTLP metrics, overriding the configured one.
+    ///
+    /// Protocol precedence is this value or `OTEL_EXPORTER_OTLP_METRICS_PROTOCOL`,
+    /// then the backend configuration, which defaults to `OTEL_EXPORTER_OTLP_PROTOCOL`.
+    #[cfg(feature = "metrics")]
     #[cfg_attr(
         feature = "clap",
         arg(
-            long = "export-directive",
-            help = "Export filter",
-            value_delimiter = ',',
-            num_args = 1..,
-            env = env_vars::OWIWI_EXPORT_LOG,
+            name = "otel-metrics-protocol",
+            long,
+            help = "OTLP metrics protocol (grpc, http/protobuf or http/json)",
+            env = env_vars::OTEL_EXPORTER_OTLP_METRICS_PROTOCOL,
         )
     )]
-    #[builder(default)]
-    pub export_directives: Vec<Directive>,
-    /// Event output format.
+    pub metrics_protocol: Option<Protocol>,
+
+    /// Aggregation temporality of the OTLP metrics, overriding the configured
+    /// one. Delta-only backends such as Datadog need `delta`.
+    #[cfg(feature = "metrics")]
     #[cfg_attr(
         feature = "clap",
         arg(
-            name = "event-format",
+            name = "otel-metrics-temporality",
             long,
             value_enum,
-            help = "Output format for trace events",
-            default_value_t = Default::default(),
+            ignore_case = true,
+            help = "Metrics aggregation temporality",
+            env = env_vars::OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE,
         )
     )]
-    #[builder(default)]
-    pub event_format: EventFormat,
-    /// Verbosity flags
-    #[cfg(feature = "clap")]
-    #[command(flatten)]
-    #[builder(default)]
-    pub verbose: Verbosity,
+    pub metrics_temporality: Option<Temporality>,
 
-    /// Disables all telemetry when `true`.
+    /// Reports process memory, CPU time and thread count with the metrics.
+    #[cfg(feature = "process-metrics")]
     #[cfg_attr(
         feature = "clap",
         arg(
-            name = "no-telemetry",
+            name = "metrics-process",
             long,
-            help = "Disable all telemetry",
-            env = env_vars::OTEL_SDK_DISABLED,
+            help = "Report process memory, CPU time and thread count",
+            env = env_vars::OWIWI_METRICS_PROCESS,
         )
     )]
     #[builder(default)]
-    pub no_telemetry: bool,
-}
-
-impl Default for Owiwi {
-    fn default() -> Self {
-        Self::builder().build()
-    }
-}
+    pub metrics_process: bool,
 
-impl Owiwi {
-    /// Initializes the tracing and optionally metrics provider.
-    ///
-    /// Sets up a [`tracing_subscriber`] registry with an OpenTelemetry layer,
-    /// error layer, and a formatting layer. It dispatches on [`Self::trace_exporter`]
-    /// and [`Self::metric_exporter`] to build the configured providers.
-    ///
-    /// Returns an [`OwiwiGuard`] that must be held for the lifetime of the program.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the exporter cannot be built, filter directives
-    /// are invalid, or a global subscriber is already set, or no tokio runtime is available.
-    ///
-    /// # Examples
+    /// Export timeout of the OTLP backends, overriding the configured ones.
     ///
-    /// ```no_run
-    /// use owiwi::Owiwi;
-    ///
-    /// let guard = Owiwi::builder()
-    ///     .service_name("owiwi-test")
-    ///     .build()
-    ///     .try_init()?;
-    /// # Ok::<_, owiwi::Error>(())
-    /// ```
-    pub fn try_init(mut self) -> Result<OwiwiGuard> {
-        if self.is_disabled() {
-            return self.noop();
-        }
-        let resource = self.build_resource();
+    /// Accepts integer milliseconds, as defined by the spec, or a duration
+    /// such as `10s`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-exporter-timeout",
+            long,
+            help = "OTLP export timeout, in milliseconds or as a duration (e.g. 10000, 10s)",
+            value_parser = env_vars::parse_timeout,
+            env = env_vars::OTEL_EXPORTER_OTLP_TIMEOUT,
+        )
+    )]
+    pub exporter_timeout: Option<Duration>,
 
-        #[cfg(feature = "metrics")]
-        let meter_provider = {
-            let interval = self
-                .metrics_interval
-                .take()
-                .map(|d| {
-                    std::time::Duration::try_from(d).map_err(|err| ErrorKind::ExporterConfig {
-                        reason: format!("invalid metrics interval: {err}"),
-                    })
-                })
-                .transpose()?;
-            std::mem::take(&mut self.metrics).build_provider(resource.clone(), interval)?
-        };
+    /// Payload compression of the OTLP backends, overriding the configured
+    /// ones. `Some(None)` disables compression.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-exporter-compression",
+            long,
+            help = "OTLP payload compression (gzip or none)",
+            value_parser = Compression::parse_optional,
+            env = env_vars::OTEL_EXPORTER_OTLP_COMPRESSION,
+        )
+    )]
+    pub exporter_compression: Option<CompressionOverride>,
 
-        let exporter = std::mem::take(&mut self.traces);
-        let tracer_provider = exporter.build_provider(resource, self.sampler.take())?;
+    /// Transport protocol of the OTLP backends, overriding the configured
+    /// ones. HTTP needs the `http` feature.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-exporter-protocol",
+            long,
+            help = "OTLP protocol (grpc, http/protobuf or http/json)",
+            env = env_vars::OTEL_EXPORTER_OTLP_PROTOCOL,
+        )
+    )]
+    pub exporter_protocol: Option<Protocol>,
 
-        self.finish(
-            tracer_provider,
-            #[cfg(feature = "metrics")]
-            meter_provider,
+    /// Fails initialization when the trace collector does not accept a TCP
+    /// connection within the exporter timeout.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-validate-endpoint",
+            long,
+            help = "Fail initialization when the trace collector is unreachable"
         )
-    }
+    )]
+    #[builder(default)]
+    pub validate_endpoint: bool,
 
-    /// Sets the global tracing subscriber and returns the provider guard.
-    fn finish(
-        self,
+    /// Exports spans to the console, with a warning, when the trace backend
+    /// cannot be built instead of failing initialization.
+    #[cfg(feature = "console")]
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-fallback-to-console",
+            long,
+            help = "Export spans to the console when the trace backend cannot be built"
+        )
+    )]
+    #[builder(default)]
+    pub fallback_to_console: bool,
+
+    /// Fails initialization when the trace backend cannot be built. Defaults
+    /// to `true`; otherwise an error is logged and spans are not exported.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-strict",
+            long,
+            help = "Fail initialization when the trace backend cannot be built",
+            default_value_t = true,
+            action = clap::ArgAction::Set,
+        )
+    )]
+    #[builder(default = true)]
+    pub strict: bool,
+
+    /// Records panics as error events and flushes the spans and logs before
+    /// the previously installed panic hook runs.
+    ///
+    /// Buffered telemetry is otherwise lost when the panic aborts the
+    /// process or the guard is never dropped.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-panic-hook",
+            long,
+            help = "Record panics and flush telemetry before the panic hook runs"
+        )
+    )]
+    #[builder(default)]
+    pub install_panic_hook: bool,
+    /// Adds the [`ErrorLayer`] capturing [`tracing_error::SpanTrace`]s.
+    /// Defaults to `true`.
+    ///
+    /// Disabling it saves the per-span bookkeeping in latency-sensitive
+    /// services, at the cost of empty span traces in errors.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-error-layer",
+            help = "Capture span traces for errors",
+            default_value_t = true,
+            action = clap::ArgAction::Set,
+        )
+    )]
+    #[builder(default = true)]
+    pub error_layer: bool,
+
+    /// Logs the effective telemetry settings as a single `info` event once
+    /// the subscriber is installed, with secrets redacted.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-log-config",
+            long,
+            help = "Log the effective telemetry settings on startup"
+        )
+    )]
+    #[builder(default)]
+    pub log_config: bool,
+
+    /// Maximum time allowed to flush and shut down the providers.
+    /// Defaults to 5 seconds.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-shutdown-timeout",
+            long,
+            help = "Telemetry shutdown timeout (e.g. 5s, 500ms)",
+            env = env_vars::OWIWI_SHUTDOWN_TIMEOUT,
+        )
+    )]
+    pub shutdown_timeout: Option<jiff::SignedDuration>,
+
+    /// Trace filter directives overriding `RUST_LOG` and the verbosity flags.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-directive",
+            help = "Trace filter",
+            value_delimiter = ',',
+            num_args = 1..,
+        )
+    )]
+    #[builder(default)]
+    pub tracing_directives: Vec<Directive>,
+
+    /// File of trace filter directives, one per line, applied before
+    /// [`Self::tracing_directives`]. Blank lines and lines starting with `#`
+    /// are skipped.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-directives-file",
+            help = "File of trace filter directives, one per line"
+        )
+    )]
+    #[builder(into)]
+    pub tracing_directives_file: Option<PathBuf>,
+
+    /// Targets shown at the current level, hiding every other target.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-include",
+            help = "Only show these targets (e.g. my_crate)",
+            value_delimiter = ',',
+            num_args = 1..,
+        )
+    )]
+    #[builder(default)]
+    pub trace_include: Vec<String>,
+
+    /// Targets hidden from the formatted output.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-exclude",
+            help = "Hide these targets (e.g. hyper,h2)",
+            value_delimiter = ',',
+            num_args = 1..,
+        )
+    )]
+    #[builder(default)]
+    pub trace_exclude: Vec<String>,
+
+    /// Fails initialization when `RUST_LOG` is not valid Unicode or holds
+    /// invalid directives. Defaults to `true`; otherwise the problem is
+    /// reported on stderr and `RUST_LOG` is ignored.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-strict-env-filter",
+            long,
+            help = "Fail initialization when RUST_LOG cannot be parsed",
+            default_value_t = true,
+            action = clap::ArgAction::Set,
+        )
+    )]
+    #[builder(default = true)]
+    pub strict_env_filter: bool,
+
+    /// Level of the OpenTelemetry SDK's own diagnostics in the formatted
+    /// output. When unset, they follow the other filter sources.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "otel-log-level",
+            help = "Level of the OpenTelemetry SDK diagnostics (off, error, warn, info, debug)",
+            env = env_vars::OTEL_LOG_LEVEL,
+        )
+    )]
+    pub sdk_log_level: Option<LevelFilter>,
+    /// Filter directives for the OpenTelemetry export layer, independent of the
+    /// terminal filter. Defaults to `info`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "export-directive",
+            help = "Export filter",
+            value_delimiter = ',',
+            num_args = 1..,
+            env = env_vars::OWIWI_EXPORT_LOG,
+        )
+    )]
+    #[builder(default)]
+    pub export_directives: Vec<Directive>,
+    /// Event output format. Defaults to `OWIWI_TRACE_FORMAT`, then to
+    /// [`EventFormat::Full`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "event-format",
+            long,
+            value_enum,
+            help = "Output format for trace events",
+            default_value_t = Default::default(),
+            env = env_vars::OWIWI_TRACE_FORMAT,
+        )
+    )]
+    #[builder(default = EventFormat::from_env())]
+    pub event_format: EventFormat,
+    /// Timestamps of the formatted output.
+    #[cfg_attr(feature = "clap", command(flatten))]
+    #[builder(default)]
+    pub time: TimeFormat,
+    /// Span lifecycle events printed by the formatted output. None by default.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-span-events",
+            long,
+            help = "Span lifecycle events to print (new, enter, exit, close, active, full)",
+            value_delimiter = ',',
+        )
+    )]
+    #[builder(default)]
+    pub span_events: Vec<SpanEvent>,
+
+    /// Field name patterns whose values are replaced by `[REDACTED]` in the
+    /// formatted output, exported spans and logs, and Sentry events and
+    /// breadcrumbs.
+    ///
+    /// A `*` at the start or end of a pattern matches any suffix or prefix,
+    /// e.g. `*_token`. Names are compared case-insensitively. Sentry
+    /// transactions are not redacted.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-redact-fields",
+            long,
+            help = "Field names to redact, with * at the start or end (e.g. password,authorization,*_token)",
+            value_delimiter = ',',
+        )
+    )]
+    #[builder(default)]
+    pub redact_fields: Vec<String>,
+    /// Forces ANSI colors on or off. Defaults to `NO_COLOR` and terminal detection.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-ansi",
+            help = "Force ANSI colors on or off (defaults to terminal detection)"
+        )
+    )]
+    pub ansi: Option<bool>,
+    /// Shows or hides the thread names, overriding the event format default.
+    /// Does not apply to [`EventFormat::Logfmt`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-thread-names",
+            help = "Show or hide thread names (defaults to the event format)"
+        )
+    )]
+    pub thread_names: Option<bool>,
+    /// Shows or hides the thread IDs, overriding the event format default.
+    /// Does not apply to [`EventFormat::Logfmt`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-thread-ids",
+            help = "Show or hide thread IDs (defaults to the event format)"
+        )
+    )]
+    pub thread_ids: Option<bool>,
+    /// Shows or hides the source file, overriding the event format default.
+    /// Does not apply to [`EventFormat::Logfmt`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-file",
+            help = "Show or hide source files (defaults to the event format)"
+        )
+    )]
+    pub file: Option<bool>,
+    /// Shows or hides the source line number, overriding the event format
+    /// default. Does not apply to [`EventFormat::Logfmt`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-line-number",
+            help = "Show or hide source line numbers (defaults to the event format)"
+        )
+    )]
+    pub line_number: Option<bool>,
+    /// Destination of the formatted output. Defaults to stderr.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long = "trace-output",
+            help = "Trace output: stderr, stdout, or a file path (appended)",
+            default_value_t = Default::default(),
+        )
+    )]
+    #[builder(default)]
+    pub output: OutputTarget,
+    /// Flattens event fields into the top-level JSON object.
+    /// Only applies to [`EventFormat::Json`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, help = "Flatten event fields into the JSON object")
+    )]
+    #[builder(default)]
+    pub json_flatten_event: bool,
+    /// Includes the current span in JSON events. Defaults to `true`.
+    /// Only applies to [`EventFormat::Json`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            long,
+            help = "Include the current span in JSON events",
+            default_value_t = true,
+            action = clap::ArgAction::Set,
+        )
+    )]
+    #[builder(default = true)]
+    pub json_current_span: bool,
+    /// Includes every span in scope, root first, in JSON events.
+    /// Only applies to [`EventFormat::Json`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, help = "Include the list of spans in scope in JSON events")
+    )]
+    #[builder(default)]
+    pub json_span_list: bool,
+    /// Verbosity flags
+    #[cfg(feature = "clap")]
+    #[command(flatten)]
+    #[builder(default)]
+    pub verbose: Verbosity,
+
+    /// Maximum number of events exported per second. Excess events are dropped
+    /// from export and summarized periodically. Unlimited when unset.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-max-events-per-second",
+            long,
+            help = "Maximum number of events exported per second"
+        )
+    )]
+    pub max_events_per_second: Option<NonZeroU32>,
+
+    /// Maximum number of spans exported per second. Unlimited when unset.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-max-spans-per-second",
+            long,
+            help = "Maximum number of spans exported per second"
+        )
+    )]
+    pub max_spans_per_second: Option<NonZeroU32>,
+
+    /// Disables all telemetry when `true`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "no-telemetry",
+            long,
+            help = "Disable all telemetry",
+            value_parser = parse_sdk_disabled,
+            env = env_vars::OTEL_SDK_DISABLED,
+        )
+    )]
+    #[builder(default)]
+    pub no_telemetry: bool,
+
+    /// Disables tracing entirely when `true`, including the formatted output.
+    ///
+    /// Unlike [`Self::no_telemetry`], no layer is installed and no provider is built.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "trace-disabled",
+            long,
+            help = "Disable all tracing output and export",
+            env = env_vars::OWIWI_DISABLED,
+        )
+    )]
+    #[builder(default)]
+    pub disabled: bool,
+}
+
+impl Default for Owiwi {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Owiwi {
+    /// Initializes the tracing and optionally metrics provider.
+    ///
+    /// Sets up a [`tracing_subscriber`] registry with an OpenTelemetry layer,
+    /// error layer, and a formatting layer. It dispatches on [`Self::trace_exporter`]
+    /// and [`Self::metric_exporter`] to build the configured providers.
+    ///
+    /// Returns an [`OwiwiGuard`] that must be held for the lifetime of the program.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the exporter cannot be built, filter directives
+    /// are invalid, or a global subscriber is already set, or no tokio runtime is available.
+    /// The gRPC exporters need a runtime, so call it inside `#[tokio::main]` or
+    /// after entering one with [`Runtime::enter`](tokio::runtime::Runtime::enter).
+    /// Unless telemetry is disabled, it fails without one.
+    /// Calling it again after a successful initialization returns an error for which
+    /// [`Error::is_already_initialized`](crate::Error::is_already_initialized) is `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use owiwi::Owiwi;
+    ///
+    /// let guard = Owiwi::builder()
+    ///     .service_name("owiwi-test")
+    ///     .build()
+    ///     .try_init()?;
+    /// # Ok::<_, owiwi::Error>(())
+    /// ```
+    pub fn try_init(self) -> Result<OwiwiGuard> {
+        self.init(None, Vec::new())
+    }
+
+    /// Checks the configuration the way [`Self::try_init`] does, without
+    /// installing a global subscriber or connecting to the collectors.
+    ///
+    /// The filters, timeouts, sampler, TLS files and exporters are built, and
+    /// the exporter is returned instead of the console or disabled fallback.
+    /// The collector reachability check of [`Self::validate_endpoint`] and the
+    /// output file are skipped. Outside a tokio runtime, a temporary one is used,
+    /// so it can be called anywhere and repeatedly, e.g. in CI or tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error [`Self::try_init`] would return for this configuration,
+    /// except for an already installed subscriber.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use owiwi::Owiwi;
+    ///
+    /// Owiwi::builder()
+    ///     .service_name("owiwi-test")
+    ///     .traces_sampler("traceidratio")
+    ///     .traces_sampler_arg("0.5")
+    ///     .build()
+    ///     .validate()?;
+    /// # Ok::<_, owiwi::Error>(())
+    /// ```
+    pub fn validate(mut self) -> Result<()> {
+        if self.disabled {
+            return Ok(());
+        }
+        self.resolve_filter(None)?;
+        if self.is_disabled() {
+            return Ok(());
+        }
+        self.shutdown_timeout()?;
+        self.export_filter_layer()?;
+        self.validate_endpoint = false;
+
+        let runtime = match tokio::runtime::Handle::try_current() {
+            Ok(_) => None,
+            Err(_) => Some(
+                tokio::runtime::Builder::new_current_thread()
+                    .build()
+                    .map_err(|_| ErrorKind::NoTokioRuntime)?,
+            ),
+        };
+        let _entered = runtime.as_ref().map(tokio::runtime::Runtime::enter);
+        let providers = self.build_providers()?;
+        providers
+            .fallback
+            .map_or(Ok(()), |fallback| Err(fallback.into_error()))
+    }
+
+    /// Builds the tracer provider and a tracer named after the service,
+    /// without installing a global subscriber.
+    ///
+    /// Only the resource and trace settings are used, for embedding in an
+    /// existing subscriber, e.g. with `tracing_opentelemetry::layer().with_tracer(tracer)`.
+    /// The global context propagators are set as by [`Self::try_init`]. When
+    /// telemetry is disabled, the tracer records nothing. The caller shuts the
+    /// provider down.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the exporter cannot be built or no tokio runtime
+    /// is available while telemetry is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use owiwi::Owiwi;
+    /// use tracing_subscriber::layer::SubscriberExt as _;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (provider, tracer) = Owiwi::builder()
+    ///     .service_name("embedded")
+    ///     .build()
+    ///     .build_tracer()?;
+    /// let subscriber =
+    ///     tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    /// tracing::subscriber::with_default(subscriber, || tracing::info!("traced"));
+    /// provider.shutdown()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build_tracer(mut self) -> Result<(SdkTracerProvider, SdkTracer)> {
+        if self.disabled || self.is_disabled() {
+            self.resolve_service_name();
+            let provider = SdkTracerProvider::builder()
+                .with_sampler(Sampler::AlwaysOff)
+                .build();
+            let tracer = provider.tracer(self.service_name);
+            return Ok((provider, tracer));
+        }
+        // Only the trace backend is built.
+        #[cfg(feature = "logs")]
+        {
+            self.logs = LogExporter::None;
+        }
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics = MetricExporter::None;
+        }
+        let providers = self.build_providers()?;
+        self.set_text_map_propagator();
+        TraceFallback::log(providers.fallback);
+        let tracer = providers.tracer_provider.tracer(self.service_name);
+        Ok((providers.tracer_provider, tracer))
+    }
+
+    /// Initializes like [`Self::try_init`], using `filter` verbatim for the
+    /// formatted output.
+    ///
+    /// `RUST_LOG`, [`Self::tracing_directives`] and the verbosity flags are
+    /// ignored. The export filter is unaffected.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::try_init`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use owiwi::Owiwi;
+    /// use tracing_subscriber::EnvFilter;
+    ///
+    /// let filter = EnvFilter::new("warn,my_crate=trace");
+    /// let guard = Owiwi::default().try_init_with_filter(filter)?;
+    /// # Ok::<_, owiwi::Error>(())
+    /// ```
+    pub fn try_init_with_filter(self, filter: EnvFilter) -> Result<OwiwiGuard> {
+        self.init(Some(filter), Vec::new())
+    }
+
+    /// Initializes like [`Self::try_init`], adding `layers` to the subscriber.
+    ///
+    /// The layers see every span and event, regardless of the output and
+    /// export filters. They are also installed when telemetry export is
+    /// disabled, but not when all tracing is disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::try_init`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use owiwi::Owiwi;
+    /// use tracing_subscriber::Layer as _;
+    /// use tracing_subscriber::filter::LevelFilter;
+    ///
+    /// let audit = tracing_subscriber::fmt::layer()
+    ///     .json()
+    ///     .with_filter(LevelFilter::WARN);
+    /// let guard = Owiwi::default().try_init_with_layers(vec![Box::new(audit)])?;
+    /// # Ok::<_, owiwi::Error>(())
+    /// ```
+    pub fn try_init_with_layers(
+        self,
+        layers: Vec<Box<dyn Layer<Registry> + Send + Sync>>,
+    ) -> Result<OwiwiGuard> {
+        self.init(None, layers)
+    }
+
+    /// Builds the providers and installs the subscriber, with an optional
+    /// explicit filter replacing [`Self::filter_layer`] and additional layers.
+    fn init(mut self, filter: Option<EnvFilter>, layers: UserLayers) -> Result<OwiwiGuard> {
+        if self.disabled {
+            return self.install_disabled();
+        }
+        if self.is_disabled() {
+            return self.noop(filter, layers);
+        }
+        let providers = self.build_providers()?;
+
+        #[cfg(feature = "process-metrics")]
+        if self.metrics_process
+            && let Some(provider) = &providers.meter_provider
+        {
+            use opentelemetry::metrics::MeterProvider as _;
+            crate::metrics::process::register(&provider.meter(env!("CARGO_PKG_NAME")));
+        }
+
+        let log_config = self.log_config;
+        let guard = self.finish(
+            filter,
+            layers,
+            providers.tracer_provider,
+            #[cfg(feature = "logs")]
+            providers.logger_provider,
+            #[cfg(feature = "metrics")]
+            providers.meter_provider,
+        )?;
+        if log_config {
+            providers.startup.log();
+        }
+        TraceFallback::log(providers.fallback);
+        Ok(guard)
+    }
+
+    /// Builds the metric, log and trace providers, applying the exporter
+    /// overrides, without installing anything globally.
+    ///
+    /// The gRPC exporters need a tokio runtime, so it fails outside one.
+    fn build_providers(&mut self) -> Result<Providers> {
+        if tokio::runtime::Handle::try_current().is_err() {
+            return Err(ErrorKind::NoTokioRuntime.into());
+        }
+        let resource = self.build_resource();
+        let headers = self.exporter_headers();
+        let tls = self.tls.build()?;
+
+        #[cfg(feature = "metrics")]
+        let meter_provider = {
+            let interval = self
+                .metrics_interval
+                .take()
+                .map(|d| {
+                    std::time::Duration::try_from(d).map_err(|err| ErrorKind::ExporterConfig {
+                        reason: format!("invalid metrics interval: {err}"),
+                    })
+                })
+                .transpose()?;
+            let mut metrics = std::mem::take(&mut self.metrics);
+            metrics.extend_headers(&headers);
+            if let Some(timeout) = self.exporter_timeout {
+                metrics.set_timeout(timeout);
+            }
+            if let Some(compression) = self.exporter_compression {
+                metrics.set_compression(compression);
+            }
+            if let Some(tls) = &tls {
+                metrics.set_tls_config(tls.clone());
+            }
+            if let Some(insecure) = self.tls.insecure {
+                metrics.set_insecure(insecure);
+            }
+            if !self.keepalive.is_unset() {
+                metrics.set_keepalive(self.keepalive);
+            }
+            if let Some(temporality) = self.metrics_temporality {
+                metrics.set_temporality(temporality);
+            }
+            if let Some(protocol) = self
+                .metrics_protocol
+                .or_else(|| {
+                    std::env::var(env_vars::OTEL_EXPORTER_OTLP_METRICS_PROTOCOL)
+                        .ok()
+                        .and_then(|s| s.parse().ok())
+                })
+                .or(self.exporter_protocol)
+            {
+                metrics.set_protocol(protocol);
+            }
+            if let Some(endpoint) = self
+                .metrics_endpoint
+                .take()
+                .or_else(|| endpoint_from_env(env_vars::OTEL_EXPORTER_OTLP_METRICS_ENDPOINT))
+            {
+                metrics.set_endpoint(endpoint);
+            }
+            metrics.build_provider(resource.clone(), interval, &self.metrics_histogram_buckets)?
+        };
+
+        #[cfg(feature = "logs")]
+        let logger_provider = {
+            let mut logs = std::mem::take(&mut self.logs);
+            logs.extend_headers(&headers);
+            if let Some(timeout) = self.exporter_timeout {
+                logs.set_timeout(timeout);
+            }
+            if let Some(compression) = self.exporter_compression {
+                logs.set_compression(compression);
+            }
+            if let Some(protocol) = self.exporter_protocol {
+                logs.set_protocol(protocol);
+            }
+            if let Some(tls) = &tls {
+                logs.set_tls_config(tls.clone());
+            }
+            if let Some(insecure) = self.tls.insecure {
+                logs.set_insecure(insecure);
+            }
+            if !self.keepalive.is_unset() {
+                logs.set_keepalive(self.keepalive);
+            }
+            logs.build_provider_with(resource.clone(), self.redaction())?
+        };
+
+        let (tracer_provider, fallback, startup) = self.trace_provider(resource, &headers, tls)?;
+
+        Ok(Providers {
+            tracer_provider,
+            fallback,
+            startup,
+            #[cfg(feature = "logs")]
+            logger_provider,
+            #[cfg(feature = "metrics")]
+            meter_provider,
+        })
+    }
+
+    /// Builds the tracer provider of the configured trace backend, applying
+    /// the endpoint, header, TLS, retry and timeout overrides.
+    fn trace_provider(
+        &mut self,
+        resource: Resource,
+        headers: &[(String, SecretString)],
+        tls: Option<ClientTlsConfig>,
+    ) -> Result<(SdkTracerProvider, Option<TraceFallback>, StartupConfig)> {
+        let mut exporter = std::mem::take(&mut self.traces);
+        // Named before the overrides below turn vendor backends into OTLP.
+        let name = exporter.name();
+        #[cfg(feature = "uptrace")]
+        exporter.resolve_dsn()?;
+        if let Some(endpoint) = self
+            .traces_endpoint
+            .take()
+            .or_else(|| endpoint_from_env(env_vars::OTEL_EXPORTER_OTLP_TRACES_ENDPOINT))
+        {
+            exporter.set_endpoint(endpoint);
+        }
+        // Checked before the overrides below turn vendor backends into OTLP.
+        match self.tls.insecure {
+            Some(insecure) => exporter.set_insecure(insecure)?,
+            None => exporter.check_endpoint()?,
+        }
+        exporter.extend_headers(headers);
+        if let Some(tls) = tls {
+            exporter.set_tls_config(tls);
+        }
+        if let Some(retry) = self.retry {
+            exporter.set_retry(retry);
+        }
+        if !self.keepalive.is_unset() {
+            exporter.set_keepalive(self.keepalive);
+        }
+        if let Some(timeout) = self.exporter_timeout {
+            exporter.set_timeout(timeout);
+        }
+        if let Some(compression) = self.exporter_compression {
+            exporter.set_compression(compression);
+        }
+        if let Some(protocol) = self.exporter_protocol {
+            exporter.set_protocol(protocol);
+        }
+        if self.validate_endpoint
+            && let Some((endpoint, timeout)) = exporter.otlp_target()
+        {
+            otlp::check_reachable(endpoint, timeout)?;
+        }
+        let startup = self.startup_config(name, &exporter, headers);
+        let (provider, fallback) = self.build_tracer_provider(exporter, resource)?;
+        Ok((provider, fallback, startup))
+    }
+
+    /// Returns the effective settings of the trace backend `exporter`, which
+    /// was configured as the backend `name`.
+    fn startup_config(
+        &self,
+        name: &'static str,
+        exporter: &TraceExporter,
+        headers: &[(String, SecretString)],
+    ) -> StartupConfig {
+        let (sampler, sampler_arg) = if self.sampler.is_some() {
+            ("custom".to_owned(), None)
+        } else if let Some(ratio) = self.sample_parent_based {
+            (
+                "parentbased_traceidratio".to_owned(),
+                Some(ratio.to_string()),
+            )
+        } else {
+            let sampler = self
+                .traces_sampler
+                .clone()
+                .or_else(|| std::env::var(env_vars::OTEL_TRACES_SAMPLER).ok())
+                .unwrap_or_else(|| "parentbased_always_on".to_owned());
+            let arg = self
+                .traces_sampler_arg
+                .clone()
+                .or_else(|| std::env::var(env_vars::OTEL_TRACES_SAMPLER_ARG).ok());
+            (sampler, arg)
+        };
+        StartupConfig {
+            exporter: name,
+            endpoint: exporter.otlp_target().map(|(endpoint, _)| endpoint.clone()),
+            headers: headers.iter().map(|(name, _)| name.clone()).collect(),
+            sampler,
+            sampler_arg,
+            event_format: self.event_format,
+        }
+    }
+
+    /// Builds the tracer provider for `exporter`.
+    ///
+    /// A failure yields a console provider with [`Self::fallback_to_console`],
+    /// or a provider exporting nothing when [`Self::strict`] is off, along
+    /// with the original error.
+    fn build_tracer_provider(
+        &mut self,
+        exporter: TraceExporter,
+        resource: Resource,
+    ) -> Result<(SdkTracerProvider, Option<TraceFallback>)> {
+        #[cfg(feature = "console")]
+        let exporter = match exporter {
+            TraceExporter::Console => self.console_exporter(),
+            exporter => exporter,
+        };
+        let sampler = self.build_sampler()?;
+        let batch = std::mem::take(&mut self.batch);
+        let setup = self.tracer_setup();
+        let redaction = self.redaction();
+        let err = match exporter.build_provider_with(
+            resource.clone(),
+            sampler.clone(),
+            batch.clone(),
+            setup.clone(),
+            redaction.clone(),
+        ) {
+            Ok(provider) => return Ok((provider, None)),
+            Err(err) => err,
+        };
+        #[cfg(feature = "console")]
+        if self.fallback_to_console {
+            let provider = self
+                .console_exporter()
+                .build_provider_with(resource, sampler, batch, setup, redaction)?;
+            return Ok((provider, Some(TraceFallback::Console(err))));
+        }
+        if self.strict {
+            return Err(err);
+        }
+        let provider = SdkTracerProvider::builder().with_resource(resource).build();
+        Ok((provider, Some(TraceFallback::Disabled(err))))
+    }
+
+    /// Returns the console backend printing spans like the events: OTLP JSON
+    /// for [`EventFormat::Json`], so that stdout stays parseable, and the
+    /// stdout exporter format otherwise.
+    #[cfg(feature = "console")]
+    const fn console_exporter(&self) -> TraceExporter {
+        match self.event_format {
+            EventFormat::Json => TraceExporter::ConsoleJson,
+            _ => TraceExporter::Console,
+        }
+    }
+
+    /// Initializes tracing with a caller-provided span exporter.
+    ///
+    /// The configured backend and metrics are ignored. Spans are exported
+    /// synchronously as they end, which makes this suitable for asserting on
+    /// instrumentation with [`InMemoryCollector`](crate::testing::InMemoryCollector).
+    /// Unlike [`Self::try_init`], no tokio runtime is required.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the filter directives or sampler are invalid,
+    /// or a global subscriber is already set.
+    #[cfg(feature = "testing")]
+    pub fn try_init_with_exporter<E>(mut self, exporter: E) -> Result<OwiwiGuard>
+    where
+        E: opentelemetry_sdk::trace::SpanExporter + 'static,
+    {
+        let resource = self.build_resource();
+        let sampler = self.build_sampler()?;
+        let setup = self.tracer_setup();
+        let min_span_duration = setup.min_span_duration;
+        let builder = setup
+            .apply(SdkTracerProvider::builder(), sampler)
+            .with_resource(resource);
+        let processor =
+            SimpleSpanProcessor::new(RedactingExporter::new(exporter, self.redaction()));
+        let builder = with_exporting_processor(builder, processor, min_span_duration);
+
+        self.finish(
+            None,
+            Vec::new(),
+            builder.build(),
+            #[cfg(feature = "logs")]
+            None,
+            #[cfg(feature = "metrics")]
+            None,
+        )
+    }
+
+    /// Returns the tracer provider settings shared by every backend.
+    fn tracer_setup(&self) -> TracerSetup {
+        TracerSetup {
+            ids: self.id_generator(),
+            limits: self.span_limits,
+            span_attributes: self
+                .default_span_attributes
+                .iter()
+                .map(|(key, value)| opentelemetry::KeyValue::new(key.clone(), value.clone()))
+                .collect(),
+            always_sample_attribute: self.always_sample_attribute.clone(),
+            min_span_duration: self.min_span_duration,
+        }
+    }
+
+    /// Returns the trace and span ID generator.
+    #[cfg_attr(
+        not(feature = "xray"),
+        allow(clippy::unused_self, reason = "only X-Ray changes the generator")
+    )]
+    const fn id_generator(&self) -> IdGenerator {
+        #[cfg(feature = "xray")]
+        if self.xray {
+            return IdGenerator::Xray;
+        }
+        IdGenerator::Random
+    }
+
+    /// Returns the redaction of [`Self::redact_fields`].
+    fn redaction(&self) -> FieldRedaction {
+        FieldRedaction::new(&self.redact_fields)
+    }
+
+    /// Returns the context propagators, with X-Ray added in X-Ray mode.
+    fn propagators(&self) -> Vec<Propagator> {
+        #[cfg(feature = "xray")]
+        if self.xray {
+            return propagation::with_xray(&self.propagators);
+        }
+        self.propagators.clone()
+    }
+
+    /// Installs the context propagators as the global text map propagator.
+    fn set_text_map_propagator(&self) {
+        opentelemetry::global::set_text_map_propagator(propagation::text_map_propagator(
+            &self.propagators(),
+        ));
+    }
+
+    /// Sets the global tracing subscriber and returns the provider guard.
+    fn finish(
+        self,
+        filter: Option<EnvFilter>,
+        layers: UserLayers,
+        tracer_provider: SdkTracerProvider,
+        #[cfg(feature = "logs")] logger_provider: Option<
+            opentelemetry_sdk::logs::SdkLoggerProvider,
+        >,
+        #[cfg(feature = "metrics")] meter_provider: Option<
+            opentelemetry_sdk::metrics::SdkMeterProvider,
+        >,
+    ) -> Result<OwiwiGuard> {
+        let shutdown_timeout = self.shutdown_timeout()?;
+        let tracer = tracer_provider.tracer(self.service_name.clone());
+
+        let (filter_layer, reload_handle) = self.resolve_filter(filter).map(reload::Layer::new)?;
+        let export_filter = self.export_filter_layer()?;
+        let rate_limit =
+            RateLimitFilter::new(self.max_events_per_second, self.max_spans_per_second);
+
+        let otel_layer = tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(export_filter.and(rate_limit));
+
+        #[cfg(feature = "logs")]
+        let log_layer = match &logger_provider {
+            Some(provider) => Some(
+                opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge::new(provider)
+                    .with_filter(self.log_export_filter_layer()?),
+            ),
+            None => None,
+        };
+        #[cfg(not(feature = "logs"))]
+        let log_layer = tracing_subscriber::layer::Identity::new();
+
+        #[cfg(feature = "metrics")]
+        let metrics_layer = meter_provider.as_ref().map(crate::metrics::event_layer);
+        #[cfg(not(feature = "metrics"))]
+        let metrics_layer = tracing_subscriber::layer::Identity::new();
+
+        let fmt_layer = self.fmt_layer()?.with_filter(filter_layer);
+
+        #[cfg(feature = "sentry")]
+        let sentry = self
+            .sentry
+            .as_ref()
+            .map(|config| config.init(self.service_version.as_deref(), self.redaction()))
+            .transpose()?;
+        #[cfg(feature = "sentry")]
+        let sentry_layer = match &sentry {
+            Some(_) => Some(
+                ::sentry::integrations::tracing::layer().with_filter(self.export_filter_layer()?),
+            ),
+            None => None,
+        };
+        #[cfg(not(feature = "sentry"))]
+        let sentry_layer = tracing_subscriber::layer::Identity::new();
+
+        let guard = OwiwiGuard {
+            tracer_provider,
+            #[cfg(feature = "logs")]
+            logger_provider,
+            #[cfg(feature = "metrics")]
+            meter_provider,
+            #[cfg(feature = "sentry")]
+            sentry,
+            filter_handle: Some(FilterHandle::new(reload_handle)),
+            shutdown_timeout,
+            service_name: self.service_name.clone(),
+        };
+        let subscriber = tracing_subscriber::registry()
+            .with(layers)
+            .with(otel_layer)
+            .with(log_layer)
+            .with(metrics_layer)
+            .with(sentry_layer)
+            .with(self.error_layer.then(ErrorLayer::default))
+            .with(fmt_layer);
+        // Not `SubscriberInitExt::try_init`, which also installs a `log`
+        // logger when `tracing-subscriber` has its `tracing-log` feature.
+        if tracing::subscriber::set_global_default(subscriber).is_err() {
+            // The providers are already running, stop their export tasks.
+            let _ = guard.shutdown();
+            return Err(ErrorKind::AlreadyInitialized.into());
+        }
+        #[cfg(feature = "log")]
+        crate::log_bridge::install();
+
+        self.set_text_map_propagator();
+
+        #[cfg(feature = "metrics")]
+        if let Some(meter_provider) = &guard.meter_provider {
+            opentelemetry::global::set_meter_provider(meter_provider.clone());
+        }
+
+        if self.install_panic_hook {
+            PanicFlush {
+                tracer_provider: guard.tracer_provider.clone(),
+                #[cfg(feature = "logs")]
+                logger_provider: guard.logger_provider.clone(),
+            }
+            .install();
+        }
+
+        Ok(guard)
+    }
+
+    /// Builds an OpenTelemetry [`Resource`].
+    ///
+    /// Duplicate attribute keys resolve last-wins. The service name and version
+    /// always take precedence over the generic resource attributes.
+    ///
+    /// The resolved service name is written back to [`Self::service_name`].
+    fn build_resource(&mut self) -> Resource {
+        self.resolve_service_name();
+        let service_name = self.service_name.clone();
+
+        let attrs = {
+            let taken = std::mem::take(&mut self.resource_attrs);
+            if taken.is_empty() {
+                std::env::var(env_vars::OTEL_RESOURCE_ATTRIBUTES)
+                    .ok()
+                    .and_then(|raw| env_vars::parse_key_values(&raw).ok())
+                    .unwrap_or_default()
+            } else {
+                taken
+            }
+        };
+
+        let has_instance_id = attrs.iter().any(|(k, _)| k == SERVICE_INSTANCE_ID_KEY);
+        let mut builder = Resource::builder().with_attributes(
+            attrs
+                .into_iter()
+                .map(|(k, v)| opentelemetry::KeyValue::new(k, v)),
+        );
+        let namespace = self
+            .service_namespace
+            .take()
+            .or_else(|| non_empty_var(env_vars::OTEL_SERVICE_NAMESPACE));
+        if let Some(namespace) = namespace {
+            builder = builder.with_attribute(opentelemetry::KeyValue::new(
+                SERVICE_NAMESPACE_KEY,
+                namespace,
+            ));
+        }
+        // Kept for the Sentry release.
+        if self.service_version.is_none() {
+            self.service_version = non_empty_var(env_vars::OTEL_SERVICE_VERSION);
+        }
+        if let Some(version) = self.service_version.clone() {
+            builder =
+                builder.with_attribute(opentelemetry::KeyValue::new(SERVICE_VERSION_KEY, version));
+        }
+        let instance_id = self.service_instance_id.take().or_else(|| {
+            (self.auto_instance_id && !has_instance_id).then(|| uuid::Uuid::new_v4().to_string())
+        });
+        if let Some(instance_id) = instance_id {
+            builder = builder.with_attribute(opentelemetry::KeyValue::new(
+                SERVICE_INSTANCE_ID_KEY,
+                instance_id,
+            ));
+        }
+        builder.with_service_name(service_name).build()
+    }
+
+    /// Resolves an empty service name from `OTEL_SERVICE_NAME`, then the default.
+    fn resolve_service_name(&mut self) {
+        let name = std::mem::take(&mut self.service_name);
+        self.service_name = service_name_or(name, std::env::var(env_vars::OTEL_SERVICE_NAME).ok());
+    }
+
+    /// Takes the exporter headers, falling back to `OTEL_EXPORTER_OTLP_HEADERS`.
+    fn exporter_headers(&mut self) -> Vec<(String, SecretString)> {
+        let headers = std::mem::take(&mut self.exporter_headers);
+        if !headers.is_empty() {
+            return headers;
+        }
+        std::env::var(env_vars::OTEL_EXPORTER_OTLP_HEADERS)
+            .ok()
+            .and_then(|raw| {
+                raw.split(',')
+                    .map(env_vars::parse_secret_key_value)
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Resolves the provider shutdown timeout.
+    fn shutdown_timeout(&self) -> Result<Duration> {
+        let Some(timeout) = self.shutdown_timeout else {
+            return Ok(DEFAULT_SHUTDOWN_TIMEOUT);
+        };
+        Duration::try_from(timeout).map_err(|err| {
+            ErrorKind::ExporterConfig {
+                reason: format!("invalid shutdown timeout: {err}"),
+            }
+            .into()
+        })
+    }
+
+    /// Resolves the span sampler from the configuration.
+    fn build_sampler(&mut self) -> Result<Option<Sampler>> {
+        if let Some(sampler) = self.sampler.take() {
+            return Ok(Some(sampler));
+        }
+        if let Some(ratio) = self.sample_parent_based {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(ErrorKind::InvalidSamplerRatio { ratio }.into());
+            }
+            return Ok(Some(Sampler::ParentBased(Box::new(
+                Sampler::TraceIdRatioBased(ratio),
+            ))));
+        }
+        self.traces_sampler
+            .as_deref()
+            .map(|name| parse_sampler(name, self.traces_sampler_arg.as_deref()))
+            .transpose()
+    }
+
+    /// Creates a formatting layer writing to the configured output.
+    fn fmt_layer<S>(&self) -> Result<impl Layer<S>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        self.time.validate()?;
+        let ansi = resolve_ansi(self.ansi, self.output.is_terminal());
+        let writer = self.output.make_writer()?;
+        let layer: Box<dyn Layer<S> + Send + Sync> = match self.event_format {
+            EventFormat::Compact => {
+                let format = self
+                    .source_details(self.event_format.compact())
+                    .with_ansi(ansi);
+                self.text_layer(format, false, writer, ansi)
+            }
+            EventFormat::Full => {
+                let format = self
+                    .source_details(self.event_format.full())
+                    .with_ansi(ansi);
+                self.text_layer(format, true, writer, ansi)
+            }
+            EventFormat::Pretty => {
+                let format = self
+                    .source_details(self.event_format.pretty())
+                    .with_ansi(ansi);
+                self.text_layer(format, true, writer, ansi)
+            }
+            EventFormat::Json => {
+                let format = self
+                    .source_details(self.event_format.json())
+                    .flatten_event(self.json_flatten_event)
+                    .with_current_span(self.json_current_span)
+                    .with_span_list(self.json_span_list);
+                let layer = tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_span_events(fmt_span(&self.span_events))
+                    .fmt_fields(RedactJsonFields::new(self.redaction()));
+                let redaction = self.redaction();
+                if self.time.is_enabled(true) {
+                    let format = format.with_timer(self.time.clone());
+                    Box::new(layer.event_format(RedactJson::new(format, redaction)))
+                } else {
+                    Box::new(layer.event_format(RedactJson::new(format.without_time(), redaction)))
+                }
+            }
+            EventFormat::Logfmt => {
+                let time = self.time.is_enabled(true).then(|| self.time.clone());
+                let redaction = self.redaction();
+                Box::new(
+                    tracing_subscriber::fmt::layer()
+                        .with_writer(writer)
+                        .with_ansi(false)
+                        .with_span_events(fmt_span(&self.span_events))
+                        .fmt_fields(RedactFields::new(LogfmtFields::SPAN, redaction.clone()))
+                        .event_format(Logfmt::new(time, redaction)),
+                )
+            }
+        };
+
+        Ok(layer)
+    }
+
+    /// Applies the thread and source location overrides over the format
+    /// defaults.
+    fn source_details<L, T>(&self, mut format: Format<L, T>) -> Format<L, T> {
+        if let Some(thread_names) = self.thread_names {
+            format = format.with_thread_names(thread_names);
+        }
+        if let Some(thread_ids) = self.thread_ids {
+            format = format.with_thread_ids(thread_ids);
+        }
+        if let Some(file) = self.file {
+            format = format.with_file(file);
+        }
+        if let Some(line_number) = self.line_number {
+            format = format.with_line_number(line_number);
+        }
+        format
+    }
+
+    /// Creates a text formatting layer, applying the timestamp configuration
+    /// over the format default.
+    fn text_layer<S, L, T>(
+        &self,
+        format: Format<L, T>,
+        time_by_default: bool,
+        writer: BoxMakeWriter,
+        ansi: bool,
+    ) -> Box<dyn Layer<S> + Send + Sync>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+        Format<L, TimeFormat>: FormatEvent<S, RedactFields<DefaultFields>> + Send + Sync + 'static,
+        Format<L, ()>: FormatEvent<S, RedactFields<DefaultFields>> + Send + Sync + 'static,
+    {
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(ansi)
+            .with_span_events(fmt_span(&self.span_events))
+            .fmt_fields(RedactFields::new(DefaultFields::new(), self.redaction()));
+        if self.time.is_enabled(time_by_default) {
+            Box::new(layer.event_format(format.with_timer(self.time.clone())))
+        } else {
+            Box::new(layer.event_format(format.without_time()))
+        }
+    }
+
+    /// Returns the explicit filter if given, the configured one otherwise.
+    fn resolve_filter(&self, filter: Option<EnvFilter>) -> Result<EnvFilter> {
+        filter.map_or_else(|| self.filter_layer(), Ok)
+    }
+
+    /// Creates a filter layer from the configuration.
+    ///
+    /// See [`Self::layered_filter`] for the precedence rules.
+    fn filter_layer(&self) -> Result<EnvFilter> {
+        let rust_log = self.rust_log(std::env::var(EnvFilter::DEFAULT_ENV))?;
+        self.layered_filter(rust_log.as_deref())
+    }
+
+    /// Returns the `RUST_LOG` directives, if set.
+    ///
+    /// An unreadable or invalid value is an error with
+    /// [`Self::strict_env_filter`], and is reported and ignored otherwise.
+    fn rust_log(&self, var: std::result::Result<String, VarError>) -> Result<Option<String>> {
+        let err = match var {
+            Ok(directives) => match EnvFilter::builder().parse(&directives) {
+                Ok(_) => return Ok(Some(directives)),
+                Err(source) => Error::from(ErrorKind::ParseFilter(source)),
+            },
+            Err(VarError::NotPresent) => return Ok(None),
+            Err(source) => ErrorKind::ParseDirective { source }.into(),
+        };
+        if self.strict_env_filter {
+            return Err(err);
+        }
+        eprintln!("ignoring {}: {err}", EnvFilter::DEFAULT_ENV);
+        Ok(None)
+    }
+
+    /// Layers the filter sources, each one overriding the previous:
+    ///
+    /// 1. `RUST_LOG` when set, the default level otherwise. The default level is
+    ///    the verbosity level with the `clap` feature, `INFO` without it.
+    /// 2. The verbosity flags, when given, replacing the global level.
+    /// 3. [`Self::trace_include`] and [`Self::trace_exclude`].
+    /// 4. [`Self::sdk_log_level`] for the `opentelemetry*` targets.
+    /// 5. [`Self::tracing_directives_file`].
+    /// 6. [`Self::tracing_directives`].
+    fn layered_filter(&self, rust_log: Option<&str>) -> Result<EnvFilter> {
+        let mut layer = match rust_log {
+            Some(directives) => EnvFilter::builder().parse(directives)?,
+            None => EnvFilter::builder().parse(self.default_level().to_string())?,
+        };
+        #[cfg(feature = "clap")]
+        if rust_log.is_some() && self.verbose.is_present() {
+            layer = layer.add_directive(self.default_level().into());
+        }
+        if !self.trace_include.is_empty() {
+            let level = self.global_level(rust_log);
+            layer = layer.add_directive(LevelFilter::OFF.into());
+            for target in &self.trace_include {
+                layer = layer.add_directive(format!("{target}={level}").parse()?);
+            }
+        }
+        for target in &self.trace_exclude {
+            layer = layer.add_directive(format!("{target}=off").parse()?);
+        }
+        if let Some(level) = self.sdk_log_level() {
+            layer = layer.add_directive(format!("{SDK_LOG_TARGET}={level}").parse()?);
+        }
+        if let Some(path) = &self.tracing_directives_file {
+            for directive in read_directives(path)? {
+                layer = layer.add_directive(directive);
+            }
+        }
+        for directive in &self.tracing_directives {
+            layer = layer.add_directive(directive.clone());
+        }
+        Ok(layer)
+    }
+
+    /// Returns the level of the targets without a directive of their own: the
+    /// verbosity flags when given, then the bare level of `RUST_LOG`, then the
+    /// default level.
+    fn global_level(&self, rust_log: Option<&str>) -> LevelFilter {
+        #[cfg(feature = "clap")]
+        if self.verbose.is_present() {
+            return self.default_level();
+        }
+        rust_log
+            .and_then(|directives| {
+                directives
+                    .split(',')
+                    .rev()
+                    .find_map(|directive| directive.trim().parse().ok())
+            })
+            .unwrap_or_else(|| self.default_level())
+    }
+
+    /// Returns the level of the SDK diagnostics, read from `OTEL_LOG_LEVEL`
+    /// without the `clap` feature.
+    #[cfg_attr(
+        feature = "clap",
+        allow(
+            clippy::missing_const_for_fn,
+            reason = "clap reads the environment variable"
+        )
+    )]
+    fn sdk_log_level(&self) -> Option<LevelFilter> {
+        #[cfg(not(feature = "clap"))]
+        if self.sdk_log_level.is_none() {
+            return std::env::var(env_vars::OTEL_LOG_LEVEL)
+                .ok()
+                .and_then(|level| level.parse().ok());
+        }
+        self.sdk_log_level
+    }
+
+    /// Returns the global level used when no other source sets one.
+    #[cfg_attr(
+        not(feature = "clap"),
+        allow(
+            clippy::unused_self,
+            clippy::missing_const_for_fn,
+            reason = "only the verbosity flags change the level"
+        )
+    )]
+    fn default_level(&self) -> LevelFilter {
+        #[cfg(feature = "clap")]
+        let level = self.verbose.tracing_level_filter();
+        #[cfg(not(feature = "clap"))]
+        let level = LevelFilter::INFO;
+        level
+    }
+
+    fn export_filter_layer(&self) -> Result<EnvFilter> {
+        if !self.export_directives.is_empty() {
+            let mut filter = EnvFilter::builder().parse("")?;
+            for directive in &self.export_directives {
+                filter = filter.add_directive(directive.clone());
+            }
+
+            return Ok(filter);
+        }
+
+        #[cfg(not(feature = "clap"))]
+        if let Ok(val) = std::env::var(env_vars::OWIWI_EXPORT_LOG) {
+            return Ok(EnvFilter::try_new(val)?);
+        }
+
+        Ok(EnvFilter::try_new("info")?)
+    }
+
+    /// Creates the export filter for log records, silencing the exporter's
+    /// own dependencies.
+    #[cfg(feature = "logs")]
+    fn log_export_filter_layer(&self) -> Result<EnvFilter> {
+        let mut filter = self.export_filter_layer()?;
+        for target in SUPPRESSED_TARGETS {
+            filter = filter.add_directive(format!("{target}=off").parse()?);
+        }
+        Ok(filter)
+    }
+
+    /// Returns whether telemetry is disabled by [`Self::no_telemetry`] or `OTEL_SDK_DISABLED`.
+    fn is_disabled(&self) -> bool {
+        self.no_telemetry || sdk_disabled(std::env::var(env_vars::OTEL_SDK_DISABLED).ok())
+    }
+
+    fn noop(mut self, filter: Option<EnvFilter>, layers: UserLayers) -> Result<OwiwiGuard> {
+        self.resolve_service_name();
+        let (filter_layer, reload_handle) = self.resolve_filter(filter).map(reload::Layer::new)?;
+        let fmt_layer = self.fmt_layer()?.with_filter(filter_layer);
+        let subscriber = tracing_subscriber::registry().with(layers).with(fmt_layer);
+        tracing::subscriber::set_global_default(subscriber)
+            .map_err(|_| ErrorKind::AlreadyInitialized)?;
+        #[cfg(feature = "log")]
+        crate::log_bridge::install();
+        let mut guard = OwiwiGuard::noop();
+        guard.filter_handle = Some(FilterHandle::new(reload_handle));
+        guard.service_name = self.service_name;
+        Ok(guard)
+    }
+
+    /// Installs a subscriber discarding all spans and events.
+    fn install_disabled(mut self) -> Result<OwiwiGuard> {
+        self.resolve_service_name();
+        tracing::subscriber::set_global_default(NoSubscriber::default())
+            .map_err(|_| ErrorKind::AlreadyInitialized)?;
+        let mut guard = OwiwiGuard::noop();
+        guard.service_name = self.service_name;
+        Ok(guard)
+    }
+}
+
+/// Returns whether an `OTEL_SDK_DISABLED` value disables the SDK, which per the
+/// specification only `true`, case-insensitively, does.
+fn sdk_disabled(value: Option<String>) -> bool {
+    value.is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+}
+
+/// Parses the `--no-telemetry` value, read from `OTEL_SDK_DISABLED`, with the
+/// rule of [`sdk_disabled`] rather than clap's falsey values.
+#[cfg(feature = "clap")]
+fn parse_sdk_disabled(value: &str) -> std::result::Result<bool, std::convert::Infallible> {
+    Ok(sdk_disabled(Some(value.to_owned())))
+}
+
+/// Returns the value of the environment variable `var`, unless unset or empty.
+fn non_empty_var(var: &str) -> Option<String> {
+    std::env::var(var).ok().filter(|value| !value.is_empty())
+}
+
+/// Returns the configured service name, or the `OTEL_SERVICE_NAME` value, or
+/// the default name.
+fn service_name_or(name: String, env: Option<String>) -> String {
+    if !name.is_empty() {
+        return name;
+    }
+    env.filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_owned())
+}
+
+/// Reads a directives file, one directive per line, skipping blank lines and
+/// `#` comments.
+fn read_directives(path: &Path) -> Result<Vec<Directive>> {
+    let contents = std::fs::read_to_string(path).map_err(|source| ErrorKind::ReadDirectives {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_directive_lines(&contents).map_err(|(line, source)| {
+        ErrorKind::ParseDirectivesFile {
+            path: path.to_path_buf(),
+            line,
+            source,
+        }
+        .into()
+    })
+}
+
+/// Parses one directive per line, returning the 1-based number of the first
+/// invalid line on failure.
+fn parse_directive_lines(
+    contents: &str,
+) -> std::result::Result<Vec<Directive>, (usize, tracing_subscriber::filter::ParseError)> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| line.parse().map_err(|err| (number, err)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::elements_are;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::none;
+    use googletest::matchers::ok;
+    use googletest::matchers::pat;
+    use googletest::matchers::some;
+    use opentelemetry::Key;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[gtest]
+    fn build_resource_sets_service_name() {
+        let mut owiwi = Owiwi::builder().service_name("test_service").build();
+        let resource = owiwi.build_resource();
+        expect_that!(resource, pat!(Resource { .. }));
+        expect_that!(resource.is_empty(), eq(false));
+        let key = Key::new("service.name");
+        let name = resource.get(&key).map(|v| String::from(v.as_str()));
+        expect_that!(name, some(eq("test_service")));
+    }
+
+    #[gtest]
+    fn filter_layer_with_directives() {
+        let owiwi = Owiwi::builder()
+            .tracing_directives(vec![
+                "info".parse().expect("valid directive"),
+                "my_crate=debug".parse().expect("valid directive"),
+            ])
+            .build();
+
+        expect_that!(owiwi.filter_layer(), ok(anything()));
+    }
+
+    #[gtest]
+    fn build_resource_with_custom_attributes() {
+        let mut owiwi = Owiwi::builder()
+            .resource_attrs(vec![
+                ("env".to_owned(), "staging".to_owned()),
+                ("region".to_owned(), "us-east-1".to_owned()),
+            ])
+            .build();
+
+        let resource = owiwi.build_resource();
+        let env_key = Key::new("env");
+        let env_val = resource.get(&env_key).map(|v| String::from(v.as_str()));
+        expect_that!(env_val, some(eq("staging")));
+    }
+
+    #[gtest]
+    fn build_resource_duplicate_keys_last_wins() {
+        let mut owiwi = Owiwi::builder()
+            .resource_attrs(vec![
+                ("env".to_owned(), "staging".to_owned()),
+                ("env".to_owned(), "production".to_owned()),
+            ])
+            .service_version("1.2.3")
+            .build();
+
+        let resource = owiwi.build_resource();
+        let env_val = resource
+            .get(&Key::new("env"))
+            .map(|v| String::from(v.as_str()));
+        expect_that!(env_val, some(eq("production")));
+        let version = resource
+            .get(&Key::new("service.version"))
+            .map(|v| String::from(v.as_str()));
+        expect_that!(version, some(eq("1.2.3")));
+    }
+
+    /// Returns a backend whose exporter cannot be built.
+    fn broken_backend() -> TraceExporter {
+        TraceExporter::Otlp(
+            crate::OtlpConfig::builder()
+                .endpoint("http://collector:4317".parse().expect("to be valid"))
+                .timeout(Duration::ZERO)
+                .headers(vec![("invalid key".to_owned(), "value".to_owned())])
+                .build(),
+        )
+    }
+
+    #[gtest]
+    fn build_tracer_requires_a_runtime() {
+        expect_that!(Owiwi::default().build_tracer(), err(anything()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[gtest]
+    async fn build_tracer_returns_a_recording_tracer() {
+        use opentelemetry::trace::Span as _;
+        use opentelemetry::trace::Tracer as _;
+
+        let (_provider, tracer) = Owiwi::builder()
+            .service_name("embedded")
+            .build()
+            .build_tracer()
+            .expect("tracer to be built");
+        let span = tracer.start("work");
+        expect_that!(span.is_recording(), eq(true));
+    }
+
+    #[gtest]
+    fn build_tracer_is_a_no_op_without_telemetry() {
+        use opentelemetry::trace::Span as _;
+        use opentelemetry::trace::Tracer as _;
+
+        // No runtime is needed, as no exporter is built.
+        let (_provider, tracer) = Owiwi::builder()
+            .service_name("embedded")
+            .no_telemetry(true)
+            .build()
+            .build_tracer()
+            .expect("tracer to be built");
+        let span = tracer.start("work");
+        expect_that!(span.is_recording(), eq(false));
+    }
+
+    #[gtest]
+    fn broken_backend_fails_by_default() {
+        let mut owiwi = Owiwi::default();
+        let resource = owiwi.build_resource();
+        expect_that!(
+            owiwi.build_tracer_provider(broken_backend(), resource),
+            err(anything())
+        );
+    }
+
+    #[cfg(feature = "console")]
+    #[gtest]
+    fn broken_backend_falls_back_to_console() {
+        let mut owiwi = Owiwi::builder().fallback_to_console(true).build();
+        let resource = owiwi.build_resource();
+        let result = owiwi.build_tracer_provider(broken_backend(), resource);
+        expect_that!(
+            result,
+            ok((anything(), some(pat!(TraceFallback::Console(anything())))))
+        );
+    }
+
+    #[cfg(feature = "console")]
+    #[rstest]
+    #[case(EventFormat::Json, true)]
+    #[case(EventFormat::Pretty, false)]
+    #[case(EventFormat::Logfmt, false)]
+    fn console_spans_follow_the_json_event_format(
+        #[case] event_format: EventFormat,
+        #[case] json: bool,
+    ) {
+        let owiwi = Owiwi::builder().event_format(event_format).build();
+        assert_that!(
+            matches!(owiwi.console_exporter(), TraceExporter::ConsoleJson),
+            eq(json)
+        );
+    }
+
+    #[gtest]
+    fn broken_backend_is_disabled_when_not_strict() {
+        let mut owiwi = Owiwi::builder().strict(false).build();
+        let resource = owiwi.build_resource();
+        let result = owiwi.build_tracer_provider(broken_backend(), resource);
+        expect_that!(
+            result,
+            ok((anything(), some(pat!(TraceFallback::Disabled(anything())))))
+        );
+    }
+
+    /// Returns the `service.instance.id` of a freshly built resource.
+    fn instance_id(mut owiwi: Owiwi) -> Option<String> {
+        owiwi
+            .build_resource()
+            .get(&Key::new(SERVICE_INSTANCE_ID_KEY))
+            .map(|v| v.to_string())
+    }
+
+    #[gtest]
+    fn instance_id_is_unset_by_default() {
+        expect_that!(
+            instance_id(Owiwi::builder().build()),
+            googletest::matchers::none()
+        );
+    }
+
+    #[gtest]
+    fn auto_instance_id_is_a_uuid() {
+        let id = instance_id(Owiwi::builder().auto_instance_id(true).build());
+        expect_that!(
+            id.as_deref().map(uuid::Uuid::parse_str),
+            some(ok(anything()))
+        );
+    }
+
+    #[gtest]
+    fn explicit_instance_id_wins_over_auto() {
+        let owiwi = Owiwi::builder()
+            .service_instance_id("worker-7")
+            .auto_instance_id(true)
+            .build();
+        expect_that!(instance_id(owiwi), some(eq("worker-7")));
+    }
+
+    #[gtest]
+    fn instance_id_attribute_wins_over_auto() {
+        let owiwi = Owiwi::builder()
+            .resource_attrs(vec![(
+                SERVICE_INSTANCE_ID_KEY.to_owned(),
+                "host-42".to_owned(),
+            )])
+            .auto_instance_id(true)
+            .build();
+        expect_that!(instance_id(owiwi), some(eq("host-42")));
+    }
+
+    #[cfg(feature = "clap")]
+    #[derive(Debug, clap::Parser)]
+    struct Cli {
+        #[command(flatten)]
+        owiwi: Owiwi,
+    }
+
+    #[cfg(feature = "clap")]
+    #[gtest]
+    fn resource_attributes_flag_is_repeatable() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from([
+            "test",
+            "--resource-attrs",
+            "env=staging,region=eu",
+            "--otel-resource-attributes",
+            "team=core",
+        ])
+        .expect("valid arguments");
+        expect_that!(cli.owiwi.resource_attrs.len(), eq(3));
+    }
+
+    #[cfg(feature = "clap")]
+    #[gtest]
+    fn propagators_flag_is_comma_separated() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--otel-propagators", "tracecontext,baggage"])
+            .expect("valid arguments");
+        expect_that!(
+            cli.owiwi.propagators,
+            googletest::matchers::elements_are![
+                eq(&Propagator::TraceContext),
+                eq(&Propagator::Baggage)
+            ]
+        );
+    }
+
+    #[gtest]
+    fn shutdown_timeout_defaults_when_unset() {
+        let owiwi = Owiwi::default();
+        expect_that!(owiwi.shutdown_timeout(), ok(eq(&DEFAULT_SHUTDOWN_TIMEOUT)));
+    }
+
+    #[gtest]
+    fn shutdown_timeout_rejects_negative_duration() {
+        let owiwi = Owiwi::builder()
+            .shutdown_timeout(jiff::SignedDuration::from_secs(-1))
+            .build();
+        expect_that!(owiwi.shutdown_timeout(), err(anything()));
+    }
+
+    #[gtest]
+    fn startup_config_reports_the_backend_and_sampler() {
+        let owiwi = Owiwi::builder()
+            .traces_sampler("traceidratio")
+            .traces_sampler_arg("0.5")
+            .build();
+        let exporter = TraceExporter::Otlp(
+            crate::OtlpConfig::builder()
+                .endpoint("http://collector:4317".parse().expect("valid URL"))
+                .timeout(Duration::from_secs(10))
+                .build(),
+        );
+        let startup = owiwi.startup_config(
+            exporter.name(),
+            &exporter,
+            &[("x-api-key".to_owned(), "secret".into())],
+        );
+        expect_that!(startup.exporter, eq("otlp"));
+        expect_that!(
+            startup.endpoint.as_ref().map(url::Url::as_str),
+            some(eq("http://collector:4317/"))
+        );
+        expect_that!(startup.headers, elements_are![eq("x-api-key")]);
+        expect_that!(startup.sampler, eq("traceidratio"));
+        expect_that!(startup.sampler_arg, some(eq("0.5")));
+    }
+
+    #[cfg(feature = "honeycomb")]
+    #[tokio::test]
+    #[gtest]
+    async fn startup_config_names_vendor_backends_with_overrides() {
+        let mut owiwi = Owiwi::builder()
+            .traces(TraceExporter::Honeycomb(
+                crate::HoneycombConfig::builder()
+                    .endpoint("https://api.honeycomb.io".parse().expect("valid URL"))
+                    .api_key("key".into())
+                    .timeout(Duration::from_secs(10))
+                    .build(),
+            ))
+            .exporter_timeout(Duration::from_secs(5))
+            .build();
+        let (_, _, startup) = owiwi
+            .trace_provider(
+                Resource::builder().build(),
+                &[("x-team".to_owned(), "checkout".into())],
+                None,
+            )
+            .expect("tracer provider");
+        expect_that!(startup.exporter, eq("honeycomb"));
+        expect_that!(startup.headers, elements_are![eq("x-team")]);
+    }
+
+    #[gtest]
+    fn build_sampler_from_name_and_arg() {
+        let mut owiwi = Owiwi::builder()
+            .traces_sampler("parentbased_traceidratio")
+            .traces_sampler_arg("0.5")
+            .build();
+        expect_that!(owiwi.build_sampler(), ok(some(anything())));
+    }
+
+    #[gtest]
+    fn build_sampler_rejects_invalid_ratio() {
+        let mut owiwi = Owiwi::builder()
+            .traces_sampler("traceidratio")
+            .traces_sampler_arg("2")
+            .build();
+        expect_that!(owiwi.build_sampler(), err(anything()));
+    }
 
-        tracer_provider: SdkTracerProvider,
-        #[cfg(feature = "metrics")] meter_provider: Option<
-            opentelemetry_sdk::metrics::SdkMeterProvider,
-        >,
-    ) -> Result<OwiwiGuard> {
-        if tokio::runtime::Handle::try_current().is_err() {
-            return Err(ErrorKind::NoTokioRuntime.into());
-        }
-        let tracer = tracer_provider.tracer(self.service_name.clone());
+    #[rstest]
+    #[case(0.25, true)]
+    #[case(1.5, false)]
+    #[case(-0.1, false)]
+    fn sample_parent_based_takes_a_ratio(#[case] ratio: f64, #[case] valid: bool) {
+        let mut owiwi = Owiwi::builder()
+            .sample_parent_based(ratio)
+            .traces_sampler("always_off")
+            .build();
+        assert_that!(owiwi.build_sampler().is_ok(), eq(valid));
+        let exporter = broken_backend();
+        let startup = owiwi.startup_config(exporter.name(), &exporter, &[]);
+        assert_that!(startup.sampler, eq("parentbased_traceidratio"));
+        assert_that!(startup.sampler_arg, some(eq(&ratio.to_string())));
+    }
 
-        let (filter_layer, reload_handle) = self.filter_layer().map(reload::Layer::new)?;
-        let export_filter = self.export_filter_layer()?;
+    #[gtest]
+    fn validate_accepts_the_default_configuration() {
+        expect_that!(Owiwi::default().validate(), ok(anything()));
+    }
 
-        let otel_layer = tracing_opentelemetry::layer()
-            .with_tracer(tracer)
-            .with_filter(export_filter);
+    #[tokio::test]
+    #[gtest]
+    async fn validate_uses_the_current_runtime() {
+        expect_that!(Owiwi::default().validate(), ok(anything()));
+    }
 
-        let fmt_layer = self.fmt_layer().with_filter(filter_layer);
+    #[gtest]
+    fn validate_rejects_an_invalid_ratio() {
+        let owiwi = Owiwi::builder()
+            .traces_sampler("traceidratio")
+            .traces_sampler_arg("2")
+            .build();
+        expect_that!(owiwi.validate(), err(anything()));
+    }
 
-        tracing_subscriber::registry()
-            .with(otel_layer)
-            .with(ErrorLayer::default())
-            .with(fmt_layer)
-            .try_init()?;
+    #[gtest]
+    fn validate_reports_errors_ignored_when_not_strict() {
+        let owiwi = Owiwi::builder()
+            .traces(broken_backend())
+            .strict(false)
+            .build();
+        expect_that!(owiwi.validate(), err(anything()));
+    }
 
-        #[cfg(feature = "metrics")]
-        if let Some(meter_provider) = &meter_provider {
-            opentelemetry::global::set_meter_provider(meter_provider.clone());
-        }
+    #[cfg(feature = "console")]
+    #[gtest]
+    fn validate_reports_errors_hidden_by_the_console_fallback() {
+        let owiwi = Owiwi::builder()
+            .traces(broken_backend())
+            .fallback_to_console(true)
+            .build();
+        expect_that!(owiwi.validate(), err(anything()));
+    }
 
-        Ok(OwiwiGuard {
-            tracer_provider,
-            #[cfg(feature = "metrics")]
-            meter_provider,
-            filter_handle: Some(FilterHandle {
-                inner: Box::new(move |filter| {
-                    reload_handle
-                        .reload(filter)
-                        .map_err(|err| ErrorKind::FilterReload(err).into())
-                }),
-            }),
-        })
+    fn directives(directives: &[&str]) -> Vec<Directive> {
+        directives
+            .iter()
+            .map(|directive| directive.parse().expect("valid directive"))
+            .collect()
     }
 
-    /// Builds an OpenTelemetry [`Resource`].
-    fn build_resource(&mut self) -> Resource {
-        let service_name = if self.service_name.is_empty() {
-            std::env::var(env_vars::OTEL_SERVICE_NAME)
-                .unwrap_or_else(|_| DEFAULT_SERVICE_NAME.to_owned())
-        } else {
-            self.service_name.clone()
-        };
+    #[rstest]
+    #[case(Some("warn"), &[], LevelFilter::WARN)]
+    #[case(Some("warn"), &["debug"], LevelFilter::DEBUG)]
+    #[case(None, &["my_crate=debug"], LevelFilter::DEBUG)]
+    fn directives_override_rust_log(
+        #[case] rust_log: Option<&str>,
+        #[case] overrides: &[&str],
+        #[case] expected: LevelFilter,
+    ) {
+        let owiwi = Owiwi::builder()
+            .tracing_directives(directives(overrides))
+            .build();
+        let filter = owiwi.layered_filter(rust_log).expect("valid filter");
+        assert_that!(filter.max_level_hint(), some(eq(expected)));
+    }
 
-        let attrs = {
-            let taken = std::mem::take(&mut self.resource_attrs);
-            if taken.is_empty() {
-                std::env::var(env_vars::OTEL_RESOURCE_ATTRIBUTES)
-                    .ok()
-                    .and_then(|raw| env_vars::parse_key_values(&raw).ok())
-                    .unwrap_or_default()
-            } else {
-                taken
-            }
-        };
+    #[rstest]
+    #[case(Some("debug"), &["my_crate"], &[], "my_crate=debug,off")]
+    #[case(Some("warn"), &[], &["hyper", "h2"], "hyper=off,h2=off,warn")]
+    #[case(Some("info"), &["app"], &["app::db"], "app::db=off,app=info,off")]
+    #[case(Some("info,hyper=trace"), &["app"], &[], "hyper=trace,app=info,off")]
+    #[cfg_attr(
+        feature = "clap",
+        case(Some("hyper=trace"), &["app"], &[], "hyper=trace,app=error,off")
+    )]
+    fn targets_are_included_and_excluded(
+        #[case] rust_log: Option<&str>,
+        #[case] include: &[&str],
+        #[case] exclude: &[&str],
+        #[case] expected: &str,
+    ) {
+        let owiwi = Owiwi::builder()
+            .trace_include(include.iter().map(ToString::to_string).collect())
+            .trace_exclude(exclude.iter().map(ToString::to_string).collect())
+            .build();
+        let filter = owiwi.layered_filter(rust_log).expect("valid filter");
+        assert_that!(filter.to_string(), eq(expected));
+    }
 
-        Resource::builder()
-            .with_service_name(service_name)
-            .with_attributes(
-                attrs
-                    .into_iter()
-                    .map(|(k, v)| opentelemetry::KeyValue::new(k, v)),
-            )
-            .build()
+    #[gtest]
+    fn sdk_log_level_applies_to_opentelemetry_targets() {
+        let owiwi = Owiwi::builder().sdk_log_level(LevelFilter::DEBUG).build();
+        let filter = owiwi.layered_filter(Some("warn")).expect("valid filter");
+        expect_that!(filter.to_string(), eq("opentelemetry=debug,warn"));
     }
 
-    /// Creates a formatting layer.
-    fn fmt_layer<S>(&self) -> impl Layer<S>
-    where
-        S: Subscriber + for<'span> LookupSpan<'span>,
-    {
-        let layer: Box<dyn Layer<_> + Send + Sync> = match self.event_format {
-            EventFormat::Compact => {
-                let format = self.event_format.compact();
-                Box::new(tracing_subscriber::fmt::layer().event_format(format))
-            }
+    #[gtest]
+    fn directive_replaces_rust_log_target() {
+        let owiwi = Owiwi::builder()
+            .tracing_directives(directives(&["my_crate=info"]))
+            .build();
+        let filter = owiwi
+            .layered_filter(Some("my_crate=trace"))
+            .expect("valid filter");
+        expect_that!(filter.to_string(), eq("my_crate=info"));
+    }
 
-            EventFormat::Full => {
-                let format = self.event_format.full();
-                Box::new(tracing_subscriber::fmt::layer().event_format(format))
-            }
-            EventFormat::Pretty => {
-                let format = self.event_format.pretty();
-                Box::new(tracing_subscriber::fmt::layer().event_format(format))
-            }
-        };
+    #[gtest]
+    fn inline_directives_override_the_file() {
+        let path =
+            std::env::temp_dir().join(format!("owiwi-{}-directives.txt", std::process::id()));
+        std::fs::write(&path, "# noisy crates\nhyper=warn\n\nmy_crate=debug\n")
+            .expect("writable temp dir");
+        let owiwi = Owiwi::builder()
+            .tracing_directives_file(&path)
+            .tracing_directives(directives(&["my_crate=info"]))
+            .build();
+        let filter = owiwi.layered_filter(Some("warn")).expect("valid filter");
+        expect_that!(filter.to_string(), eq("my_crate=info,hyper=warn,warn"));
+    }
 
-        layer
+    #[gtest]
+    fn invalid_file_directive_reports_its_line() {
+        let result = parse_directive_lines("info\n# comment\nmy_crate=[\n");
+        expect_that!(result.map_err(|(line, _)| line), err(eq(&3)));
     }
 
-    /// Creates a filter layer from the configuration.
-    fn filter_layer(&self) -> Result<EnvFilter> {
-        let mut layer = match EnvFilter::try_from_default_env() {
-            Ok(layer) => layer,
-            Err(err) => {
-                if let Some(source) = err.source() {
-                    match source.downcast_ref::<VarError>() {
-                        Some(VarError::NotPresent) => (),
-                        Some(err) => {
-                            tracing::error!("{err:?}");
-                            return Err(ErrorKind::ParseDirective {
-                                source: err.clone(),
-                            }
-                            .into());
-                        }
-                        None => {
-                            tracing::error!("{err:?}");
-                            return Err(ErrorKind::UnexpectedFilter(err.to_string()).into());
-                        }
-                    }
-                }
-                if self.tracing_directives.is_empty() {
-                    #[cfg(feature = "clap")]
-                    let level = self.verbose.tracing_level().unwrap_or(tracing::Level::INFO);
-                    #[cfg(not(feature = "clap"))]
-                    let level = tracing::Level::INFO;
-                    EnvFilter::try_new(level.as_str())?
-                } else {
-                    EnvFilter::builder().parse("")?
-                }
-            }
-        };
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case(None, 0, &[], LevelFilter::ERROR)]
+    #[case(None, 3, &[], LevelFilter::DEBUG)]
+    #[case(Some("warn"), 0, &[], LevelFilter::WARN)]
+    #[case(Some("warn"), 2, &[], LevelFilter::INFO)]
+    #[case(Some("warn"), 2, &["trace"], LevelFilter::TRACE)]
+    fn verbosity_overrides_rust_log_and_yields_to_directives(
+        #[case] rust_log: Option<&str>,
+        #[case] verbose: u8,
+        #[case] overrides: &[&str],
+        #[case] expected: LevelFilter,
+    ) {
+        let owiwi = Owiwi::builder()
+            .verbose(Verbosity::new(verbose, 0))
+            .tracing_directives(directives(overrides))
+            .build();
+        let filter = owiwi.layered_filter(rust_log).expect("valid filter");
+        assert_that!(filter.max_level_hint(), some(eq(expected)));
+    }
 
-        for directive in &self.tracing_directives {
-            layer = layer.add_directive(directive.clone());
-        }
-        Ok(layer)
+    #[gtest]
+    fn filter_layer_defaults_to_info() {
+        let owiwi = Owiwi::default();
+        let filter = owiwi.filter_layer();
+        expect_that!(filter, ok(anything()));
     }
 
-    fn export_filter_layer(&self) -> Result<EnvFilter> {
-        if !self.export_directives.is_empty() {
-            let mut filter = EnvFilter::builder().parse("")?;
-            for directive in &self.export_directives {
-                filter = filter.add_directive(directive.clone());
-            }
+    #[rstest]
+    #[case(Err(VarError::NotUnicode("\u{fffd}".into())))]
+    #[case(Ok(String::from("my_crate=[")))]
+    fn invalid_rust_log_fails_when_strict(#[case] var: std::result::Result<String, VarError>) {
+        let owiwi = Owiwi::default();
+        assert_that!(owiwi.rust_log(var), err(anything()));
+    }
 
-            return Ok(filter);
-        }
+    #[rstest]
+    #[case(Err(VarError::NotUnicode("\u{fffd}".into())))]
+    #[case(Ok(String::from("my_crate=[")))]
+    fn invalid_rust_log_is_ignored_when_not_strict(
+        #[case] var: std::result::Result<String, VarError>,
+    ) {
+        let owiwi = Owiwi::builder().strict_env_filter(false).build();
+        let rust_log = owiwi.rust_log(var).expect("RUST_LOG ignored");
+        assert_that!(rust_log, none());
+        let filter = owiwi
+            .layered_filter(rust_log.as_deref())
+            .expect("valid filter");
+        assert_that!(filter.max_level_hint(), some(eq(owiwi.default_level())));
+    }
 
-        #[cfg(not(feature = "clap"))]
-        if let Ok(val) = std::env::var(env_vars::OWIWI_EXPORT_LOG) {
-            return Ok(EnvFilter::try_new(val)?);
-        }
+    #[rstest]
+    #[case(Err(VarError::NotPresent), None)]
+    #[case(Ok(String::from("my_crate=debug")), Some("my_crate=debug"))]
+    fn valid_rust_log_is_kept(
+        #[case] var: std::result::Result<String, VarError>,
+        #[case] expected: Option<&str>,
+    ) {
+        let owiwi = Owiwi::default();
+        let rust_log = owiwi.rust_log(var).expect("valid RUST_LOG");
+        assert_that!(rust_log.as_deref(), eq(expected));
+    }
 
-        Ok(EnvFilter::try_new("info")?)
+    #[cfg(feature = "clap")]
+    #[gtest]
+    fn json_format_flags_parse() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from([
+            "test",
+            "--event-format",
+            "json",
+            "--json-flatten-event",
+            "--json-current-span",
+            "false",
+            "--json-span-list",
+        ])
+        .expect("valid arguments");
+        expect_that!(cli.owiwi.event_format.as_str(), eq("json"));
+        expect_that!(cli.owiwi.json_flatten_event, eq(true));
+        expect_that!(cli.owiwi.json_current_span, eq(false));
+        expect_that!(cli.owiwi.json_span_list, eq(true));
     }
 
-    #[allow(
-        clippy::missing_const_for_fn,
-        reason = "cannot be constify when clap is enabled"
-    )]
-    fn is_disabled(&self) -> bool {
-        if self.no_telemetry {
-            return true;
-        }
+    #[gtest]
+    fn json_current_span_defaults_to_true() {
+        expect_that!(Owiwi::default().json_current_span, eq(true));
+        expect_that!(Owiwi::default().json_span_list, eq(false));
+    }
 
-        #[cfg(not(feature = "clap"))]
-        {
-            return std::env::var(env_vars::OTEL_SDK_DISABLED)
-                .map(|v| v.eq_ignore_ascii_case("true"))
-                .unwrap_or(false);
-        }
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case(&["--otel-traces-sampler", "traceidratio", "--otel-traces-sampler-arg", "0.1"], true)]
+    #[case(&["--otel-traces-sampler-arg", "1"], true)]
+    #[case(&["--otel-traces-sampler-arg", "1.5"], false)]
+    #[case(&["--otel-traces-sampler-arg", "-0.1"], false)]
+    #[case(&["--otel-traces-sampler-arg", "half"], false)]
+    #[case(&["--otel-traces-sampler", "sometimes"], false)]
+    fn sampler_flags_are_validated_at_parse_time(#[case] args: &[&str], #[case] valid: bool) {
+        use clap::Parser as _;
 
-        #[cfg(feature = "clap")]
-        false
+        let cli = Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied()));
+        assert_that!(cli.is_ok(), eq(valid));
     }
 
-    fn noop(self) -> Result<OwiwiGuard> {
-        let filter_layer = self.filter_layer()?;
-        let fmt_layer = self.fmt_layer().with_filter(filter_layer);
-        tracing_subscriber::registry().with(fmt_layer).try_init()?;
-        Ok(OwiwiGuard::noop())
+    #[cfg(feature = "clap")]
+    #[gtest]
+    fn trace_ansi_flag_overrides_detection() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--trace-ansi", "false"]).expect("valid arguments");
+        expect_that!(cli.owiwi.ansi, some(eq(false)));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use googletest::expect_that;
-    use googletest::gtest;
-    use googletest::matchers::anything;
-    use googletest::matchers::eq;
-    use googletest::matchers::ok;
-    use googletest::matchers::pat;
-    use googletest::matchers::some;
-    use opentelemetry::Key;
+    #[rstest]
+    #[case::compact_default(EventFormat::Compact, None, None, "owiwi.rs:", false)]
+    #[case::compact_with_file(EventFormat::Compact, Some(true), None, "owiwi.rs:", true)]
+    #[case::full_default(EventFormat::Full, None, None, "ThreadId(", false)]
+    #[case::full_with_thread_ids(EventFormat::Full, None, Some(true), "ThreadId(", true)]
+    fn source_details_override_the_format_defaults(
+        #[case] event_format: EventFormat,
+        #[case] file: Option<bool>,
+        #[case] thread_ids: Option<bool>,
+        #[case] detail: &str,
+        #[case] shown: bool,
+    ) {
+        use tracing_subscriber::layer::SubscriberExt as _;
 
-    use super::*;
+        let path = std::env::temp_dir().join(format!(
+            "owiwi-details-{}-{detail}-{shown}-{event_format}.log",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let owiwi = Owiwi::builder()
+            .event_format(event_format)
+            .output(OutputTarget::File(path.clone()))
+            .maybe_file(file)
+            .line_number(true)
+            .maybe_thread_ids(thread_ids)
+            .ansi(false)
+            .build();
+        let subscriber =
+            tracing_subscriber::registry().with(owiwi.fmt_layer().expect("valid format"));
+        tracing::subscriber::with_default(subscriber, || tracing::info!("detailed"));
+
+        let output = std::fs::read_to_string(&path).expect("output written");
+        let _ = std::fs::remove_file(path);
+        assert_that!(output.contains(detail), eq(shown));
+    }
+
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case(&[], true)]
+    #[case(&["--trace-error-layer", "false"], false)]
+    fn trace_error_layer_flag_defaults_to_enabled(#[case] args: &[&str], #[case] enabled: bool) {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied()))
+            .expect("valid arguments");
+        assert_that!(cli.owiwi.error_layer, eq(enabled));
+    }
 
+    #[cfg(feature = "clap")]
     #[gtest]
-    fn build_resource_sets_service_name() {
-        let mut owiwi = Owiwi::builder().service_name("test_service").build();
-        let resource = owiwi.build_resource();
-        expect_that!(resource, pat!(Resource { .. }));
-        expect_that!(resource.is_empty(), eq(false));
-        let key = Key::new("service.name");
-        let name = resource.get(&key).map(|v| String::from(v.as_str()));
-        expect_that!(name, some(eq("test_service")));
+    fn trace_output_flag_accepts_a_path() {
+        use clap::Parser as _;
+
+        let cli =
+            Cli::try_parse_from(["test", "--trace-output", "app.log"]).expect("valid arguments");
+        expect_that!(
+            cli.owiwi.output,
+            eq(&OutputTarget::File(std::path::PathBuf::from("app.log")))
+        );
     }
 
+    #[cfg(feature = "clap")]
     #[gtest]
-    fn filter_layer_with_directives() {
+    fn exporter_header_flag_is_repeatable() {
+        use clap::Parser as _;
+        use secrecy::ExposeSecret as _;
+
+        let cli = Cli::try_parse_from([
+            "test",
+            "--otel-exporter-header",
+            "authorization=Bearer token",
+            "--otel-exporter-header",
+            "x-team=core",
+        ])
+        .expect("valid arguments");
+        let headers: Vec<_> = cli
+            .owiwi
+            .exporter_headers
+            .iter()
+            .map(|(key, val)| (key.as_str(), val.expose_secret()))
+            .collect();
+        expect_that!(
+            headers,
+            googletest::matchers::elements_are![
+                eq(&("authorization", "Bearer token")),
+                eq(&("x-team", "core"))
+            ]
+        );
+    }
+
+    #[gtest]
+    fn exporter_headers_are_redacted_from_debug() {
         let owiwi = Owiwi::builder()
-            .tracing_directives(vec![
-                "info".parse().expect("valid directive"),
-                "my_crate=debug".parse().expect("valid directive"),
-            ])
+            .exporter_headers(vec![("authorization".to_owned(), "secret".into())])
             .build();
+        expect_that!(
+            format!("{owiwi:?}"),
+            googletest::matchers::not(googletest::matchers::contains_substring("secret"))
+        );
+    }
 
-        expect_that!(owiwi.filter_layer(), ok(anything()));
+    #[cfg(all(feature = "clap", feature = "metrics"))]
+    #[gtest]
+    fn histogram_buckets_flag_is_comma_separated() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--metrics-histogram-buckets", "0.1,0.5,1"])
+            .expect("valid arguments");
+        expect_that!(
+            cli.owiwi.metrics_histogram_buckets,
+            googletest::matchers::elements_are![eq(&0.1), eq(&0.5), eq(&1.0)]
+        );
     }
 
+    #[cfg(all(feature = "clap", feature = "process-metrics"))]
     #[gtest]
-    fn build_resource_with_custom_attributes() {
-        let mut owiwi = Owiwi::builder()
-            .resource_attrs(vec![
-                ("env".to_owned(), "staging".to_owned()),
-                ("region".to_owned(), "us-east-1".to_owned()),
-            ])
+    fn process_metrics_are_off_by_default() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test"]).expect("valid arguments");
+        expect_that!(cli.owiwi.metrics_process, eq(false));
+        let cli = Cli::try_parse_from(["test", "--metrics-process"]).expect("valid arguments");
+        expect_that!(cli.owiwi.metrics_process, eq(true));
+    }
+
+    #[cfg(all(feature = "clap", feature = "metrics"))]
+    #[gtest]
+    fn metrics_temporality_is_parsed_from_the_spec_values() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--otel-metrics-temporality", "lowmemory"])
+            .expect("valid arguments");
+        expect_that!(
+            cli.owiwi.metrics_temporality,
+            some(eq(Temporality::LowMemory))
+        );
+    }
+
+    #[cfg(all(feature = "clap", feature = "metrics"))]
+    #[gtest]
+    fn metrics_protocol_is_parsed_from_the_spec_values() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--otel-metrics-protocol", "grpc"])
+            .expect("valid arguments");
+        expect_that!(cli.owiwi.metrics_protocol, some(eq(Protocol::Grpc)));
+        expect_that!(
+            Cli::try_parse_from(["test", "--otel-metrics-protocol", "carrier-pigeon"]),
+            err(anything())
+        );
+    }
+
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case("1", Duration::from_millis(1))]
+    #[case("250us", Duration::from_micros(250))]
+    fn min_span_duration_is_parsed(#[case] value: &str, #[case] expected: Duration) {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--otel-min-span-duration", value])
+            .expect("valid arguments");
+        assert_that!(cli.owiwi.min_span_duration, some(eq(expected)));
+    }
+
+    #[cfg(feature = "clap")]
+    #[gtest]
+    fn keepalive_flags_are_parsed() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from([
+            "test",
+            "--otel-keepalive-interval",
+            "30s",
+            "--otel-keepalive-while-idle",
+            "true",
+        ])
+        .expect("valid arguments");
+        expect_that!(
+            cli.owiwi.keepalive,
+            eq(KeepaliveConfig::builder()
+                .interval(Duration::from_secs(30))
+                .while_idle(true)
+                .build())
+        );
+    }
+
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case("2500", Duration::from_millis(2500))]
+    #[case("3s", Duration::from_secs(3))]
+    fn exporter_timeout_flag_accepts_millis_and_durations(
+        #[case] value: &str,
+        #[case] expected: Duration,
+    ) {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--otel-exporter-timeout", value])
+            .expect("valid arguments");
+        assert_that!(cli.owiwi.exporter_timeout, some(eq(expected)));
+    }
+
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case("gzip", Some(Compression::Gzip))]
+    #[case("none", None)]
+    fn exporter_compression_flag_accepts_gzip_and_none(
+        #[case] value: &str,
+        #[case] expected: Option<Compression>,
+    ) {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--otel-exporter-compression", value])
+            .expect("valid arguments");
+        assert_that!(cli.owiwi.exporter_compression, some(eq(expected)));
+    }
+
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case("grpc", Protocol::Grpc)]
+    #[cfg_attr(feature = "http", case("http/protobuf", Protocol::HttpBinary))]
+    #[cfg_attr(feature = "http", case("http/json", Protocol::HttpJson))]
+    fn exporter_protocol_flag_is_parsed(#[case] value: &str, #[case] expected: Protocol) {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--otel-exporter-protocol", value])
+            .expect("valid arguments");
+        assert_that!(cli.owiwi.exporter_protocol, some(eq(expected)));
+    }
+
+    #[cfg(feature = "clap")]
+    #[gtest]
+    fn rate_limit_flags_are_parsed() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from([
+            "test",
+            "--trace-max-events-per-second",
+            "100",
+            "--trace-max-spans-per-second",
+            "10",
+        ])
+        .expect("valid arguments");
+        expect_that!(cli.owiwi.max_events_per_second, eq(NonZeroU32::new(100)));
+        expect_that!(cli.owiwi.max_spans_per_second, eq(NonZeroU32::new(10)));
+    }
+
+    #[gtest]
+    fn explicit_filter_replaces_directives() {
+        let owiwi = Owiwi::builder()
+            .tracing_directives(vec!["debug".parse().expect("valid directive")])
             .build();
+        let filter = owiwi.resolve_filter(Some(EnvFilter::new("warn")));
+        expect_that!(filter.map(|filter| filter.to_string()), ok(eq("warn")));
+    }
 
-        let resource = owiwi.build_resource();
-        let env_key = Key::new("env");
-        let env_val = resource.get(&env_key).map(|v| String::from(v.as_str()));
-        expect_that!(env_val, some(eq("staging")));
+    #[rstest]
+    #[case(Some("true"), true)]
+    #[case(Some("TRUE"), true)]
+    #[case(Some(" true "), true)]
+    #[case(Some("false"), false)]
+    #[case(Some("1"), false)]
+    #[case(Some(""), false)]
+    #[case(None, false)]
+    fn sdk_disabled_follows_the_spec(#[case] value: Option<&str>, #[case] expected: bool) {
+        assert_that!(sdk_disabled(value.map(String::from)), eq(expected));
+    }
+
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case("True", true)]
+    #[case("1", false)]
+    #[case("yes", false)]
+    #[case("on", false)]
+    fn no_telemetry_values_follow_the_spec(#[case] value: &str, #[case] expected: bool) {
+        assert_that!(parse_sdk_disabled(value), ok(eq(expected)));
     }
 
+    #[cfg(feature = "clap")]
     #[gtest]
-    fn filter_layer_defaults_to_info() {
-        let owiwi = Owiwi::default();
-        let filter = owiwi.filter_layer();
-        expect_that!(filter, ok(anything()));
+    fn no_telemetry_flag_disables_telemetry() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test", "--no-telemetry"]).expect("valid arguments");
+        expect_that!(cli.owiwi.no_telemetry, eq(true));
+    }
+
+    #[rstest]
+    #[case("checkout", Some("from-env"), "checkout")]
+    #[case("", Some("from-env"), "from-env")]
+    #[case("", Some(""), DEFAULT_SERVICE_NAME)]
+    #[case("", None, DEFAULT_SERVICE_NAME)]
+    fn service_name_falls_back_to_env(
+        #[case] name: &str,
+        #[case] env: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        assert_that!(
+            service_name_or(String::from(name), env.map(String::from)),
+            eq(expected)
+        );
+    }
+
+    #[gtest]
+    fn default_matches_the_documented_values() {
+        let default = Owiwi::default();
+        expect_that!(default.service_name, eq(""));
+        expect_that!(default.strict, eq(true));
+        expect_that!(default.strict_env_filter, eq(true));
+        expect_that!(default.error_layer, eq(true));
+        expect_that!(default.json_current_span, eq(true));
+        expect_that!(default.json_flatten_event, eq(false));
+        expect_that!(default.json_span_list, eq(false));
+        expect_that!(default.install_panic_hook, eq(false));
+        expect_that!(default.validate_endpoint, eq(false));
+        expect_that!(default.no_telemetry, eq(false));
+        expect_that!(default.disabled, eq(false));
+        expect_that!(default.output, eq(&OutputTarget::Stderr));
+        expect_that!(default.span_events, elements_are![]);
+        expect_that!(default.redact_fields, elements_are![]);
+        expect_that!(default.shutdown_timeout, none());
+        expect_that!(default.exporter_timeout, none());
+    }
+
+    #[cfg(feature = "clap")]
+    #[gtest]
+    fn parsing_no_arguments_matches_default() {
+        use clap::Parser as _;
+
+        let cli = Cli::try_parse_from(["test"]).expect("valid arguments");
+        let default = Owiwi::default();
+        expect_that!(
+            cli.owiwi.event_format.as_str(),
+            eq(default.event_format.as_str())
+        );
+        expect_that!(cli.owiwi.output, eq(&default.output));
+        expect_that!(cli.owiwi.json_current_span, eq(default.json_current_span));
+        expect_that!(cli.owiwi.json_flatten_event, eq(default.json_flatten_event));
+        expect_that!(cli.owiwi.json_span_list, eq(default.json_span_list));
+        expect_that!(cli.owiwi.no_telemetry, eq(default.no_telemetry));
+        expect_that!(cli.owiwi.disabled, eq(default.disabled));
     }
 }
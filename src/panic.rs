@@ -0,0 +1,67 @@
+//! Panic hook flushing buffered telemetry.
+
+use std::panic::PanicHookInfo;
+
+use opentelemetry_sdk::trace::SdkTracerProvider;
+
+/// Providers flushed when the process panics.
+#[derive(Clone, Debug)]
+pub(crate) struct PanicFlush {
+    pub(crate) tracer_provider: SdkTracerProvider,
+    #[cfg(feature = "logs")]
+    pub(crate) logger_provider: Option<opentelemetry_sdk::logs::SdkLoggerProvider>,
+}
+
+impl PanicFlush {
+    /// Chains a panic hook that records the panic as an error event in its
+    /// own span, flushes the providers and then runs the previously installed
+    /// hook.
+    ///
+    /// The previous hook is the default one unless the application set its
+    /// own, which keeps running.
+    pub(crate) fn install(self) {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // The current span stays open while the hook runs, so the event
+            // goes in a span that closes before the flush.
+            tracing::error_span!("panic").in_scope(|| record(info));
+            self.flush();
+            previous(info);
+        }));
+    }
+
+    /// Exports the buffered spans and logs, ignoring failures since the
+    /// process is going down.
+    fn flush(&self) {
+        let _ = self.tracer_provider.force_flush();
+        #[cfg(feature = "logs")]
+        if let Some(logger_provider) = &self.logger_provider {
+            let _ = logger_provider.force_flush();
+        }
+    }
+}
+
+/// Emits an error event with the panic message and location.
+fn record(info: &PanicHookInfo<'_>) {
+    let message = panic_message(info);
+    match info.location() {
+        Some(location) => tracing::error!(
+            panic.message = message,
+            panic.file = location.file(),
+            panic.line = location.line(),
+            panic.column = location.column(),
+            "panicked"
+        ),
+        None => tracing::error!(panic.message = message, "panicked"),
+    }
+}
+
+/// Returns the panic payload when it is a string.
+fn panic_message<'a>(info: &'a PanicHookInfo<'_>) -> &'a str {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
@@ -0,0 +1,243 @@
+//! Trace context propagation.
+
+use std::fmt;
+use std::str::FromStr;
+
+use opentelemetry::propagation::TextMapCompositePropagator;
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry_sdk::propagation::BaggagePropagator;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+
+use crate::env_vars;
+
+/// Default propagators, as defined by the OpenTelemetry specification.
+const DEFAULT_PROPAGATORS: &[Propagator] = &[Propagator::TraceContext, Propagator::Baggage];
+
+/// Context propagator installed as the global text map propagator.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Propagator {
+    /// W3C Trace Context.
+    TraceContext,
+    /// W3C Baggage.
+    Baggage,
+    /// B3 single header.
+    #[cfg(feature = "b3")]
+    B3,
+    /// B3 multi header.
+    #[cfg(feature = "b3")]
+    B3Multi,
+    /// Jaeger `uber-trace-id` header.
+    #[cfg(feature = "jaeger")]
+    Jaeger,
+    /// AWS X-Ray `X-Amzn-Trace-Id` header.
+    #[cfg(feature = "xray")]
+    Xray,
+    /// No propagation.
+    None,
+}
+
+impl Propagator {
+    /// Returns the `OTEL_PROPAGATORS` name of this propagator.
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::TraceContext => "tracecontext",
+            Self::Baggage => "baggage",
+            #[cfg(feature = "b3")]
+            Self::B3 => "b3",
+            #[cfg(feature = "b3")]
+            Self::B3Multi => "b3multi",
+            #[cfg(feature = "jaeger")]
+            Self::Jaeger => "jaeger",
+            #[cfg(feature = "xray")]
+            Self::Xray => "xray",
+            Self::None => "none",
+        }
+    }
+
+    /// Returns the propagator implementation, or `None` for [`Self::None`].
+    fn build(self) -> Option<Box<dyn TextMapPropagator + Send + Sync>> {
+        match self {
+            Self::TraceContext => Some(Box::new(TraceContextPropagator::new())),
+            Self::Baggage => Some(Box::new(BaggagePropagator::new())),
+            #[cfg(feature = "b3")]
+            Self::B3 => Some(Box::new(opentelemetry_zipkin::Propagator::with_encoding(
+                opentelemetry_zipkin::B3Encoding::SingleHeader,
+            ))),
+            #[cfg(feature = "b3")]
+            Self::B3Multi => Some(Box::new(opentelemetry_zipkin::Propagator::with_encoding(
+                opentelemetry_zipkin::B3Encoding::MultipleHeader,
+            ))),
+            #[cfg(feature = "jaeger")]
+            Self::Jaeger => Some(Box::new(opentelemetry_jaeger_propagator::Propagator::new())),
+            #[cfg(feature = "xray")]
+            Self::Xray => Some(Box::new(opentelemetry_aws::trace::XrayPropagator::new())),
+            Self::None => None,
+        }
+    }
+}
+
+impl fmt::Display for Propagator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for Propagator {
+    type Err = ParsePropagatorError;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let propagator = match value {
+            "tracecontext" => Self::TraceContext,
+            "baggage" => Self::Baggage,
+            #[cfg(feature = "b3")]
+            "b3" => Self::B3,
+            #[cfg(feature = "b3")]
+            "b3multi" => Self::B3Multi,
+            #[cfg(feature = "jaeger")]
+            "jaeger" => Self::Jaeger,
+            #[cfg(feature = "xray")]
+            "xray" => Self::Xray,
+            "none" => Self::None,
+            other => return Err(ParsePropagatorError(other.to_owned())),
+        };
+        Ok(propagator)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid propagator: {0}")]
+pub struct ParsePropagatorError(String);
+
+/// Builds the composite propagator.
+///
+/// An empty list falls back to `OTEL_PROPAGATORS`, then to `tracecontext,baggage`.
+pub(crate) fn text_map_propagator(propagators: &[Propagator]) -> TextMapCompositePropagator {
+    TextMapCompositePropagator::new(
+        resolve(propagators)
+            .into_iter()
+            .filter_map(Propagator::build)
+            .collect(),
+    )
+}
+
+/// Returns the resolved propagators with the X-Ray propagator appended, unless
+/// already listed.
+#[cfg(feature = "xray")]
+pub(crate) fn with_xray(propagators: &[Propagator]) -> Vec<Propagator> {
+    let mut propagators = resolve(propagators);
+    if !propagators.contains(&Propagator::Xray) {
+        propagators.push(Propagator::Xray);
+    }
+    propagators
+}
+
+/// Returns the configured propagators, falling back to `OTEL_PROPAGATORS`,
+/// then to the defaults.
+fn resolve(propagators: &[Propagator]) -> Vec<Propagator> {
+    if !propagators.is_empty() {
+        return propagators.to_vec();
+    }
+    std::env::var(env_vars::OTEL_PROPAGATORS)
+        .ok()
+        .and_then(|raw| {
+            raw.split(',')
+                .map(|name| name.trim().parse::<Propagator>())
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+        })
+        .unwrap_or_else(|| DEFAULT_PROPAGATORS.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::len;
+    use googletest::matchers::ok;
+    use opentelemetry::Context;
+    use opentelemetry::trace::SpanContext;
+    use opentelemetry::trace::SpanId;
+    use opentelemetry::trace::TraceContextExt as _;
+    use opentelemetry::trace::TraceFlags;
+    use opentelemetry::trace::TraceId;
+    use opentelemetry::trace::TraceState;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Propagator::TraceContext, "tracecontext")]
+    #[case(Propagator::Baggage, "baggage")]
+    #[cfg_attr(feature = "b3", case(Propagator::B3, "b3"))]
+    #[cfg_attr(feature = "b3", case(Propagator::B3Multi, "b3multi"))]
+    #[cfg_attr(feature = "jaeger", case(Propagator::Jaeger, "jaeger"))]
+    #[cfg_attr(feature = "xray", case(Propagator::Xray, "xray"))]
+    #[case(Propagator::None, "none")]
+    fn propagator_round_trips(#[case] propagator: Propagator, #[case] name: &str) {
+        assert_that!(propagator.to_string(), eq(name));
+        assert_that!(name.parse::<Propagator>(), ok(eq(&propagator)));
+    }
+
+    #[rstest]
+    #[case("w3c")]
+    #[case("TraceContext")]
+    fn parsing_invalid_propagator_fails(#[case] name: &str) {
+        assert_that!(name.parse::<Propagator>(), err(anything()));
+    }
+
+    fn sampled_context() -> Context {
+        let span_context = SpanContext::new(
+            TraceId::from(1),
+            SpanId::from(1),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        Context::new().with_remote_span_context(span_context)
+    }
+
+    #[gtest]
+    fn trace_context_propagator_injects_traceparent() {
+        let propagator = text_map_propagator(&[Propagator::TraceContext]);
+        let mut carrier: HashMap<String, String> = HashMap::new();
+        propagator.inject_context(&sampled_context(), &mut carrier);
+        expect_that!(carrier.contains_key("traceparent"), eq(true));
+    }
+
+    #[gtest]
+    fn none_propagator_injects_nothing() {
+        let propagator = text_map_propagator(&[Propagator::None]);
+        let mut carrier: HashMap<String, String> = HashMap::new();
+        propagator.inject_context(&sampled_context(), &mut carrier);
+        expect_that!(carrier, len(eq(0)));
+    }
+
+    #[cfg(feature = "xray")]
+    #[gtest]
+    fn xray_is_appended_once() {
+        let propagators = with_xray(&[Propagator::TraceContext]);
+        expect_that!(
+            propagators,
+            eq(&[Propagator::TraceContext, Propagator::Xray])
+        );
+        expect_that!(with_xray(&propagators), len(eq(2)));
+    }
+
+    #[cfg(feature = "xray")]
+    #[gtest]
+    fn xray_propagator_injects_amzn_trace_id() {
+        let propagator = text_map_propagator(&[Propagator::Xray]);
+        let mut carrier: HashMap<String, String> = HashMap::new();
+        propagator.inject_context(&sampled_context(), &mut carrier);
+        expect_that!(carrier.contains_key("x-amzn-trace-id"), eq(true));
+    }
+}
@@ -0,0 +1,179 @@
+//! OTLP transport protocol and compression selection.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// OTLP transport protocol.
+///
+/// Defaults to gRPC. The HTTP variants require the `http` feature.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Protocol {
+    /// OTLP over gRPC.
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "grpc"))]
+    Grpc,
+    /// OTLP over HTTP with binary protobuf payloads.
+    #[cfg(feature = "http")]
+    #[cfg_attr(feature = "serde", serde(rename = "http/protobuf"))]
+    HttpBinary,
+    /// OTLP over HTTP with JSON payloads.
+    #[cfg(feature = "http")]
+    #[cfg_attr(feature = "serde", serde(rename = "http/json"))]
+    HttpJson,
+}
+
+impl Protocol {
+    /// Returns the spec string representation of this protocol.
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::Grpc => "grpc",
+            #[cfg(feature = "http")]
+            Self::HttpBinary => "http/protobuf",
+            #[cfg(feature = "http")]
+            Self::HttpJson => "http/json",
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = ParseProtocolError;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let protocol = match value {
+            "grpc" => Self::Grpc,
+            #[cfg(feature = "http")]
+            "http/protobuf" => Self::HttpBinary,
+            #[cfg(feature = "http")]
+            "http/json" => Self::HttpJson,
+            other => return Err(ParseProtocolError(other.to_owned())),
+        };
+        Ok(protocol)
+    }
+}
+
+impl From<Protocol> for opentelemetry_otlp::Protocol {
+    fn from(protocol: Protocol) -> Self {
+        match protocol {
+            Protocol::Grpc => Self::Grpc,
+            #[cfg(feature = "http")]
+            Protocol::HttpBinary => Self::HttpBinary,
+            #[cfg(feature = "http")]
+            Protocol::HttpJson => Self::HttpJson,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid OTLP protocol: {0} (expected grpc, http/protobuf or http/json)")]
+pub struct ParseProtocolError(String);
+
+/// OTLP payload compression.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Compression {
+    /// Gzip compression.
+    Gzip,
+}
+
+impl Compression {
+    /// Returns the spec string representation of this compression.
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::Gzip => "gzip",
+        }
+    }
+
+    /// Parses the `OTEL_EXPORTER_OTLP_COMPRESSION` value, where `none`
+    /// disables compression.
+    pub(crate) fn parse_optional(value: &str) -> Result<Option<Self>, ParseCompressionError> {
+        match value {
+            "none" => Ok(None),
+            other => other.parse().map(Some),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for Compression {
+    type Err = ParseCompressionError;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "gzip" => Ok(Self::Gzip),
+            other => Err(ParseCompressionError(other.to_owned())),
+        }
+    }
+}
+
+impl From<Compression> for opentelemetry_otlp::Compression {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::Gzip => Self::Gzip,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid OTLP compression: {0} (expected gzip or none)")]
+pub struct ParseCompressionError(String);
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::none;
+    use googletest::matchers::ok;
+    use googletest::matchers::some;
+    use rstest::rstest;
+
+    use super::Compression;
+    use super::Protocol;
+
+    #[rstest]
+    #[case(Protocol::Grpc, "grpc")]
+    #[cfg_attr(feature = "http", case(Protocol::HttpBinary, "http/protobuf"))]
+    #[cfg_attr(feature = "http", case(Protocol::HttpJson, "http/json"))]
+    fn protocol_round_trips(#[case] protocol: Protocol, #[case] display: &str) {
+        assert_that!(protocol.to_string(), eq(display));
+        let parsed: Result<Protocol, _> = display.parse();
+        assert_that!(parsed, ok(eq(&protocol)));
+    }
+
+    #[rstest]
+    #[case("http")]
+    #[case("GRPC")]
+    #[case("")]
+    fn parsing_invalid_protocol_fails(#[case] value: &str) {
+        let result: Result<Protocol, _> = value.parse();
+        assert_that!(result, err(anything()));
+    }
+
+    #[gtest]
+    fn compression_parses_spec_values() {
+        expect_that!(
+            Compression::parse_optional("gzip"),
+            ok(some(eq(&Compression::Gzip)))
+        );
+        expect_that!(Compression::parse_optional("none"), ok(none()));
+        expect_that!(Compression::parse_optional("zstd"), err(anything()));
+    }
+}
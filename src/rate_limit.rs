@@ -0,0 +1,204 @@
+//! Rate limiting of exported spans and events.
+
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::sync::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use tracing::Metadata;
+use tracing::subscriber::Interest;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::Filter;
+
+/// Target of the summary event reporting dropped spans and events.
+const SUMMARY_TARGET: &str = "owiwi::rate_limit";
+
+/// Interval between summary events.
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Per-layer filter capping the spans and events reaching the export layer.
+///
+/// Excess items are dropped and their count is reported once per second by a
+/// `WARN` event with the `owiwi::rate_limit` target.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimitFilter {
+    events: Option<Arc<Limiter>>,
+    spans: Option<Arc<Limiter>>,
+    dropped: Arc<Dropped>,
+}
+
+impl RateLimitFilter {
+    /// Creates a filter from the per-second limits, returning `None` when no limit is set.
+    pub(crate) fn new(
+        events_per_second: Option<NonZeroU32>,
+        spans_per_second: Option<NonZeroU32>,
+    ) -> Option<Self> {
+        if events_per_second.is_none() && spans_per_second.is_none() {
+            return None;
+        }
+        let start = Instant::now();
+        Some(Self {
+            events: events_per_second.map(|max| Arc::new(Limiter::new(max, start))),
+            spans: spans_per_second.map(|max| Arc::new(Limiter::new(max, start))),
+            dropped: Arc::default(),
+        })
+    }
+
+    /// Counts an item against `limiter`, returning whether it is kept.
+    fn admit(&self, limiter: Option<&Limiter>, dropped: &AtomicU64) -> bool {
+        match limiter {
+            Some(limiter) if !limiter.try_acquire() => {
+                dropped.fetch_add(1, Ordering::Relaxed);
+                self.start_reporter();
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Spawns the summary thread on the first dropped item.
+    fn start_reporter(&self) {
+        if self.dropped.reporter.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let dropped = Arc::downgrade(&self.dropped);
+        let _ = std::thread::Builder::new()
+            .name(String::from("owiwi-rate-limit"))
+            .spawn(move || report(&dropped));
+    }
+}
+
+impl<S> Filter<S> for RateLimitFilter {
+    fn enabled(&self, meta: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        if meta.is_span() && meta.target() != SUMMARY_TARGET {
+            return self.admit(self.spans.as_deref(), &self.dropped.spans);
+        }
+        true
+    }
+
+    fn event_enabled(&self, event: &tracing::Event<'_>, _cx: &Context<'_, S>) -> bool {
+        if event.metadata().target() == SUMMARY_TARGET {
+            return true;
+        }
+        self.admit(self.events.as_deref(), &self.dropped.events)
+    }
+
+    fn callsite_enabled(&self, _meta: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+}
+
+/// Fixed one-second window counter.
+#[derive(Debug)]
+struct Limiter {
+    max: u64,
+    start: Instant,
+    window: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Limiter {
+    fn new(max: NonZeroU32, start: Instant) -> Self {
+        Self {
+            max: u64::from(max.get()),
+            start,
+            window: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a slot in the current window, returning `false` when it is full.
+    fn try_acquire(&self) -> bool {
+        let now = self.start.elapsed().as_secs();
+        let window = self.window.load(Ordering::Relaxed);
+        if now != window
+            && self
+                .window
+                .compare_exchange(window, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            self.count.store(0, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed) < self.max
+    }
+}
+
+/// Counts of items dropped since the last summary.
+#[derive(Debug, Default)]
+struct Dropped {
+    events: AtomicU64,
+    spans: AtomicU64,
+    reporter: AtomicBool,
+}
+
+/// Emits a summary of dropped items every interval until the filter is dropped.
+///
+/// Runs outside of the subscriber so that the summary is not swallowed by the
+/// dispatcher's re-entrancy guard.
+fn report(dropped: &Weak<Dropped>) {
+    loop {
+        std::thread::sleep(SUMMARY_INTERVAL);
+        let Some(dropped) = dropped.upgrade() else {
+            return;
+        };
+        let events = dropped.events.swap(0, Ordering::Relaxed);
+        let spans = dropped.spans.swap(0, Ordering::Relaxed);
+        if events > 0 || spans > 0 {
+            tracing::warn!(
+                target: SUMMARY_TARGET,
+                dropped_events = events,
+                dropped_spans = spans,
+                "export rate limit exceeded"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::eq;
+    use googletest::matchers::none;
+
+    use super::*;
+
+    fn limit(max: u32) -> Option<NonZeroU32> {
+        NonZeroU32::new(max)
+    }
+
+    #[gtest]
+    fn no_limit_builds_no_filter() {
+        expect_that!(RateLimitFilter::new(None, None), none());
+    }
+
+    #[gtest]
+    fn limiter_caps_the_window() {
+        let limiter = Limiter::new(NonZeroU32::new(2).expect("non-zero"), Instant::now());
+        let admitted = (0..5).filter(|_| limiter.try_acquire()).count();
+        expect_that!(admitted, eq(2));
+    }
+
+    #[gtest]
+    fn excess_events_are_counted_as_dropped() {
+        let filter = RateLimitFilter::new(limit(3), None).expect("a limit is set");
+        let kept = (0..10)
+            .filter(|_| filter.admit(filter.events.as_deref(), &filter.dropped.events))
+            .count();
+        expect_that!(kept, eq(3));
+        expect_that!(filter.dropped.events.load(Ordering::Relaxed), eq(7));
+    }
+
+    #[gtest]
+    fn unlimited_spans_are_kept() {
+        let filter = RateLimitFilter::new(limit(1), None).expect("a limit is set");
+        let kept = (0..10)
+            .filter(|_| filter.admit(filter.spans.as_deref(), &filter.dropped.spans))
+            .count();
+        expect_that!(kept, eq(10));
+    }
+}
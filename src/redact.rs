@@ -0,0 +1,789 @@
+//! Redaction of sensitive field values.
+//!
+//! Text and JSON output is redacted while the fields are recorded, exported
+//! spans right before export and exported logs as they are emitted.
+
+use std::fmt;
+use std::sync::Arc;
+
+#[cfg(feature = "logs")]
+use opentelemetry::InstrumentationScope;
+use opentelemetry::KeyValue;
+#[cfg(feature = "logs")]
+use opentelemetry::logs::AnyValue;
+#[cfg(feature = "logs")]
+use opentelemetry::logs::LogRecord as _;
+#[cfg(feature = "logs")]
+use opentelemetry::logs::Logger as _;
+#[cfg(feature = "logs")]
+use opentelemetry::logs::LoggerProvider as _;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkResult;
+#[cfg(feature = "logs")]
+use opentelemetry_sdk::logs::LogProcessor;
+#[cfg(feature = "logs")]
+use opentelemetry_sdk::logs::SdkLogRecord;
+#[cfg(feature = "logs")]
+use opentelemetry_sdk::logs::SdkLogger;
+#[cfg(feature = "logs")]
+use opentelemetry_sdk::logs::SdkLoggerProvider;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanExporter;
+use tracing::Event;
+use tracing::Subscriber;
+use tracing::Value;
+use tracing::field::DisplayValue;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::field::display;
+use tracing::span::Record;
+use tracing_subscriber::field::MakeVisitor;
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::field::VisitFmt;
+use tracing_subscriber::field::VisitOutput;
+use tracing_subscriber::fmt::FmtContext;
+use tracing_subscriber::fmt::FormatEvent;
+use tracing_subscriber::fmt::FormatFields;
+use tracing_subscriber::fmt::FormattedFields;
+use tracing_subscriber::fmt::format::JsonVisitor;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Replacement of redacted values.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Serializes a secret as [`REDACTED`].
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_secret<S: serde::Serializer>(
+    _secret: &secrecy::SecretString,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(REDACTED)
+}
+
+/// Serializes a set secret as [`REDACTED`].
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_optional_secret<S: serde::Serializer>(
+    secret: &Option<secrecy::SecretString>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match secret {
+        Some(_) => serializer.serialize_some(REDACTED),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serializes header names with [`REDACTED`] values, since headers commonly
+/// carry credentials.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_header_values<S: serde::Serializer>(
+    headers: &[(String, String)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(headers.iter().map(|(name, _)| (name, REDACTED)))
+}
+
+/// Name of the field holding the event message, which is never redacted.
+const MESSAGE_FIELD: &str = "message";
+
+/// Field name pattern, with `*` allowed at the start and end.
+#[derive(Debug)]
+enum Pattern {
+    Any,
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    Contains(String),
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Self {
+        let pattern = pattern.to_ascii_lowercase();
+        let (leading, rest) = pattern
+            .strip_prefix('*')
+            .map_or((false, pattern.as_str()), |rest| (true, rest));
+        let (trailing, rest) = rest
+            .strip_suffix('*')
+            .map_or((false, rest), |rest| (true, rest));
+        let rest = rest.to_owned();
+        match (leading, trailing) {
+            _ if rest.is_empty() && (leading || trailing) => Self::Any,
+            (true, true) => Self::Contains(rest),
+            (true, false) => Self::Suffix(rest),
+            (false, true) => Self::Prefix(rest),
+            (false, false) => Self::Exact(rest),
+        }
+    }
+
+    /// Matches a lowercase field name.
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(pattern) => name == pattern,
+            Self::Prefix(pattern) => name.starts_with(pattern.as_str()),
+            Self::Suffix(pattern) => name.ends_with(pattern.as_str()),
+            Self::Contains(pattern) => name.contains(pattern.as_str()),
+        }
+    }
+}
+
+/// Field names whose values are replaced by [`REDACTED`].
+///
+/// Names are compared case-insensitively. A `*` at the start or end of a
+/// pattern matches any suffix or prefix, so `*_token` matches `api_token`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FieldRedaction {
+    patterns: Arc<[Pattern]>,
+}
+
+impl FieldRedaction {
+    /// Creates a redaction from field name patterns.
+    pub(crate) fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .map(|pattern| Pattern::new(pattern.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Returns `true` when no pattern is configured.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns `true` when the field `name` must be redacted.
+    ///
+    /// The event message is not a field to redact, whatever the patterns.
+    pub(crate) fn matches(&self, name: &str) -> bool {
+        if self.is_empty() || name == MESSAGE_FIELD {
+            return false;
+        }
+        let name = name.to_ascii_lowercase();
+        self.patterns.iter().any(|pattern| pattern.matches(&name))
+    }
+
+    /// Redacts matching attributes in place.
+    fn redact_attributes(&self, attributes: &mut [KeyValue]) {
+        for attribute in attributes {
+            if self.matches(attribute.key.as_str()) {
+                attribute.value = REDACTED.into();
+            }
+        }
+    }
+}
+
+/// Field formatter redacting the fields of the wrapped formatter.
+#[derive(Debug)]
+pub(crate) struct RedactFields<M> {
+    inner: M,
+    redaction: FieldRedaction,
+}
+
+impl<M> RedactFields<M> {
+    pub(crate) const fn new(inner: M, redaction: FieldRedaction) -> Self {
+        Self { inner, redaction }
+    }
+}
+
+impl<T, M: MakeVisitor<T>> MakeVisitor<T> for RedactFields<M> {
+    type Visitor = RedactVisitor<M::Visitor>;
+
+    fn make_visitor(&self, target: T) -> Self::Visitor {
+        RedactVisitor {
+            inner: self.inner.make_visitor(target),
+            redaction: self.redaction.clone(),
+        }
+    }
+}
+
+/// Visitor recording [`REDACTED`] in place of matching fields.
+pub(crate) struct RedactVisitor<V> {
+    inner: V,
+    redaction: FieldRedaction,
+}
+
+impl<V: Visit> Visit for RedactVisitor<V> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_f64(field, value);
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_i64(field, value);
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_u64(field, value);
+        }
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_i128(field, value);
+        }
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_u128(field, value);
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_bool(field, value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_str(field, value);
+        }
+    }
+
+    fn record_bytes(&mut self, field: &Field, value: &[u8]) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_bytes(field, value);
+        }
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_error(field, value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if self.redaction.matches(field.name()) {
+            self.inner.record_str(field, REDACTED);
+        } else {
+            self.inner.record_debug(field, value);
+        }
+    }
+}
+
+impl<O, V: VisitOutput<O>> VisitOutput<O> for RedactVisitor<V> {
+    fn finish(self) -> O {
+        self.inner.finish()
+    }
+}
+
+impl<V: VisitFmt> VisitFmt for RedactVisitor<V> {
+    fn writer(&mut self) -> &mut dyn fmt::Write {
+        self.inner.writer()
+    }
+}
+
+/// JSON field formatter redacting the span fields, the counterpart of
+/// [`RedactFields`] for [`JsonFields`](tracing_subscriber::fmt::format::JsonFields).
+#[derive(Debug)]
+pub(crate) struct RedactJsonFields {
+    redaction: FieldRedaction,
+}
+
+impl RedactJsonFields {
+    pub(crate) const fn new(redaction: FieldRedaction) -> Self {
+        Self { redaction }
+    }
+}
+
+impl<'writer> FormatFields<'writer> for RedactJsonFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut visitor = RedactVisitor {
+            inner: JsonVisitor::new(&mut writer),
+            redaction: self.redaction.clone(),
+        };
+        fields.record(&mut visitor);
+        visitor.finish()
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &Record<'_>,
+    ) -> fmt::Result {
+        if current.is_empty() {
+            return self.format_fields(current.as_writer(), fields);
+        }
+        // Like `JsonFields`, the recorded fields are merged into the span's
+        // JSON object.
+        let mut added = String::new();
+        self.format_fields(Writer::new(&mut added), fields)?;
+        let mut merged: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(current).map_err(|_err| fmt::Error)?;
+        let added: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&added).map_err(|_err| fmt::Error)?;
+        merged.extend(added);
+        current.fields = serde_json::Value::Object(merged).to_string();
+        Ok(())
+    }
+}
+
+/// Maximum number of fields of an event [`RedactJson`] can replay.
+const MAX_REPLAYED_FIELDS: usize = 32;
+
+/// Value of an event field, recorded to replay the event.
+enum FieldValue {
+    F64(f64),
+    I64(i64),
+    U64(u64),
+    Bool(bool),
+    Str(String),
+    /// Any other value, as the JSON formatter writes it.
+    Debug(DisplayValue<String>),
+}
+
+impl FieldValue {
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            Self::F64(value) => value,
+            Self::I64(value) => value,
+            Self::U64(value) => value,
+            Self::Bool(value) => value,
+            Self::Str(value) => value,
+            Self::Debug(value) => value,
+        }
+    }
+}
+
+/// Visitor recording the event fields, with [`REDACTED`] in place of the
+/// matching ones.
+struct RecordRedacted<'a> {
+    redaction: &'a FieldRedaction,
+    values: Vec<(Field, FieldValue)>,
+}
+
+impl RecordRedacted<'_> {
+    fn push(&mut self, field: &Field, value: FieldValue) {
+        let value = if self.redaction.matches(field.name()) {
+            FieldValue::Str(REDACTED.to_owned())
+        } else {
+            value
+        };
+        self.values.push((field.clone(), value));
+    }
+}
+
+impl Visit for RecordRedacted<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, FieldValue::F64(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, FieldValue::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, FieldValue::U64(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, FieldValue::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, FieldValue::Str(value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, FieldValue::Debug(display(format!("{value:?}"))));
+    }
+}
+
+/// JSON event formatter redacting the event fields of the wrapped one.
+///
+/// The JSON formatter writes the event fields without the field formatter,
+/// so events with matching fields are replayed with [`REDACTED`] values. The
+/// span fields are redacted by [`RedactJsonFields`].
+#[derive(Debug)]
+pub(crate) struct RedactJson<F> {
+    inner: F,
+    redaction: FieldRedaction,
+}
+
+impl<F> RedactJson<F> {
+    pub(crate) const fn new(inner: F, redaction: FieldRedaction) -> Self {
+        Self { inner, redaction }
+    }
+}
+
+impl<S, N, F> FormatEvent<S, N> for RedactJson<F>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        if !event
+            .fields()
+            .any(|field| self.redaction.matches(field.name()))
+        {
+            return self.inner.format_event(ctx, writer, event);
+        }
+        let mut recorded = RecordRedacted {
+            redaction: &self.redaction,
+            values: Vec::new(),
+        };
+        event.record(&mut recorded);
+        // Nothing is written when the event cannot be replayed.
+        let Some((first, _)) = recorded.values.first() else {
+            return Err(fmt::Error);
+        };
+        if recorded.values.len() > MAX_REPLAYED_FIELDS {
+            return Err(fmt::Error);
+        }
+        let values: [(&Field, Option<&dyn Value>); MAX_REPLAYED_FIELDS] =
+            std::array::from_fn(|i| {
+                recorded
+                    .values
+                    .get(i)
+                    .map_or((first, None), |(field, value)| {
+                        (field, Some(value.as_value()))
+                    })
+            });
+        let metadata = event.metadata();
+        let values = metadata.fields().value_set(&values);
+        let redacted = if event.is_contextual() {
+            Event::new(metadata, &values)
+        } else {
+            Event::new_child_of(event.parent().cloned(), metadata, &values)
+        };
+        self.inner.format_event(ctx, writer, &redacted)
+    }
+}
+
+/// Span exporter redacting span and event attributes before export.
+#[derive(Debug)]
+pub(crate) struct RedactingExporter<E> {
+    inner: E,
+    redaction: FieldRedaction,
+}
+
+impl<E> RedactingExporter<E> {
+    pub(crate) const fn new(inner: E, redaction: FieldRedaction) -> Self {
+        Self { inner, redaction }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for RedactingExporter<E> {
+    async fn export(&self, mut batch: Vec<SpanData>) -> OTelSdkResult {
+        if !self.redaction.is_empty() {
+            for span in &mut batch {
+                self.redaction.redact_attributes(&mut span.attributes);
+                for event in &mut span.events.events {
+                    self.redaction.redact_attributes(&mut event.attributes);
+                }
+            }
+        }
+        self.inner.export(batch).await
+    }
+
+    fn shutdown_with_timeout(&mut self, timeout: std::time::Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+/// Log processor redacting record attributes before handing them on.
+#[cfg(feature = "logs")]
+#[derive(Debug)]
+pub(crate) struct RedactingLogProcessor<P> {
+    inner: P,
+    redaction: FieldRedaction,
+    /// Source of blank records, since record attributes cannot be replaced
+    /// in place.
+    records: SdkLogger,
+}
+
+#[cfg(feature = "logs")]
+impl<P> RedactingLogProcessor<P> {
+    pub(crate) fn new(inner: P, redaction: FieldRedaction) -> Self {
+        Self {
+            inner,
+            redaction,
+            records: SdkLoggerProvider::builder().build().logger("owiwi"),
+        }
+    }
+
+    /// Copies `record` with its matching attributes redacted.
+    fn redact(&self, record: &SdkLogRecord) -> SdkLogRecord {
+        let mut redacted = self.records.create_log_record();
+        if let Some(name) = record.event_name() {
+            redacted.set_event_name(name);
+        }
+        if let Some(target) = record.target() {
+            redacted.set_target(target.clone());
+        }
+        if let Some(timestamp) = record.timestamp() {
+            redacted.set_timestamp(timestamp);
+        }
+        if let Some(timestamp) = record.observed_timestamp() {
+            redacted.set_observed_timestamp(timestamp);
+        }
+        if let Some(context) = record.trace_context() {
+            redacted.set_trace_context(context.trace_id, context.span_id, context.trace_flags);
+        }
+        if let Some(text) = record.severity_text() {
+            redacted.set_severity_text(text);
+        }
+        if let Some(severity) = record.severity_number() {
+            redacted.set_severity_number(severity);
+        }
+        if let Some(body) = record.body() {
+            redacted.set_body(body.clone());
+        }
+        redacted.add_attributes(record.attributes_iter().map(|(key, value)| {
+            let value = if self.redaction.matches(key.as_str()) {
+                AnyValue::from(REDACTED)
+            } else {
+                value.clone()
+            };
+            (key.clone(), value)
+        }));
+        redacted
+    }
+}
+
+#[cfg(feature = "logs")]
+impl<P: LogProcessor> LogProcessor for RedactingLogProcessor<P> {
+    fn emit(&self, record: &mut SdkLogRecord, scope: &InstrumentationScope) {
+        if record
+            .attributes_iter()
+            .any(|(key, _)| self.redaction.matches(key.as_str()))
+        {
+            *record = self.redact(record);
+        }
+        self.inner.emit(record, scope);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: std::time::Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Mutex;
+
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    #[cfg(feature = "logs")]
+    use googletest::matchers::contains;
+    use googletest::matchers::contains_substring;
+    #[cfg(feature = "logs")]
+    use googletest::matchers::elements_are;
+    use googletest::matchers::eq;
+    use googletest::matchers::not;
+    #[cfg(feature = "logs")]
+    use googletest::matchers::some;
+    #[cfg(feature = "logs")]
+    use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+    #[cfg(feature = "logs")]
+    use opentelemetry_sdk::logs::InMemoryLogExporter;
+    #[cfg(feature = "logs")]
+    use opentelemetry_sdk::logs::SimpleLogProcessor;
+    use rstest::rstest;
+    use tracing_subscriber::fmt::MakeWriter;
+    use tracing_subscriber::fmt::format::DefaultFields;
+    #[cfg(feature = "logs")]
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    use super::*;
+
+    #[rstest]
+    #[case("password", "password", true)]
+    #[case("password", "PASSWORD", true)]
+    #[case("password", "password_hint", false)]
+    #[case("*_token", "api_token", true)]
+    #[case("*_token", "token", false)]
+    #[case("auth*", "authorization", true)]
+    #[case("auth*", "oauth", false)]
+    #[case("*secret*", "client_secret_id", true)]
+    #[case("*", "anything", true)]
+    #[case("a*b", "a*b", true)]
+    #[case("a*b", "axb", false)]
+    fn patterns_match_field_names(
+        #[case] pattern: &str,
+        #[case] name: &str,
+        #[case] expected: bool,
+    ) {
+        assert_that!(FieldRedaction::new(&[pattern]).matches(name), eq(expected));
+    }
+
+    /// Writer capturing the formatted output.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().expect("not poisoned").clone()).expect("UTF-8 output")
+        }
+    }
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().expect("not poisoned").write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    fn emit() {
+        tracing::info_span!("login", auth_token = "span-secret").in_scope(|| {
+            tracing::info!(
+                password = "hunter2",
+                attempts = 3,
+                user = "ada",
+                "signed in"
+            );
+        });
+    }
+
+    #[gtest]
+    fn text_output_is_redacted() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(buffer.clone())
+            .with_ansi(false)
+            .fmt_fields(RedactFields::new(
+                DefaultFields::new(),
+                FieldRedaction::new(&["password", "*_token"]),
+            ))
+            .finish();
+        tracing::subscriber::with_default(subscriber, emit);
+
+        let output = buffer.contents();
+        expect_that!(output, not(contains_substring("hunter2")));
+        expect_that!(output, not(contains_substring("span-secret")));
+        expect_that!(output, contains_substring("password=\"[REDACTED]\""));
+        expect_that!(output, contains_substring("user=\"ada\""));
+    }
+
+    /// Returns the JSON output of [`emit`], redacting `patterns`.
+    fn json_output(patterns: &[&str]) -> String {
+        let buffer = Buffer::default();
+        let redaction = FieldRedaction::new(patterns);
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(buffer.clone())
+            .fmt_fields(RedactJsonFields::new(redaction.clone()))
+            .event_format(RedactJson::new(
+                tracing_subscriber::fmt::format().json().without_time(),
+                redaction,
+            ))
+            .finish();
+        tracing::subscriber::with_default(subscriber, emit);
+        buffer.contents()
+    }
+
+    #[gtest]
+    fn json_output_is_redacted() {
+        let output = json_output(&["password", "*_token"]);
+        expect_that!(output, not(contains_substring("hunter2")));
+        expect_that!(output, not(contains_substring("span-secret")));
+        expect_that!(output, contains_substring("\"password\":\"[REDACTED]\""));
+        expect_that!(output, contains_substring("\"auth_token\":\"[REDACTED]\""));
+        expect_that!(output, contains_substring("\"attempts\":3"));
+    }
+
+    #[gtest]
+    fn json_output_keeps_its_key_order() {
+        let expected = json_output(&[])
+            .replace("\"hunter2\"", "\"[REDACTED]\"")
+            .replace("\"span-secret\"", "\"[REDACTED]\"");
+        expect_that!(json_output(&["password", "*_token"]), eq(&expected));
+    }
+
+    #[cfg(feature = "logs")]
+    #[gtest]
+    fn exported_logs_are_redacted() {
+        let exporter = InMemoryLogExporter::default();
+        let provider = SdkLoggerProvider::builder()
+            .with_log_processor(RedactingLogProcessor::new(
+                SimpleLogProcessor::new(exporter.clone()),
+                FieldRedaction::new(&["password", "*_token"]),
+            ))
+            .build();
+        let subscriber =
+            tracing_subscriber::registry().with(OpenTelemetryTracingBridge::new(&provider));
+        tracing::subscriber::with_default(subscriber, emit);
+
+        let logs = exporter.get_emitted_logs().expect("emitted logs");
+        let attributes: Vec<_> = logs
+            .iter()
+            .flat_map(|log| log.record.attributes_iter())
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+        for attribute in [
+            ("password".to_owned(), AnyValue::from(REDACTED)),
+            ("attempts".to_owned(), AnyValue::from(3_i64)),
+            ("user".to_owned(), AnyValue::from("ada")),
+        ] {
+            expect_that!(attributes, contains(eq(&attribute)));
+        }
+        expect_that!(
+            logs.iter()
+                .map(|log| log.record.body().cloned())
+                .collect::<Vec<_>>(),
+            elements_are![some(eq(&AnyValue::from("signed in")))]
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Sentry integration.
+
+use std::fmt;
+use std::time::Duration;
+
+use bon::Builder;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sentry::protocol::Context;
+use sentry::protocol::Event;
+use sentry::protocol::Map;
+use sentry::protocol::Value;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::redact::FieldRedaction;
+use crate::redact::REDACTED;
+
+/// Forwarding of spans and events to [Sentry](https://sentry.io), alongside
+/// the OpenTelemetry export.
+///
+/// Error events become Sentry events, warning and info events breadcrumbs, and
+/// sampled spans Sentry transactions.
+#[must_use]
+#[derive(Clone, Debug, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SentryConfig {
+    /// Project DSN.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
+    pub dsn: SecretString,
+    /// Fraction of traces sent as transactions, between 0.0 and 1.0. Defaults to 0.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub traces_sample_rate: f32,
+}
+
+impl SentryConfig {
+    /// Initializes the Sentry client, tagging events with `release` and
+    /// redacting the matching fields of events and breadcrumbs.
+    pub(crate) fn init(
+        &self,
+        release: Option<&str>,
+        redaction: FieldRedaction,
+    ) -> Result<SentryGuard, Error> {
+        if !(0.0..=1.0).contains(&self.traces_sample_rate) {
+            return Err(ErrorKind::InvalidSamplerRatio {
+                ratio: f64::from(self.traces_sample_rate),
+            }
+            .into());
+        }
+        let dsn = self
+            .dsn
+            .expose_secret()
+            .parse()
+            .map_err(|source| ErrorKind::InvalidSentryDsn { source })?;
+        let mut options = sentry::ClientOptions::new()
+            .maybe_release(release.map(str::to_owned))
+            .traces_sample_rate(self.traces_sample_rate);
+        if !redaction.is_empty() {
+            let breadcrumbs = redaction.clone();
+            options = options
+                .before_send(move |mut event| {
+                    redact_event(&redaction, &mut event);
+                    Some(event)
+                })
+                .before_breadcrumb(move |mut breadcrumb| {
+                    redact_values(&breadcrumbs, &mut breadcrumb.data);
+                    Some(breadcrumb)
+                });
+        }
+        options.dsn = Some(dsn);
+        Ok(SentryGuard(sentry::init(options)))
+    }
+}
+
+/// Redacts the matching tags and tracing fields of a Sentry event.
+fn redact_event(redaction: &FieldRedaction, event: &mut Event<'static>) {
+    for (key, value) in &mut event.tags {
+        if redaction.matches(key) {
+            REDACTED.clone_into(value);
+        }
+    }
+    for context in event.contexts.values_mut() {
+        if let Context::Other(fields) = context {
+            redact_values(redaction, fields);
+        }
+    }
+}
+
+/// Redacts the matching entries of a map of field values.
+fn redact_values(redaction: &FieldRedaction, values: &mut Map<String, Value>) {
+    for (key, value) in values {
+        if redaction.matches(key) {
+            *value = Value::from(REDACTED);
+        }
+    }
+}
+
+/// Keeps the Sentry client alive, flushing pending events when dropped.
+pub(crate) struct SentryGuard(sentry::ClientInitGuard);
+
+impl SentryGuard {
+    /// Sends pending events, waiting at most `timeout`.
+    pub(crate) fn flush(&self, timeout: Duration) -> bool {
+        self.0.flush(Some(timeout))
+    }
+
+    /// Sends pending events and disables the client, waiting at most `timeout`.
+    pub(crate) fn close(&self, timeout: Duration) -> bool {
+        self.0.close(Some(timeout))
+    }
+}
+
+impl fmt::Debug for SentryGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SentryGuard").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::not;
+    use googletest::matchers::some;
+
+    use super::*;
+
+    #[gtest]
+    fn malformed_dsn_is_rejected_without_being_echoed() {
+        let config = SentryConfig::builder()
+            .dsn("not-a-dsn-secret".into())
+            .build();
+        let result = config.init(None, FieldRedaction::default());
+        expect_that!(result, err(anything()));
+        if let Err(err) = result {
+            expect_that!(err.to_string(), not(contains_substring("not-a-dsn-secret")));
+        }
+    }
+
+    #[gtest]
+    fn out_of_range_sample_rate_is_rejected() {
+        let config = SentryConfig::builder()
+            .dsn("https://public@sentry.example.com/1".into())
+            .traces_sample_rate(1.5)
+            .build();
+        expect_that!(
+            config.init(None, FieldRedaction::default()),
+            err(anything())
+        );
+    }
+
+    #[gtest]
+    fn dsn_is_redacted_from_debug() {
+        let config = SentryConfig::builder()
+            .dsn("https://public@sentry.example.com/1".into())
+            .build();
+        expect_that!(format!("{config:?}"), not(contains_substring("public@")));
+    }
+
+    #[gtest]
+    fn event_fields_and_tags_are_redacted() {
+        let fields = Map::from([
+            ("password".to_owned(), Value::from("hunter2")),
+            ("user".to_owned(), Value::from("ada")),
+        ]);
+        let mut event = Event {
+            tags: Map::from([("api_token".to_owned(), "s3cr3t".to_owned())]),
+            contexts: Map::from([("Rust Tracing Fields".to_owned(), Context::Other(fields))]),
+            ..Event::default()
+        };
+        redact_event(&FieldRedaction::new(&["password", "*_token"]), &mut event);
+
+        expect_that!(event.tags.get("api_token"), some(eq(REDACTED)));
+        let Some(Context::Other(fields)) = event.contexts.get("Rust Tracing Fields") else {
+            panic!("fields context to be kept");
+        };
+        expect_that!(fields.get("password"), some(eq(&Value::from(REDACTED))));
+        expect_that!(fields.get("user"), some(eq(&Value::from("ada"))));
+    }
+}
@@ -0,0 +1,42 @@
+//! Span status helpers.
+
+use std::fmt::Display;
+
+use opentelemetry::trace::Status;
+use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+/// Extension trait recording outcomes as the OpenTelemetry span status.
+pub trait SpanExt {
+    /// Sets the span status from `result`: `OK` for `Ok`, `ERROR` with the
+    /// error's [`Display`] output as the message for `Err`.
+    ///
+    /// The status is set directly, so `otel.status_code` and
+    /// `otel.status_message` don't need to be declared when creating the span.
+    /// They're still recorded when declared, to reach the fmt output.
+    ///
+    /// ```no_run
+    /// use owiwi::SpanExt as _;
+    ///
+    /// let span = tracing::info_span!("load");
+    /// let result = std::fs::read_to_string("config.toml");
+    /// span.record_result(&result);
+    /// ```
+    fn record_result<T, E: Display>(&self, result: &Result<T, E>);
+}
+
+impl SpanExt for tracing::Span {
+    fn record_result<T, E: Display>(&self, result: &Result<T, E>) {
+        match result {
+            Ok(_) => {
+                self.record("otel.status_code", "OK");
+                self.set_status(Status::Ok);
+            }
+            Err(error) => {
+                let message = error.to_string();
+                self.record("otel.status_code", "ERROR");
+                self.record("otel.status_message", message.as_str());
+                self.set_status(Status::error(message));
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Test helpers for asserting on exported telemetry.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanExporter;
+
+/// Span exporter capturing finished spans in memory.
+///
+/// Clones share the same storage, so keep a clone to inspect the spans after
+/// handing the collector to [`Owiwi::try_init_with_exporter`](crate::Owiwi::try_init_with_exporter).
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryCollector {
+    spans: Arc<Mutex<Vec<SpanData>>>,
+    resource: Arc<Mutex<Option<Resource>>>,
+}
+
+impl InMemoryCollector {
+    /// Returns all spans captured so far.
+    #[must_use]
+    pub fn spans(&self) -> Vec<SpanData> {
+        self.spans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the names of all spans captured so far.
+    #[must_use]
+    pub fn span_names(&self) -> Vec<String> {
+        self.spans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|span| span.name.to_string())
+            .collect()
+    }
+
+    /// Returns the resource attached to the exported spans.
+    #[must_use]
+    pub fn resource(&self) -> Option<Resource> {
+        self.resource
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Removes all captured spans.
+    pub fn clear(&self) {
+        self.spans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl SpanExporter for InMemoryCollector {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        self.spans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(batch);
+        Ok(())
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        *self.resource.lock().unwrap_or_else(PoisonError::into_inner) = Some(resource.clone());
+    }
+}
@@ -0,0 +1,176 @@
+//! TLS configuration for gRPC exporters.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use bon::Builder;
+#[cfg(feature = "clap")]
+use clap::Args;
+use opentelemetry_otlp::tonic_types::transport::Certificate;
+use opentelemetry_otlp::tonic_types::transport::ClientTlsConfig;
+use opentelemetry_otlp::tonic_types::transport::Identity;
+
+#[cfg(feature = "clap")]
+use crate::env_vars;
+use crate::error::Error;
+use crate::error::ErrorKind;
+
+/// PEM files used to secure, and optionally mutually authenticate, gRPC exporters,
+/// and whether to use TLS at all.
+///
+/// Unset values keep the system roots without client authentication, and use
+/// TLS for `https` and `grpcs` endpoints only.
+#[must_use]
+#[derive(Clone, Debug, Default, Builder)]
+#[cfg_attr(feature = "clap", derive(Args))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TlsConfig {
+    /// CA certificate used to verify the collector, replacing the system roots.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-ca-cert",
+            long,
+            help = "PEM CA certificate to verify the collector",
+            env = env_vars::OTEL_EXPORTER_OTLP_CERTIFICATE,
+        )
+    )]
+    #[builder(into)]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Client certificate for mutual TLS. Requires [`Self::client_key`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-client-cert",
+            long,
+            help = "PEM client certificate for mutual TLS",
+            env = env_vars::OTEL_EXPORTER_OTLP_CLIENT_CERTIFICATE,
+        )
+    )]
+    #[builder(into)]
+    pub client_cert: Option<PathBuf>,
+
+    /// Client private key for mutual TLS. Requires [`Self::client_cert`].
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-client-key",
+            long,
+            help = "PEM client private key for mutual TLS",
+            env = env_vars::OTEL_EXPORTER_OTLP_CLIENT_KEY,
+        )
+    )]
+    #[builder(into)]
+    pub client_key: Option<PathBuf>,
+
+    /// Sends in plaintext when `true`, over TLS when `false`, overriding the
+    /// endpoint scheme. `None` keeps the scheme-derived choice.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-insecure",
+            long,
+            help = "Force plaintext (true) or TLS (false), whatever the endpoint scheme",
+            env = env_vars::OTEL_EXPORTER_OTLP_INSECURE,
+        )
+    )]
+    pub insecure: Option<bool>,
+}
+
+impl TlsConfig {
+    /// Reads the PEM files and builds the client TLS configuration.
+    ///
+    /// Returns `None` when no file is configured.
+    pub(crate) fn build(&self) -> Result<Option<ClientTlsConfig>, Error> {
+        if self.ca_cert.is_none() && self.client_cert.is_none() && self.client_key.is_none() {
+            return Ok(None);
+        }
+
+        let mut tls = ClientTlsConfig::new();
+        tls = match &self.ca_cert {
+            Some(path) => tls.ca_certificate(Certificate::from_pem(read_pem(path)?)),
+            None => tls.with_enabled_roots(),
+        };
+
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                tls = tls.identity(Identity::from_pem(read_pem(cert)?, read_pem(key)?));
+            }
+            (None, None) => {}
+            _ => {
+                return Err(ErrorKind::ExporterConfig {
+                    reason: String::from("client certificate and key must be set together"),
+                }
+                .into());
+            }
+        }
+        Ok(Some(tls))
+    }
+}
+
+/// Reads a PEM file.
+fn read_pem(path: &Path) -> Result<Vec<u8>, Error> {
+    std::fs::read(path).map_err(|source| {
+        ErrorKind::ReadTlsFile {
+            path: path.to_path_buf(),
+            source,
+        }
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::err;
+    use googletest::matchers::none;
+    use googletest::matchers::ok;
+    use googletest::matchers::some;
+
+    use super::*;
+
+    fn pem_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("owiwi-{}-{name}", std::process::id()));
+        std::fs::write(
+            &path,
+            "-----BEGIN CERTIFICATE-----\n-----END CERTIFICATE-----\n",
+        )
+        .expect("writable temp dir");
+        path
+    }
+
+    #[gtest]
+    fn empty_config_builds_nothing() {
+        expect_that!(TlsConfig::default().build(), ok(none()));
+    }
+
+    #[gtest]
+    fn mutual_tls_reads_all_files() {
+        let config = TlsConfig::builder()
+            .ca_cert(pem_file("ca.pem"))
+            .client_cert(pem_file("client.pem"))
+            .client_key(pem_file("client.key"))
+            .build();
+        expect_that!(config.build(), ok(some(anything())));
+    }
+
+    #[gtest]
+    fn missing_file_is_an_error() {
+        let config = TlsConfig::builder()
+            .ca_cert("/nonexistent/owiwi/ca.pem")
+            .build();
+        expect_that!(config.build(), err(anything()));
+    }
+
+    #[gtest]
+    fn client_cert_without_key_is_an_error() {
+        let config = TlsConfig::builder()
+            .client_cert(pem_file("lonely.pem"))
+            .build();
+        expect_that!(config.build(), err(anything()));
+    }
+}
@@ -1,62 +1,429 @@
 //! OpenTelemetry trace setup.
 
+mod attributes;
+#[cfg(feature = "axiom")]
+mod axiom;
+mod batch;
+#[cfg(feature = "console")]
+mod console;
+#[cfg(feature = "datadog")]
+mod datadog;
+#[cfg(feature = "serde")]
+mod de;
+mod duration;
 #[cfg(feature = "honeycomb")]
 mod honeycomb;
+mod keepalive;
+mod limits;
 pub(crate) mod otlp;
+#[cfg(feature = "http")]
+mod partial_success;
+mod retry;
+mod sampler;
+mod shorthand;
+#[cfg(feature = "signoz")]
+mod signoz;
+#[cfg(feature = "tempo")]
+mod tempo;
+mod token;
+#[cfg(feature = "uptrace")]
+mod uptrace;
+use attributes::DefaultAttributes;
+#[cfg(feature = "axiom")]
+#[doc(inline)]
+pub use axiom::AxiomConfig;
+pub use batch::BatchConfig;
+#[doc(inline)]
+pub use batch::BatchRuntime;
+#[cfg(feature = "datadog")]
+#[doc(inline)]
+pub use datadog::DatadogConfig;
+pub(crate) use duration::with_exporting_processor;
 #[cfg(feature = "honeycomb")]
 #[doc(inline)]
 pub use honeycomb::HoneycombConfig;
+#[doc(inline)]
+pub use keepalive::KeepaliveConfig;
+#[doc(inline)]
+pub use limits::SpanLimits;
+use opentelemetry::KeyValue;
 use opentelemetry_otlp::SpanExporter;
+use opentelemetry_otlp::tonic_types::transport::ClientTlsConfig;
 use opentelemetry_sdk::resource::Resource;
 use opentelemetry_sdk::trace::Sampler;
 use opentelemetry_sdk::trace::SdkTracerProvider;
+#[cfg(feature = "console")]
+use opentelemetry_sdk::trace::SimpleSpanProcessor;
+use opentelemetry_sdk::trace::TracerProviderBuilder;
 pub use otlp::OtlpConfig;
+#[doc(inline)]
+pub use retry::RetryConfig;
+use retry::RetryingExporter;
+use sampler::AttributeSampler;
+use secrecy::SecretString;
+#[cfg(feature = "signoz")]
+#[doc(inline)]
+pub use signoz::SigNozConfig;
+#[cfg(feature = "tempo")]
+#[doc(inline)]
+pub use tempo::TempoConfig;
+#[doc(inline)]
+pub use token::OtlpTokenConfig;
+#[cfg(feature = "uptrace")]
+#[doc(inline)]
+pub use uptrace::UptraceConfig;
 
+use crate::Compression;
+use crate::Protocol;
 use crate::env_vars;
 use crate::error::Error;
 use crate::error::ErrorKind;
+use crate::redact::FieldRedaction;
+use crate::redact::RedactingExporter;
 
 /// Trace backend selection
+///
+/// Deserializes from either `{ type = "otlp", endpoint = ... }` or
+/// `{ otlp = { endpoint = ... } }`, with kebab-case backend names.
+/// Serializes to the first shape, with secrets redacted.
+#[allow(
+    clippy::large_enum_variant,
+    reason = "short-lived init value, not stored"
+)]
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "kebab-case")
+)]
 pub enum TraceExporter {
     /// Export span to stdout.
     #[cfg(feature = "console")]
     Console,
 
+    /// Export span to stdout as single-line OTLP JSON, one export request per line.
+    #[cfg(feature = "console")]
+    ConsoleJson,
+
+    /// Export to Axiom with a bearer token and dataset.
+    #[cfg(feature = "axiom")]
+    Axiom(AxiomConfig),
+
+    /// Export to Datadog OTLP intake.
+    #[cfg(feature = "datadog")]
+    Datadog(DatadogConfig),
+
     /// Export to Honeycomb.
     #[cfg(feature = "honeycomb")]
     Honeycomb(HoneycombConfig),
 
+    /// Export to SigNoz with an access token.
+    #[cfg(feature = "signoz")]
+    #[cfg_attr(feature = "serde", serde(rename = "signoz"))]
+    SigNoz(SigNozConfig),
+
+    /// Export to Grafana Tempo with basic authentication.
+    #[cfg(feature = "tempo")]
+    Tempo(TempoConfig),
+
+    /// Export to Uptrace, with the endpoint derived from the project DSN.
+    #[cfg(feature = "uptrace")]
+    Uptrace(UptraceConfig),
+
     /// Export via OTLP/gRPC to a collector
     Otlp(OtlpConfig),
 }
 
 impl TraceExporter {
+    /// Uptrace backend for `dsn` over gRPC, with the default timeout.
+    #[cfg(feature = "uptrace")]
+    pub fn uptrace(dsn: SecretString) -> Self {
+        Self::Uptrace(
+            UptraceConfig::builder()
+                .dsn(dsn)
+                .timeout(otlp::DEFAULT_OTLP_TIMEOUT)
+                .build(),
+        )
+    }
+
     /// Build tracer provider from the exporter backend.
+    ///
+    /// The batch configuration only applies to backends using batch export.
     pub fn build_provider(
         self,
         resource: Resource,
         sampler: Option<Sampler>,
+        batch: BatchConfig,
     ) -> Result<SdkTracerProvider, Error> {
+        self.build_provider_with(
+            resource,
+            sampler,
+            batch,
+            TracerSetup::default(),
+            FieldRedaction::default(),
+        )
+    }
+
+    /// Builds the tracer provider with the given shared settings, redacting
+    /// matching attributes before export.
+    pub(crate) fn build_provider_with(
+        self,
+        resource: Resource,
+        sampler: Option<Sampler>,
+        batch: BatchConfig,
+        setup: TracerSetup,
+        redaction: FieldRedaction,
+    ) -> Result<SdkTracerProvider, Error> {
+        self.check_endpoint()?;
         match self {
             #[cfg(feature = "console")]
-            Self::Console => {
-                let provider = SdkTracerProvider::builder()
-                    .with_resource(resource)
-                    .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
-                    .build();
-                Ok(provider)
+            Self::Console => build_console_provider(
+                opentelemetry_stdout::SpanExporter::default(),
+                resource,
+                sampler,
+                batch,
+                setup,
+                redaction,
+            ),
+            #[cfg(feature = "console")]
+            Self::ConsoleJson => build_console_provider(
+                console::OtlpJsonExporter::default(),
+                resource,
+                sampler,
+                batch,
+                setup,
+                redaction,
+            ),
+            #[cfg(feature = "axiom")]
+            Self::Axiom(config) => {
+                let exporter = OtlpConfig::from(config).build_exporter()?;
+                build_tracer_provider(exporter, None, resource, sampler, batch, setup, redaction)
+            }
+            #[cfg(feature = "datadog")]
+            Self::Datadog(config) => {
+                let exporter = OtlpConfig::from(config).build_exporter()?;
+                build_tracer_provider(exporter, None, resource, sampler, batch, setup, redaction)
             }
             #[cfg(feature = "honeycomb")]
             Self::Honeycomb(config) => {
                 let exporter = OtlpConfig::from(config).build_exporter()?;
-                build_tracer_provider(exporter, resource, sampler)
+                build_tracer_provider(exporter, None, resource, sampler, batch, setup, redaction)
+            }
+            #[cfg(feature = "signoz")]
+            Self::SigNoz(config) => {
+                let exporter = OtlpConfig::from(config).build_exporter()?;
+                build_tracer_provider(exporter, None, resource, sampler, batch, setup, redaction)
+            }
+            #[cfg(feature = "tempo")]
+            Self::Tempo(config) => {
+                let exporter = OtlpConfig::from(config).build_exporter()?;
+                build_tracer_provider(exporter, None, resource, sampler, batch, setup, redaction)
+            }
+            #[cfg(feature = "uptrace")]
+            Self::Uptrace(config) => {
+                let exporter = OtlpConfig::try_from(config)?.build_exporter()?;
+                build_tracer_provider(exporter, None, resource, sampler, batch, setup, redaction)
             }
             Self::Otlp(config) => {
+                let retry = config.retry;
                 let exporter = config.build_exporter()?;
-                build_tracer_provider(exporter, resource, sampler)
+                build_tracer_provider(exporter, retry, resource, sampler, batch, setup, redaction)
+            }
+        }
+    }
+}
+
+impl TraceExporter {
+    /// Replaces the endpoint of OTLP-based backends.
+    pub(crate) fn set_endpoint(&mut self, endpoint: url::Url) {
+        match self {
+            #[cfg(feature = "console")]
+            Self::Console | Self::ConsoleJson => {}
+            #[cfg(feature = "axiom")]
+            Self::Axiom(config) => config.endpoint = endpoint,
+            #[cfg(feature = "datadog")]
+            Self::Datadog(config) => config.endpoint = endpoint,
+            #[cfg(feature = "honeycomb")]
+            Self::Honeycomb(config) => config.endpoint = endpoint,
+            #[cfg(feature = "signoz")]
+            Self::SigNoz(config) => config.endpoint = endpoint,
+            #[cfg(feature = "tempo")]
+            Self::Tempo(config) => config.endpoint = endpoint,
+            #[cfg(feature = "uptrace")]
+            Self::Uptrace(_) => self.update_otlp(|config| config.endpoint = endpoint),
+            Self::Otlp(config) => config.endpoint = endpoint,
+        }
+    }
+
+    /// Replaces an Uptrace backend by the OTLP configuration parsed from its
+    /// DSN, so that its endpoint is known.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DSN is invalid.
+    #[cfg(feature = "uptrace")]
+    pub(crate) fn resolve_dsn(&mut self) -> Result<(), Error> {
+        if let Self::Uptrace(config) = self {
+            *self = Self::Otlp(OtlpConfig::try_from(config.clone())?);
+        }
+        Ok(())
+    }
+
+    /// Rejects plaintext endpoints of backends only reachable over TLS.
+    pub(crate) fn check_endpoint(&self) -> Result<(), Error> {
+        self.tls_only_endpoint()
+            .map_or(Ok(()), |(endpoint, backend)| {
+                otlp::require_tls(endpoint, backend)
+            })
+    }
+
+    /// Returns the endpoint and name of backends only reachable over TLS.
+    const fn tls_only_endpoint(&self) -> Option<(&url::Url, &'static str)> {
+        match self {
+            #[cfg(feature = "axiom")]
+            Self::Axiom(config) => Some((&config.endpoint, "Axiom")),
+            #[cfg(feature = "honeycomb")]
+            Self::Honeycomb(config) => Some((&config.endpoint, "Honeycomb")),
+            _ => None,
+        }
+    }
+
+    /// Returns the endpoint and timeout of OTLP-based backends.
+    ///
+    /// Uptrace has none until its DSN is resolved.
+    pub(crate) const fn otlp_target(&self) -> Option<(&url::Url, std::time::Duration)> {
+        match self {
+            #[cfg(feature = "console")]
+            Self::Console | Self::ConsoleJson => None,
+            #[cfg(feature = "axiom")]
+            Self::Axiom(config) => Some((&config.endpoint, config.timeout)),
+            #[cfg(feature = "datadog")]
+            Self::Datadog(config) => Some((&config.endpoint, config.timeout)),
+            #[cfg(feature = "honeycomb")]
+            Self::Honeycomb(config) => Some((&config.endpoint, config.timeout)),
+            #[cfg(feature = "signoz")]
+            Self::SigNoz(config) => Some((&config.endpoint, config.timeout)),
+            #[cfg(feature = "tempo")]
+            Self::Tempo(config) => Some((&config.endpoint, config.timeout)),
+            #[cfg(feature = "uptrace")]
+            Self::Uptrace(_) => None,
+            Self::Otlp(config) => Some((&config.endpoint, config.timeout)),
+        }
+    }
+
+    /// Returns the backend name, as in the serialized configuration.
+    pub(crate) const fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "console")]
+            Self::Console => "console",
+            #[cfg(feature = "console")]
+            Self::ConsoleJson => "console-json",
+            #[cfg(feature = "axiom")]
+            Self::Axiom(_) => "axiom",
+            #[cfg(feature = "datadog")]
+            Self::Datadog(_) => "datadog",
+            #[cfg(feature = "honeycomb")]
+            Self::Honeycomb(_) => "honeycomb",
+            #[cfg(feature = "signoz")]
+            Self::SigNoz(_) => "signoz",
+            #[cfg(feature = "tempo")]
+            Self::Tempo(_) => "tempo",
+            #[cfg(feature = "uptrace")]
+            Self::Uptrace(_) => "uptrace",
+            Self::Otlp(_) => "otlp",
+        }
+    }
+
+    /// Adds headers to OTLP-based backends, replacing entries with the same key.
+    pub(crate) fn extend_headers(&mut self, headers: &[(String, SecretString)]) {
+        if headers.is_empty() {
+            return;
+        }
+        self.update_otlp(|config| otlp::merge_headers(&mut config.headers, headers));
+    }
+
+    /// Replaces the retry policy of OTLP-based backends.
+    pub(crate) fn set_retry(&mut self, retry: RetryConfig) {
+        self.update_otlp(|config| config.retry = Some(retry));
+    }
+
+    /// Replaces the gRPC keepalive of OTLP-based backends.
+    pub(crate) fn set_keepalive(&mut self, keepalive: KeepaliveConfig) {
+        self.update_otlp(|config| config.keepalive = keepalive);
+    }
+
+    /// Replaces the export timeout of OTLP-based backends.
+    pub(crate) fn set_timeout(&mut self, timeout: std::time::Duration) {
+        self.update_otlp(|config| config.timeout = timeout);
+    }
+
+    /// Replaces the transport protocol of OTLP-based backends.
+    pub(crate) fn set_protocol(&mut self, protocol: Protocol) {
+        self.update_otlp(|config| config.protocol = protocol);
+    }
+
+    /// Replaces the payload compression of OTLP-based backends.
+    pub(crate) fn set_compression(&mut self, compression: Option<Compression>) {
+        self.update_otlp(|config| config.compression = compression);
+    }
+
+    /// Replaces the TLS configuration of OTLP-based backends.
+    pub(crate) fn set_tls_config(&mut self, tls: ClientTlsConfig) {
+        self.update_otlp(|config| config.tls_config = Some(tls));
+    }
+
+    /// Forces plaintext or TLS for OTLP-based backends, whatever their
+    /// endpoint scheme.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `insecure` is `true` for a backend only reachable
+    /// over TLS.
+    pub(crate) fn set_insecure(&mut self, insecure: bool) -> Result<(), Error> {
+        if insecure && let Some((_, backend)) = self.tls_only_endpoint() {
+            return Err(ErrorKind::ExporterConfig {
+                reason: format!("{backend} requires TLS and cannot be insecure"),
             }
+            .into());
         }
+        self.update_otlp(|config| config.insecure = Some(insecure));
+        Ok(())
+    }
+
+    /// Applies `update` to the OTLP configuration of OTLP-based backends.
+    ///
+    /// Vendor backends are converted to their OTLP configuration first. An
+    /// Uptrace backend with an invalid DSN is left as is, for the provider
+    /// build to report.
+    fn update_otlp(&mut self, update: impl FnOnce(&mut OtlpConfig)) {
+        let mut config = match std::mem::take(self) {
+            #[cfg(feature = "console")]
+            console @ (Self::Console | Self::ConsoleJson) => {
+                *self = console;
+                return;
+            }
+            #[cfg(feature = "axiom")]
+            Self::Axiom(config) => OtlpConfig::from(config),
+            #[cfg(feature = "datadog")]
+            Self::Datadog(config) => OtlpConfig::from(config),
+            #[cfg(feature = "honeycomb")]
+            Self::Honeycomb(config) => OtlpConfig::from(config),
+            #[cfg(feature = "signoz")]
+            Self::SigNoz(config) => OtlpConfig::from(config),
+            #[cfg(feature = "tempo")]
+            Self::Tempo(config) => OtlpConfig::from(config),
+            #[cfg(feature = "uptrace")]
+            Self::Uptrace(config) => match OtlpConfig::try_from(config.clone()) {
+                Ok(otlp) => otlp,
+                Err(_) => {
+                    *self = Self::Uptrace(config);
+                    return;
+                }
+            },
+            Self::Otlp(config) => config,
+        };
+        update(&mut config);
+        *self = Self::Otlp(config);
     }
 }
 
@@ -66,47 +433,177 @@ impl Default for TraceExporter {
     }
 }
 
-/// Builds a tracer provider from an exporter, resource, and optional sampler.
+/// Trace and span ID generation.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum IdGenerator {
+    /// Random IDs.
+    #[default]
+    Random,
+    /// AWS X-Ray IDs, with trace IDs prefixed by the epoch seconds.
+    #[cfg(feature = "xray")]
+    Xray,
+}
+
+impl IdGenerator {
+    /// Installs the ID generator on the tracer provider builder.
+    #[cfg_attr(
+        not(feature = "xray"),
+        allow(
+            clippy::missing_const_for_fn,
+            reason = "installing the X-Ray generator is not const"
+        )
+    )]
+    pub(crate) fn apply(self, builder: TracerProviderBuilder) -> TracerProviderBuilder {
+        match self {
+            Self::Random => builder,
+            #[cfg(feature = "xray")]
+            Self::Xray => {
+                builder.with_id_generator(opentelemetry_aws::trace::XrayIdGenerator::default())
+            }
+        }
+    }
+}
+
+/// Tracer provider settings shared by every backend.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TracerSetup {
+    /// Trace and span ID generator.
+    pub(crate) ids: IdGenerator,
+    /// Caps on the attributes, events and links per span.
+    pub(crate) limits: SpanLimits,
+    /// Attributes set on every span as it starts.
+    pub(crate) span_attributes: Vec<KeyValue>,
+    /// Attribute whose spans are sampled whatever the sampler decides.
+    pub(crate) always_sample_attribute: Option<String>,
+    /// Spans shorter than this are not exported.
+    pub(crate) min_span_duration: Option<std::time::Duration>,
+}
+
+impl TracerSetup {
+    /// Installs the settings and `sampler` on the tracer provider builder,
+    /// before any exporting processor.
+    ///
+    /// Without a sampler, the SDK reads `OTEL_TRACES_SAMPLER`.
+    pub(crate) fn apply(
+        self,
+        builder: TracerProviderBuilder,
+        sampler: Option<Sampler>,
+    ) -> TracerProviderBuilder {
+        let mut builder = self.limits.apply(self.ids.apply(builder));
+        builder = match (self.always_sample_attribute, sampler) {
+            (Some(attribute), sampler) => {
+                builder.with_sampler(AttributeSampler::new(attribute, sampler))
+            }
+            (None, Some(sampler)) => builder.with_sampler(sampler),
+            (None, None) => builder,
+        };
+        if self.span_attributes.is_empty() {
+            return builder;
+        }
+        builder.with_span_processor(DefaultAttributes::new(self.span_attributes))
+    }
+}
+
+/// Builds a console tracer provider, exporting on span end unless console
+/// batching is enabled.
+#[cfg(feature = "console")]
+fn build_console_provider<E>(
+    exporter: E,
+    resource: Resource,
+    sampler: Option<Sampler>,
+    batch: BatchConfig,
+    setup: TracerSetup,
+    redaction: FieldRedaction,
+) -> Result<SdkTracerProvider, Error>
+where
+    E: opentelemetry_sdk::trace::SpanExporter + 'static,
+{
+    let exporter = RedactingExporter::new(exporter, redaction);
+    let min_span_duration = setup.min_span_duration;
+    let builder = setup
+        .apply(SdkTracerProvider::builder(), sampler)
+        .with_resource(resource);
+    let builder = if batch.console {
+        batch.add_processor(builder, exporter, min_span_duration)?
+    } else {
+        let processor = SimpleSpanProcessor::new(exporter);
+        with_exporting_processor(builder, processor, min_span_duration)
+    };
+    Ok(builder.build())
+}
+
+/// Builds a batching tracer provider from an exporter, resource, and optional
+/// sampler, retrying failed exports when a policy is given.
 fn build_tracer_provider(
     exporter: SpanExporter,
+    retry: Option<RetryConfig>,
     resource: Resource,
     sampler: Option<Sampler>,
+    batch: BatchConfig,
+    setup: TracerSetup,
+    redaction: FieldRedaction,
 ) -> Result<SdkTracerProvider, Error> {
-    let mut builder = SdkTracerProvider::builder().with_resource(resource);
-    match sampler {
-        Some(sampler) => {
-            builder = builder.with_sampler(sampler);
-        }
-        None => {
-            if let Ok(sampler) = std::env::var(env_vars::OTEL_TRACES_SAMPLER) {
+    let exporter = RedactingExporter::new(exporter, redaction);
+    let sampler = match sampler {
+        Some(sampler) => Some(sampler),
+        None => std::env::var(env_vars::OTEL_TRACES_SAMPLER)
+            .ok()
+            .map(|sampler| {
                 let arg = std::env::var(env_vars::OTEL_TRACES_SAMPLER_ARG).ok();
-                let sampler = parse_sampler(&sampler, arg.as_deref())?;
-                builder = builder.with_sampler(sampler);
-            }
+                parse_sampler(&sampler, arg.as_deref())
+            })
+            .transpose()?,
+    };
+    let min_span_duration = setup.min_span_duration;
+    let builder = setup
+        .apply(SdkTracerProvider::builder(), sampler)
+        .with_resource(resource);
+
+    let builder = match retry {
+        Some(retry) => {
+            retry.validate()?;
+            let exporter = RetryingExporter::new(exporter, retry);
+            batch.add_processor(builder, exporter, min_span_duration)?
         }
-    }
+        None => batch.add_processor(builder, exporter, min_span_duration)?,
+    };
+    Ok(builder.build())
+}
 
-    Ok(builder.with_batch_exporter(exporter).build())
+/// Supported `OTEL_TRACES_SAMPLER` names.
+#[cfg(feature = "clap")]
+pub(crate) const SAMPLER_NAMES: &[&str] = &[
+    "always_on",
+    "always_off",
+    "traceidratio",
+    "parentbased_always_on",
+    "parentbased_always_off",
+    "parentbased_traceidratio",
+];
+
+/// Checks that a sampler argument is a ratio between 0.0 and 1.0.
+#[cfg(feature = "clap")]
+pub(crate) fn parse_sampler_arg(arg: &str) -> Result<String, String> {
+    match arg.parse::<f64>() {
+        Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(arg.to_owned()),
+        Ok(ratio) => Err(format!("sampler ratio {ratio} is not between 0.0 and 1.0")),
+        Err(err) => Err(format!(
+            "expected a sampler ratio between 0.0 and 1.0: {err}"
+        )),
+    }
 }
 
-/// Parses trace sampler
-fn parse_sampler(name: &str, arg: Option<&str>) -> Result<Sampler, Error> {
+/// Parses a trace sampler from its `OTEL_TRACES_SAMPLER` name and argument.
+pub(crate) fn parse_sampler(name: &str, arg: Option<&str>) -> Result<Sampler, Error> {
     match name {
         "always_on" => Ok(Sampler::AlwaysOn),
         "always_off" => Ok(Sampler::AlwaysOff),
-        "traceidratio" => {
-            let ratio: f64 = arg
-                .ok_or_else(|| ErrorKind::ExporterConfig {
-                    reason: String::from("missing trace id ratio"),
-                })?
-                .parse()
-                .map_err(|err| ErrorKind::ExporterConfig {
-                    reason: format!("unable to parse trace id argument `{err}`"),
-                })?;
-            Ok(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
-                ratio,
-            ))))
-        }
+        "traceidratio" => Ok(Sampler::TraceIdRatioBased(parse_ratio(arg)?)),
+        "parentbased_always_on" => Ok(Sampler::ParentBased(Box::new(Sampler::AlwaysOn))),
+        "parentbased_always_off" => Ok(Sampler::ParentBased(Box::new(Sampler::AlwaysOff))),
+        "parentbased_traceidratio" => Ok(Sampler::ParentBased(Box::new(
+            Sampler::TraceIdRatioBased(parse_ratio(arg)?),
+        ))),
         other => Err(ErrorKind::ExporterConfig {
             reason: format!("invalid sampler `{other}`"),
         }
@@ -114,16 +611,110 @@ fn parse_sampler(name: &str, arg: Option<&str>) -> Result<Sampler, Error> {
     }
 }
 
+/// Parses a sampling ratio, which must be within `0.0..=1.0`.
+fn parse_ratio(arg: Option<&str>) -> Result<f64, Error> {
+    let ratio: f64 = arg
+        .ok_or_else(|| ErrorKind::ExporterConfig {
+            reason: String::from("missing trace id ratio"),
+        })?
+        .parse()
+        .map_err(|err| ErrorKind::ExporterConfig {
+            reason: format!("unable to parse trace id argument `{err}`"),
+        })?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(ErrorKind::InvalidSamplerRatio { ratio }.into());
+    }
+    Ok(ratio)
+}
+
 #[cfg(test)]
 mod tests {
+    use googletest::assert_that;
     use googletest::expect_that;
     use googletest::gtest;
     use googletest::matchers::anything;
+    use googletest::matchers::eq;
     use googletest::matchers::err;
     use googletest::matchers::ok;
+    use googletest::matchers::some;
+    use rstest::rstest;
 
     use super::*;
 
+    #[cfg(feature = "uptrace")]
+    #[gtest]
+    fn uptrace_dsn_resolves_to_its_endpoint() {
+        let mut exporter = TraceExporter::uptrace("https://token@api.uptrace.dev".into());
+        expect_that!(exporter.otlp_target(), googletest::matchers::none());
+        exporter.resolve_dsn().expect("valid DSN");
+        expect_that!(
+            exporter
+                .otlp_target()
+                .map(|(endpoint, _)| endpoint.as_str()),
+            some(eq("https://api.uptrace.dev:4317/"))
+        );
+    }
+
+    #[cfg(feature = "uptrace")]
+    #[gtest]
+    fn invalid_uptrace_dsn_fails_the_build() {
+        let mut exporter = TraceExporter::uptrace("api.uptrace.dev".into());
+        exporter.set_timeout(std::time::Duration::from_secs(1));
+        expect_that!(exporter.clone().resolve_dsn(), err(anything()));
+        expect_that!(
+            exporter.build_provider(Resource::builder().build(), None, BatchConfig::default()),
+            err(anything())
+        );
+    }
+
+    #[gtest]
+    fn exporter_timeout_overrides_the_backend_timeout() {
+        let mut exporter = TraceExporter::Otlp(
+            OtlpConfig::builder()
+                .endpoint("http://collector:4317".parse().expect("to be valid"))
+                .timeout(std::time::Duration::from_secs(10))
+                .build(),
+        );
+        exporter.set_timeout(std::time::Duration::from_millis(1500));
+        expect_that!(
+            exporter.otlp_target().map(|(_, timeout)| timeout),
+            some(eq(std::time::Duration::from_millis(1500)))
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[gtest]
+    fn exporter_protocol_overrides_the_backend_protocol() {
+        let mut exporter = TraceExporter::Otlp(
+            OtlpConfig::builder()
+                .endpoint("http://collector:4317".parse().expect("to be valid"))
+                .timeout(std::time::Duration::from_secs(10))
+                .build(),
+        );
+        exporter.set_protocol(Protocol::HttpJson);
+        expect_that!(
+            matches!(exporter, TraceExporter::Otlp(config) if config.protocol == Protocol::HttpJson),
+            eq(true)
+        );
+    }
+
+    #[cfg(feature = "honeycomb")]
+    #[gtest]
+    fn exporter_compression_overrides_vendor_backends() {
+        let mut exporter = TraceExporter::Honeycomb(
+            HoneycombConfig::builder()
+                .endpoint("https://api.honeycomb.io:443".parse().expect("valid URL"))
+                .api_key("hc-secret".into())
+                .timeout(std::time::Duration::from_secs(10))
+                .build(),
+        );
+        exporter.set_compression(Some(Compression::Gzip));
+        let TraceExporter::Otlp(config) = exporter else {
+            panic!("Honeycomb to be converted to OTLP");
+        };
+        expect_that!(config.compression, some(eq(Compression::Gzip)));
+    }
+
     #[gtest]
     fn parse_sampler_always_on() {
         let sampler = parse_sampler("always_on", None);
@@ -148,9 +739,36 @@ mod tests {
         expect_that!(sampler, err(anything()));
     }
 
+    #[gtest]
+    fn parse_sampler_parentbased_traceidratio() {
+        let sampler = parse_sampler("parentbased_traceidratio", Some("0.25"));
+        expect_that!(sampler, ok(anything()));
+    }
+
+    #[rstest]
+    #[case("-0.1")]
+    #[case("1.5")]
+    #[case("NaN")]
+    fn parse_sampler_rejects_out_of_range_ratio(#[case] ratio: &str) {
+        let sampler = parse_sampler("traceidratio", Some(ratio));
+        assert_that!(sampler, err(anything()));
+    }
+
     #[gtest]
     fn parse_sampler_invalid_name() {
         let sampler = parse_sampler("bogus", None);
         expect_that!(sampler, err(anything()));
     }
+
+    #[cfg(feature = "console")]
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    fn console_provider_builds(#[case] batched: bool) {
+        let batch = BatchConfig::builder().console(batched).build();
+        let provider =
+            TraceExporter::Console.build_provider(Resource::builder().build(), None, batch);
+        let provider = provider.expect("console provider");
+        assert_that!(provider.shutdown(), ok(anything()));
+    }
 }
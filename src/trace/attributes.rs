@@ -0,0 +1,87 @@
+//! Attributes added to every span.
+
+use std::time::Duration;
+
+use opentelemetry::Context;
+use opentelemetry::KeyValue;
+use opentelemetry::trace::Span as _;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::trace::Span;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanProcessor;
+
+/// Span processor setting fixed attributes on every span as it starts.
+///
+/// Unlike resource attributes, these are recorded on each span, next to the
+/// attributes of the span itself.
+#[derive(Debug)]
+pub(crate) struct DefaultAttributes {
+    attributes: Vec<KeyValue>,
+}
+
+impl DefaultAttributes {
+    /// Creates a processor setting `attributes`.
+    pub(crate) const fn new(attributes: Vec<KeyValue>) -> Self {
+        Self { attributes }
+    }
+}
+
+impl SpanProcessor for DefaultAttributes {
+    fn on_start(&self, span: &mut Span, _cx: &Context) {
+        span.set_attributes(self.attributes.iter().cloned());
+    }
+
+    fn on_end(&self, _span: SpanData) {}
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains;
+    use googletest::matchers::eq;
+    use googletest::matchers::len;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    use super::*;
+
+    #[gtest]
+    fn tracing_spans_carry_the_default_attributes() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_span_processor(DefaultAttributes::new(vec![
+                KeyValue::new("region", "eu-west-1"),
+                KeyValue::new("cluster", "blue"),
+            ]))
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("request").entered();
+        });
+
+        let spans = exporter.get_finished_spans().expect("spans collected");
+        assert_that!(spans, len(eq(1)));
+        expect_that!(
+            spans[0].attributes,
+            contains(eq(&KeyValue::new("region", "eu-west-1")))
+        );
+        expect_that!(
+            spans[0].attributes,
+            contains(eq(&KeyValue::new("cluster", "blue")))
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Axiom configuration.
+
+use std::time::Duration;
+
+use bon::Builder;
+use secrecy::SecretString;
+use url::Url;
+
+use crate::Compression;
+use crate::OtlpConfig;
+use crate::OtlpTokenConfig;
+use crate::Protocol;
+
+/// Header naming the Axiom dataset receiving the spans.
+const DATASET_HEADER: &str = "x-axiom-dataset";
+
+/// Configuration for [Axiom](https://axiom.co) trace export.
+#[derive(Debug, Clone, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct AxiomConfig {
+    /// Exporter endpoint.
+    pub endpoint: Url,
+    /// API token, sent as a bearer token.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
+    pub token: SecretString,
+    /// Dataset receiving the spans.
+    #[builder(into)]
+    pub dataset: String,
+    /// Export timeout, see [`OtlpConfig::timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
+    pub timeout: Duration,
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+}
+
+impl From<AxiomConfig> for OtlpConfig {
+    fn from(config: AxiomConfig) -> Self {
+        let mut otlp = OtlpConfig::from(OtlpTokenConfig {
+            timeout: config.timeout,
+            protocol: config.protocol,
+            compression: config.compression,
+            ..OtlpTokenConfig::axiom(config.endpoint, &config.token)
+        });
+        otlp.headers
+            .push((DATASET_HEADER.to_owned(), config.dataset));
+        otlp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::eq;
+    use googletest::matchers::unordered_elements_are;
+
+    use super::*;
+
+    #[gtest]
+    fn token_and_dataset_are_sent_as_headers() {
+        let config = AxiomConfig::builder()
+            .endpoint("https://api.axiom.co:443".parse().expect("valid URL"))
+            .token("xaat-secret".into())
+            .dataset("traces")
+            .timeout(Duration::from_secs(5))
+            .build();
+        let otlp = OtlpConfig::from(config);
+        expect_that!(
+            otlp.headers,
+            unordered_elements_are![
+                eq(&("authorization".to_owned(), "Bearer xaat-secret".to_owned())),
+                eq(&("x-axiom-dataset".to_owned(), "traces".to_owned())),
+            ]
+        );
+        expect_that!(otlp.timeout, eq(Duration::from_secs(5)));
+    }
+}
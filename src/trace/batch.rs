@@ -0,0 +1,290 @@
+//! Batch span processor configuration.
+
+use std::time::Duration;
+
+use bon::Builder;
+#[cfg(feature = "clap")]
+use clap::Args;
+use jiff::SignedDuration;
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::trace::BatchSpanProcessor;
+use opentelemetry_sdk::trace::SpanExporter;
+use opentelemetry_sdk::trace::TracerProviderBuilder;
+use opentelemetry_sdk::trace::span_processor_with_async_runtime::BatchSpanProcessor as AsyncBatchSpanProcessor;
+
+use super::with_exporting_processor;
+#[cfg(feature = "clap")]
+use crate::env_vars;
+use crate::error::Error;
+use crate::error::ErrorKind;
+
+/// Tuning for the batch span processor used by OTLP backends, and optionally
+/// by the console exporters.
+///
+/// Unset values fall back to the `OTEL_BSP_*` variables, then to the SDK
+/// defaults.
+#[must_use]
+#[derive(Clone, Debug, Default, Builder)]
+#[cfg_attr(feature = "clap", derive(Args))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BatchConfig {
+    /// Maximum number of spans buffered before new spans are dropped.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "batch-max-queue-size",
+            long,
+            help = "Maximum number of spans buffered for export",
+            env = env_vars::OTEL_BSP_MAX_QUEUE_SIZE,
+        )
+    )]
+    pub max_queue_size: Option<usize>,
+
+    /// Maximum number of spans sent in a single export.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "batch-max-export-size",
+            long,
+            help = "Maximum number of spans per export batch",
+            env = env_vars::OTEL_BSP_MAX_EXPORT_BATCH_SIZE,
+        )
+    )]
+    pub max_export_batch_size: Option<usize>,
+
+    /// Delay between two consecutive exports.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "batch-scheduled-delay",
+            long,
+            help = "Delay between two batch exports, in milliseconds or as a duration (e.g. 5s)",
+            value_parser = parse_millis_or_duration,
+            env = env_vars::OTEL_BSP_SCHEDULE_DELAY,
+        )
+    )]
+    pub scheduled_delay: Option<SignedDuration>,
+
+    /// Maximum time allowed for one batch export on the Tokio runtimes. The
+    /// thread runtime relies on the exporter timeout instead, see
+    /// [`OtlpConfig::timeout`](crate::OtlpConfig::timeout).
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "batch-export-timeout",
+            long,
+            help = "Maximum time of a batch export on the Tokio runtimes, in milliseconds or as a duration (e.g. 30s)",
+            value_parser = parse_millis_or_duration,
+            env = env_vars::OTEL_BSP_EXPORT_TIMEOUT,
+        )
+    )]
+    pub export_timeout: Option<SignedDuration>,
+
+    /// Batches the console exporters, which export synchronously on span end
+    /// by default.
+    #[cfg(feature = "console")]
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "batch-console",
+            long,
+            help = "Export console spans in batches instead of on span end"
+        )
+    )]
+    #[builder(default)]
+    pub console: bool,
+
+    /// Runtime running the batch export. Defaults to a dedicated thread.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "batch-runtime",
+            long,
+            value_enum,
+            default_value = "thread",
+            help = "Runtime running the batch export"
+        )
+    )]
+    #[builder(default)]
+    pub runtime: BatchRuntime,
+}
+
+/// Parses integer milliseconds, as the `OTEL_BSP_*` variables define, or a
+/// duration such as `5s`.
+#[cfg(feature = "clap")]
+fn parse_millis_or_duration(value: &str) -> Result<SignedDuration, jiff::Error> {
+    let value = value.trim();
+    value.parse::<i64>().map_or_else(
+        |_| value.parse(),
+        |millis| Ok(SignedDuration::from_millis(millis)),
+    )
+}
+
+/// Runtime running the batch span export.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum BatchRuntime {
+    /// Dedicated background thread of the SDK, independent of any async
+    /// runtime.
+    #[default]
+    Thread,
+    /// Task spawned on the Tokio runtime the providers are built in, which
+    /// must be a multi-thread runtime.
+    Tokio,
+    /// Task running on a current-thread Tokio runtime of its own, in a
+    /// separate thread. Use it from a `current_thread` Tokio runtime, where a
+    /// task of the application runtime would hang on shutdown.
+    TokioCurrentThread,
+}
+
+impl BatchConfig {
+    /// Builds the SDK batch configuration, which reads the `OTEL_BSP_*`
+    /// variables for the unset values.
+    pub(crate) fn build(self) -> Result<opentelemetry_sdk::trace::BatchConfig, Error> {
+        let mut builder = opentelemetry_sdk::trace::BatchConfigBuilder::default();
+        if let Some(size) = self.max_queue_size {
+            builder = builder.with_max_queue_size(size);
+        }
+        if let Some(size) = self.max_export_batch_size {
+            builder = builder.with_max_export_batch_size(size);
+        }
+        if let Some(delay) = self.scheduled_delay {
+            let delay = Duration::try_from(delay).map_err(|err| ErrorKind::ExporterConfig {
+                reason: format!("invalid batch scheduled delay: {err}"),
+            })?;
+            builder = builder.with_scheduled_delay(delay);
+        }
+        if let Some(timeout) = self.export_timeout {
+            let timeout = Duration::try_from(timeout).map_err(|err| ErrorKind::ExporterConfig {
+                reason: format!("invalid batch export timeout: {err}"),
+            })?;
+            builder = builder.with_max_export_timeout(timeout);
+        }
+        Ok(builder.build())
+    }
+
+    /// Adds a batch processor exporting to `exporter` on the configured
+    /// runtime, dropping the spans shorter than `min_span_duration`.
+    pub(crate) fn add_processor<E>(
+        self,
+        builder: TracerProviderBuilder,
+        exporter: E,
+        min_span_duration: Option<Duration>,
+    ) -> Result<TracerProviderBuilder, Error>
+    where
+        E: SpanExporter + 'static,
+    {
+        let runtime = self.runtime;
+        let config = self.build()?;
+        let builder = match runtime {
+            BatchRuntime::Thread => {
+                let processor = BatchSpanProcessor::builder(exporter)
+                    .with_batch_config(config)
+                    .build();
+                with_exporting_processor(builder, processor, min_span_duration)
+            }
+            BatchRuntime::Tokio => {
+                let processor = AsyncBatchSpanProcessor::builder(exporter, runtime::Tokio)
+                    .with_batch_config(config)
+                    .build();
+                with_exporting_processor(builder, processor, min_span_duration)
+            }
+            BatchRuntime::TokioCurrentThread => {
+                let processor =
+                    AsyncBatchSpanProcessor::builder(exporter, runtime::TokioCurrentThread)
+                        .with_batch_config(config)
+                        .build();
+                with_exporting_processor(builder, processor, min_span_duration)
+            }
+        };
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::len;
+    use googletest::matchers::ok;
+    use opentelemetry::trace::Tracer as _;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[gtest]
+    fn default_batch_config_builds() {
+        expect_that!(BatchConfig::default().build(), ok(anything()));
+    }
+
+    #[gtest]
+    fn batch_config_with_values_builds() {
+        let config = BatchConfig::builder()
+            .max_queue_size(4096)
+            .max_export_batch_size(1024)
+            .scheduled_delay(SignedDuration::from_millis(500))
+            .build();
+        expect_that!(config.build(), ok(anything()));
+    }
+
+    #[gtest]
+    fn negative_scheduled_delay_is_rejected() {
+        let config = BatchConfig::builder()
+            .scheduled_delay(SignedDuration::from_secs(-1))
+            .build();
+        expect_that!(config.build(), err(anything()));
+    }
+
+    #[gtest]
+    fn negative_export_timeout_is_rejected() {
+        let config = BatchConfig::builder()
+            .export_timeout(SignedDuration::from_secs(-1))
+            .build();
+        expect_that!(config.build(), err(anything()));
+    }
+
+    #[cfg(feature = "clap")]
+    #[rstest]
+    #[case("5000", SignedDuration::from_secs(5))]
+    #[case(" 250 ", SignedDuration::from_millis(250))]
+    #[case("500ms", SignedDuration::from_millis(500))]
+    #[case("30s", SignedDuration::from_secs(30))]
+    fn delays_accept_millis_and_durations(#[case] value: &str, #[case] expected: SignedDuration) {
+        assert_that!(parse_millis_or_duration(value), ok(eq(&expected)));
+    }
+
+    #[rstest]
+    #[case(BatchRuntime::Thread)]
+    #[case(BatchRuntime::Tokio)]
+    #[case(BatchRuntime::TokioCurrentThread)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn every_runtime_exports_on_flush(#[case] runtime: BatchRuntime) {
+        let exporter = InMemorySpanExporter::default();
+        let builder = BatchConfig::builder()
+            .runtime(runtime)
+            .build()
+            .add_processor(SdkTracerProvider::builder(), exporter.clone(), None)
+            .expect("valid batch config");
+        let provider = builder.build();
+
+        provider.tracer("test").in_span("request", |_cx| {});
+        provider.force_flush().expect("spans flushed");
+
+        assert_that!(exporter.get_finished_spans().expect("spans"), len(eq(1)));
+        provider.shutdown().expect("provider shut down");
+    }
+}
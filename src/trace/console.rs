@@ -0,0 +1,84 @@
+//! Console span export as OTLP JSON.
+
+use std::io::Write;
+
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkError;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanExporter;
+
+/// Span exporter writing one OTLP JSON export request per line to stdout.
+#[derive(Debug, Default)]
+pub(crate) struct OtlpJsonExporter {
+    resource: ResourceAttributesWithSchema,
+}
+
+impl OtlpJsonExporter {
+    /// Encodes a batch as a single-line OTLP JSON export request.
+    fn encode(&self, batch: Vec<SpanData>) -> Result<String, OTelSdkError> {
+        let request = ExportTraceServiceRequest {
+            resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+        };
+        serde_json::to_string(&request)
+            .map_err(|err| OTelSdkError::InternalFailure(err.to_string()))
+    }
+}
+
+impl SpanExporter for OtlpJsonExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let line = self.encode(batch)?;
+        writeln!(std::io::stdout().lock(), "{line}")
+            .map_err(|err| OTelSdkError::InternalFailure(err.to_string()))
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::not;
+    use googletest::matchers::ok;
+    use opentelemetry::KeyValue;
+    use opentelemetry::trace::Tracer;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+
+    use super::*;
+
+    fn finished_spans() -> Vec<SpanData> {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        provider.tracer("console").in_span("checkout", |_| {});
+        exporter.get_finished_spans().expect("spans to be recorded")
+    }
+
+    #[gtest]
+    fn batch_is_encoded_as_single_line_otlp_json() {
+        let mut exporter = OtlpJsonExporter::default();
+        exporter.set_resource(
+            &Resource::builder_empty()
+                .with_attribute(KeyValue::new("service.name", "shop"))
+                .build(),
+        );
+        let line = exporter.encode(finished_spans());
+        expect_that!(line, ok(contains_substring("\"resourceSpans\"")));
+        expect_that!(line, ok(contains_substring("\"name\":\"checkout\"")));
+        expect_that!(line, ok(contains_substring("\"shop\"")));
+        expect_that!(line, ok(not(contains_substring("\n"))));
+    }
+}
@@ -0,0 +1,96 @@
+//! Datadog configuration.
+
+use std::time::Duration;
+
+use bon::Builder;
+use secrecy::SecretString;
+use url::Url;
+
+use crate::Compression;
+use crate::OtlpConfig;
+use crate::OtlpTokenConfig;
+use crate::Protocol;
+
+/// Configuration for [Datadog](https://www.datadoghq.com) OTLP trace intake.
+#[derive(Debug, Clone, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct DatadogConfig {
+    /// Exporter endpoint.
+    pub endpoint: Url,
+    /// API key.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
+    pub api_key: SecretString,
+    /// Export timeout, see [`OtlpConfig::timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
+    pub timeout: Duration,
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+}
+
+impl From<DatadogConfig> for OtlpTokenConfig {
+    fn from(config: DatadogConfig) -> Self {
+        Self {
+            timeout: config.timeout,
+            protocol: config.protocol,
+            compression: config.compression,
+            ..Self::datadog(config.endpoint, config.api_key)
+        }
+    }
+}
+
+impl From<DatadogConfig> for OtlpConfig {
+    fn from(config: DatadogConfig) -> Self {
+        OtlpTokenConfig::from(config).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::eq;
+    use googletest::matchers::not;
+
+    use super::*;
+
+    fn config() -> DatadogConfig {
+        DatadogConfig::builder()
+            .endpoint(
+                "https://trace.agent.datadoghq.com"
+                    .parse()
+                    .expect("valid URL"),
+            )
+            .api_key("dd-secret".into())
+            .timeout(Duration::from_secs(5))
+            .build()
+    }
+
+    #[gtest]
+    fn api_key_is_sent_as_header() {
+        let otlp = OtlpConfig::from(config());
+        expect_that!(
+            otlp.headers,
+            contains(eq(&("dd-api-key".to_owned(), "dd-secret".to_owned())))
+        );
+    }
+
+    #[gtest]
+    fn api_key_is_redacted_from_debug() {
+        expect_that!(
+            format!("{:?}", config()),
+            not(contains_substring("dd-secret"))
+        );
+    }
+}
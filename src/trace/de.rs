@@ -0,0 +1,321 @@
+//! Deserialization of [`TraceExporter`].
+//!
+//! Both the externally tagged shape (`[traces.otlp]`, `traces = "console"`) and
+//! the internally tagged shape (`type = "otlp"` next to the backend fields) are
+//! accepted, as well as the string shorthand parsed by
+//! [`TraceExporter::from_str`](std::str::FromStr::from_str).
+//!
+//! Backend timeouts are read as milliseconds, a duration string such as
+//! `"5s"`, or serde's `{ secs, nanos }` form.
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::de::Error as _;
+use serde::de::MapAccess;
+use serde::de::Visitor;
+use serde::de::value::MapAccessDeserializer;
+use serde_json::Value;
+
+#[cfg(feature = "axiom")]
+use super::AxiomConfig;
+#[cfg(feature = "datadog")]
+use super::DatadogConfig;
+#[cfg(feature = "honeycomb")]
+use super::HoneycombConfig;
+use super::OtlpConfig;
+#[cfg(feature = "signoz")]
+use super::SigNozConfig;
+#[cfg(feature = "tempo")]
+use super::TempoConfig;
+use super::TraceExporter;
+#[cfg(feature = "uptrace")]
+use super::UptraceConfig;
+
+/// Declares a mirror of [`TraceExporter`] with the given serde representation.
+macro_rules! representation {
+    ($name:ident, $($serde:tt)*) => {
+        #[allow(
+            clippy::large_enum_variant,
+            reason = "short-lived init value, not stored"
+        )]
+        #[derive(Deserialize)]
+        #[serde($($serde)*)]
+        enum $name {
+            #[cfg(feature = "console")]
+            Console,
+            #[cfg(feature = "console")]
+            ConsoleJson,
+            #[cfg(feature = "axiom")]
+            Axiom(AxiomConfig),
+            #[cfg(feature = "datadog")]
+            Datadog(DatadogConfig),
+            #[cfg(feature = "honeycomb")]
+            Honeycomb(HoneycombConfig),
+            #[cfg(feature = "signoz")]
+            #[serde(rename = "signoz")]
+            SigNoz(SigNozConfig),
+            #[cfg(feature = "tempo")]
+            Tempo(TempoConfig),
+            #[cfg(feature = "uptrace")]
+            Uptrace(UptraceConfig),
+            Otlp(OtlpConfig),
+        }
+
+        impl From<$name> for TraceExporter {
+            fn from(exporter: $name) -> Self {
+                match exporter {
+                    #[cfg(feature = "console")]
+                    $name::Console => Self::Console,
+                    #[cfg(feature = "console")]
+                    $name::ConsoleJson => Self::ConsoleJson,
+                    #[cfg(feature = "axiom")]
+                    $name::Axiom(config) => Self::Axiom(config),
+                    #[cfg(feature = "datadog")]
+                    $name::Datadog(config) => Self::Datadog(config),
+                    #[cfg(feature = "honeycomb")]
+                    $name::Honeycomb(config) => Self::Honeycomb(config),
+                    #[cfg(feature = "signoz")]
+                    $name::SigNoz(config) => Self::SigNoz(config),
+                    #[cfg(feature = "tempo")]
+                    $name::Tempo(config) => Self::Tempo(config),
+                    #[cfg(feature = "uptrace")]
+                    $name::Uptrace(config) => Self::Uptrace(config),
+                    $name::Otlp(config) => Self::Otlp(config),
+                }
+            }
+        }
+    };
+}
+
+representation!(Tagged, tag = "type", rename_all = "kebab-case");
+representation!(External, rename_all = "kebab-case");
+
+/// Key naming the backend in the internally tagged shape.
+const TAG: &str = "type";
+
+/// Description of the accepted shapes, for mismatching values.
+const EXPECTING: &str = "a trace backend, either `{ type = \"otlp\", ... }`, `{ otlp = { ... } }` or a shorthand string";
+
+impl<'de> Deserialize<'de> for TraceExporter {
+    /// Picks the shape first, a table with a `type` key being internally
+    /// tagged, a single-key table externally tagged and a string a shorthand,
+    /// so that the errors of that shape come through.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(value) => value.parse().map_err(D::Error::custom),
+            Value::Object(table) if table.contains_key(TAG) => {
+                Tagged::deserialize(Value::Object(table))
+                    .map(Into::into)
+                    .map_err(D::Error::custom)
+            }
+            Value::Object(table) if table.len() == 1 => External::deserialize(Value::Object(table))
+                .map(Into::into)
+                .map_err(D::Error::custom),
+            _ => Err(D::Error::custom(format_args!(
+                "invalid trace backend, expected {EXPECTING}"
+            ))),
+        }
+    }
+}
+
+/// Deserializes a backend timeout from milliseconds, a duration string such
+/// as `"5s"` or serde's `{ secs, nanos }` form.
+pub(crate) fn deserialize_timeout<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Duration, D::Error> {
+    deserializer.deserialize_any(TimeoutVisitor)
+}
+
+/// Visitor of [`deserialize_timeout`].
+struct TimeoutVisitor;
+
+impl<'de> Visitor<'de> for TimeoutVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("milliseconds or a duration such as `10s`")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, millis: u64) -> Result<Duration, E> {
+        Ok(Duration::from_millis(millis))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, millis: i64) -> Result<Duration, E> {
+        u64::try_from(millis)
+            .map(Duration::from_millis)
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(millis), &self))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Duration, E> {
+        crate::env_vars::parse_timeout(value).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Duration, A::Error> {
+        Duration::deserialize(MapAccessDeserializer::new(map))
+    }
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::displays_as;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::some;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Config {
+        traces: TraceExporter,
+    }
+
+    fn parse(toml: &str) -> Result<TraceExporter, toml::de::Error> {
+        toml::from_str::<Config>(toml).map(|config| config.traces)
+    }
+
+    const TIMEOUT: &str = "timeout = \"5s\"";
+
+    /// Serializes `exporter` and deserializes it back.
+    fn round_trip(exporter: &TraceExporter) -> TraceExporter {
+        let value = serde_json::to_value(exporter).expect("serializable backend");
+        serde_json::from_value(value).expect("deserializable backend")
+    }
+
+    /// Serializes `exporter` in the externally tagged shape.
+    fn externally_tagged(exporter: &TraceExporter) -> Value {
+        let Value::Object(mut fields) = serde_json::to_value(exporter).expect("serializable")
+        else {
+            panic!("backend to serialize to a table");
+        };
+        let Some(Value::String(name)) = fields.remove(TAG) else {
+            panic!("backend to serialize its type");
+        };
+        let fields = if fields.is_empty() {
+            Value::Null
+        } else {
+            Value::Object(fields)
+        };
+        serde_json::json!({ name: fields })
+    }
+
+    #[rstest]
+    #[case("otlp", "")]
+    #[cfg_attr(
+        feature = "axiom",
+        case("axiom", "token = \"token\"\ndataset = \"traces\"")
+    )]
+    #[cfg_attr(feature = "datadog", case("datadog", "api_key = \"key\""))]
+    #[cfg_attr(feature = "honeycomb", case("honeycomb", "api_key = \"key\""))]
+    #[cfg_attr(feature = "signoz", case("signoz", "access_token = \"token\""))]
+    #[cfg_attr(
+        feature = "tempo",
+        case("tempo", "username = \"user\"\npassword = \"secret\"")
+    )]
+    #[cfg_attr(
+        feature = "uptrace",
+        case("uptrace", "dsn = \"https://token@api.uptrace.dev\"")
+    )]
+    fn backends_round_trip(#[case] name: &str, #[case] fields: &str) {
+        let exporter = parse(&format!(
+            "[traces]\ntype = \"{name}\"\nendpoint = \"http://collector:4317\"\n{TIMEOUT}\n{fields}"
+        ))
+        .expect("internally tagged backend");
+        let expected = format!("{exporter:?}");
+        assert_that!(format!("{:?}", round_trip(&exporter)), eq(&expected));
+
+        let external: TraceExporter = serde_json::from_value(externally_tagged(&exporter))
+            .expect("externally tagged backend");
+        assert_that!(format!("{external:?}"), eq(&expected));
+    }
+
+    #[cfg(feature = "console")]
+    #[rstest]
+    #[case("console")]
+    #[case("console-json")]
+    fn console_backends_round_trip(#[case] name: &str) {
+        let exporter = parse(&format!("traces = {{ type = \"{name}\" }}")).expect("tagged console");
+        let expected = format!("{exporter:?}");
+        assert_that!(format!("{:?}", round_trip(&exporter)), eq(&expected));
+
+        let external: TraceExporter = serde_json::from_value(externally_tagged(&exporter))
+            .expect("externally tagged console");
+        assert_that!(format!("{external:?}"), eq(&expected));
+        let shorthand = parse(&format!("traces = \"{name}\"")).expect("shorthand console");
+        assert_that!(format!("{shorthand:?}"), eq(&expected));
+    }
+
+    #[rstest]
+    #[case("\"5s\"")]
+    #[case("\"PT5S\"")]
+    #[case("5000")]
+    #[case("{ secs = 5, nanos = 0 }")]
+    fn timeouts_accept_millis_and_durations(#[case] timeout: &str) {
+        let exporter = parse(&format!(
+            "[traces.otlp]\nendpoint = \"http://collector:4317\"\ntimeout = {timeout}"
+        ));
+        let TraceExporter::Otlp(config) = exporter.expect("OTLP backend") else {
+            panic!("OTLP backend");
+        };
+        assert_that!(config.timeout, eq(Duration::from_secs(5)));
+    }
+
+    #[rstest]
+    #[case(
+        "type = \"otlp\"\nendpoint = \"http://collector:4317\"\ntimeout = \"soon\"",
+        "invalid timeout"
+    )]
+    #[case(
+        "type = \"otlp\"\nendpoint = \"collector\"\ntimeout = 0",
+        "relative URL"
+    )]
+    #[case(
+        "otlp = { endpoint = \"http://collector:4317\", timeout = -5 }",
+        "invalid value"
+    )]
+    #[case("otlp = { timeout = 0 }", "missing field `endpoint`")]
+    fn field_errors_come_through(#[case] traces: &str, #[case] message: &str) {
+        let toml = format!("[traces]\n{traces}");
+        assert_that!(parse(&toml), err(displays_as(contains_substring(message))));
+    }
+
+    #[gtest]
+    fn table_of_several_backends_is_rejected() {
+        expect_that!(
+            parse("[traces]\notlp = {}\ndatadog = {}"),
+            err(displays_as(contains_substring("invalid trace backend")))
+        );
+    }
+
+    #[gtest]
+    fn unknown_backend_is_rejected() {
+        expect_that!(parse("traces = { type = \"jaeger\" }"), err(anything()));
+    }
+
+    #[gtest]
+    fn shorthand_string_is_accepted() {
+        let exporter = parse("traces = \"otlp@http://collector:4317\"").expect("shorthand");
+        expect_that!(
+            exporter.otlp_target().map(|(url, _)| url.to_string()),
+            some(eq("http://collector:4317/"))
+        );
+    }
+
+    #[gtest]
+    fn unsupported_shorthand_is_rejected() {
+        expect_that!(
+            parse("traces = \"zipkin@http://collector:9411\""),
+            err(displays_as(contains_substring(
+                "unsupported trace collector"
+            )))
+        );
+    }
+}
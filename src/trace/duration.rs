@@ -0,0 +1,130 @@
+//! Dropping of short spans.
+
+use std::time::Duration;
+
+use opentelemetry::Context;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::resource::Resource;
+use opentelemetry_sdk::trace::Span;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanProcessor;
+use opentelemetry_sdk::trace::TracerProviderBuilder;
+
+/// Span processor forwarding only the spans lasting at least a minimum
+/// duration to the wrapped processor.
+///
+/// Each span is checked on its own, so the children of a dropped span are
+/// still exported when they last long enough.
+#[derive(Debug)]
+pub(crate) struct MinDuration<P> {
+    inner: P,
+    min: Duration,
+}
+
+impl<P> MinDuration<P> {
+    /// Wraps `inner`, dropping the spans shorter than `min`.
+    pub(crate) const fn new(inner: P, min: Duration) -> Self {
+        Self { inner, min }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for MinDuration<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        let duration = span
+            .end_time
+            .duration_since(span.start_time)
+            .unwrap_or_default();
+        if duration >= self.min {
+            self.inner.on_end(span);
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+/// Adds the exporting `processor` to the builder, dropping the spans shorter
+/// than `min` when one is given.
+pub(crate) fn with_exporting_processor<P>(
+    builder: TracerProviderBuilder,
+    processor: P,
+    min: Option<Duration>,
+) -> TracerProviderBuilder
+where
+    P: SpanProcessor + 'static,
+{
+    match min {
+        Some(min) => builder.with_span_processor(MinDuration::new(processor, min)),
+        None => builder.with_span_processor(processor),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::gtest;
+    use googletest::matchers::eq;
+    use googletest::matchers::unordered_elements_are;
+    use opentelemetry::trace::Span as _;
+    use opentelemetry::trace::TraceContextExt as _;
+    use opentelemetry::trace::Tracer as _;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use opentelemetry_sdk::trace::SimpleSpanProcessor;
+
+    use super::*;
+
+    fn exported_span_names(min: Option<Duration>) -> Vec<String> {
+        let exporter = InMemorySpanExporter::default();
+        let provider = with_exporting_processor(
+            SdkTracerProvider::builder(),
+            SimpleSpanProcessor::new(exporter.clone()),
+            min,
+        )
+        .build();
+        let tracer = provider.tracer("test");
+
+        let cx = Context::new().with_span(tracer.start("fast_parent"));
+        let mut slow_child = tracer.start_with_context("slow_child", &cx);
+        tracer.start_with_context("fast_child", &cx).end();
+        cx.span().end();
+        std::thread::sleep(Duration::from_millis(50));
+        slow_child.end();
+
+        exporter
+            .get_finished_spans()
+            .expect("spans collected")
+            .into_iter()
+            .map(|span| span.name.into_owned())
+            .collect()
+    }
+
+    #[gtest]
+    fn short_spans_are_dropped_but_long_children_kept() {
+        let names = exported_span_names(Some(Duration::from_millis(40)));
+        assert_that!(names, unordered_elements_are![eq("slow_child")]);
+    }
+
+    #[gtest]
+    fn every_span_is_kept_without_a_minimum() {
+        let names = exported_span_names(None);
+        assert_that!(
+            names,
+            unordered_elements_are![eq("fast_parent"), eq("slow_child"), eq("fast_child")]
+        );
+    }
+}
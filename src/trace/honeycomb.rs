@@ -3,33 +3,120 @@
 use std::time::Duration;
 
 use bon::Builder;
-use secrecy::ExposeSecret;
 use secrecy::SecretString;
 use url::Url;
 
+use crate::Compression;
 use crate::OtlpConfig;
+use crate::OtlpTokenConfig;
+use crate::Protocol;
 
 /// Configuration for [Honeycomb](https://honeycomb.io) trace export.
 #[derive(Debug, Clone, Builder)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct HoneycombConfig {
     /// Exporter endpoint.
     pub endpoint: Url,
     /// API key.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
     pub api_key: SecretString,
-    /// Export timeout.
+    /// Export timeout, see [`OtlpConfig::timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
     pub timeout: Duration,
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+}
+
+impl From<HoneycombConfig> for OtlpTokenConfig {
+    fn from(config: HoneycombConfig) -> Self {
+        Self {
+            timeout: config.timeout,
+            protocol: config.protocol,
+            compression: config.compression,
+            ..Self::honeycomb(config.endpoint, config.api_key)
+        }
+    }
 }
 
 impl From<HoneycombConfig> for OtlpConfig {
     fn from(config: HoneycombConfig) -> Self {
-        OtlpConfig::builder()
-            .endpoint(config.endpoint)
-            .timeout(config.timeout)
-            .headers(vec![(
-                "x-honeycomb-team".to_owned(),
-                config.api_key.expose_secret().to_owned(),
-            )])
-            .build()
+        OtlpTokenConfig::from(config).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+
+    use super::*;
+
+    #[gtest]
+    fn honeycomb_is_a_token_config() {
+        let config = HoneycombConfig::builder()
+            .endpoint("https://api.honeycomb.io:443".parse().expect("valid URL"))
+            .api_key("hc-secret".into())
+            .timeout(Duration::from_secs(5))
+            .build();
+        let token = OtlpTokenConfig::from(config);
+        expect_that!(token.header_name, eq("x-honeycomb-team"));
+        expect_that!(token.timeout, eq(Duration::from_secs(5)));
+    }
+
+    #[gtest]
+    fn plaintext_endpoint_is_rejected() {
+        let config = HoneycombConfig::builder()
+            .endpoint("http://api.honeycomb.io:443".parse().expect("valid URL"))
+            .api_key("hc-secret".into())
+            .timeout(Duration::from_secs(5))
+            .build();
+        let result = crate::TraceExporter::Honeycomb(config).build_provider(
+            opentelemetry_sdk::Resource::builder().build(),
+            None,
+            crate::BatchConfig::default(),
+        );
+        expect_that!(
+            result.map_err(|err| err.to_string()),
+            err(contains_substring("Honeycomb requires TLS"))
+        );
+    }
+
+    #[gtest]
+    fn insecure_override_is_rejected() {
+        let config = HoneycombConfig::builder()
+            .endpoint("https://api.honeycomb.io:443".parse().expect("valid URL"))
+            .api_key("hc-secret".into())
+            .timeout(Duration::from_secs(5))
+            .build();
+        let mut exporter = crate::TraceExporter::Honeycomb(config);
+        expect_that!(
+            exporter.set_insecure(true).map_err(|err| err.to_string()),
+            err(contains_substring("Honeycomb requires TLS"))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[gtest]
+    fn api_key_is_serialized_redacted() {
+        let config = HoneycombConfig::builder()
+            .endpoint("https://api.honeycomb.io:443".parse().expect("valid URL"))
+            .api_key("hc-secret".into())
+            .timeout(Duration::from_secs(5))
+            .build();
+        let dump = serde_json::to_value(&config).expect("serializable config");
+        expect_that!(dump["api_key"], eq("[REDACTED]"));
     }
 }
@@ -0,0 +1,99 @@
+//! HTTP/2 keepalive of gRPC connections.
+
+use std::time::Duration;
+
+use bon::Builder;
+#[cfg(feature = "clap")]
+use clap::Args;
+use tonic::transport::Endpoint;
+
+#[cfg(feature = "clap")]
+use crate::env_vars;
+
+/// HTTP/2 keepalive of the gRPC connection to the collector.
+///
+/// Pings keep idle connections open behind load balancers that drop them after
+/// an idle timeout. Unset values keep the tonic defaults: no pings, a 20 second
+/// ping timeout, and pings only while requests are in flight.
+#[must_use]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Builder)]
+#[cfg_attr(feature = "clap", derive(Args))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct KeepaliveConfig {
+    /// Interval between keepalive pings.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-keepalive-interval",
+            long,
+            help = "gRPC keepalive ping interval, in milliseconds or as a duration (e.g. 30s)",
+            value_parser = env_vars::parse_timeout,
+        )
+    )]
+    pub interval: Option<Duration>,
+
+    /// Time to wait for a ping acknowledgement before closing the connection.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-keepalive-timeout",
+            long,
+            help = "gRPC keepalive ping timeout, in milliseconds or as a duration (e.g. 20s)",
+            value_parser = env_vars::parse_timeout,
+        )
+    )]
+    pub timeout: Option<Duration>,
+
+    /// Sends pings when no request is in flight.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "otel-keepalive-while-idle",
+            long,
+            help = "Send gRPC keepalive pings while the connection is idle"
+        )
+    )]
+    pub while_idle: Option<bool>,
+}
+
+impl KeepaliveConfig {
+    /// Returns whether no value is set.
+    pub(crate) fn is_unset(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Applies the set values to a channel endpoint.
+    pub(crate) fn apply(self, mut endpoint: Endpoint) -> Endpoint {
+        if let Some(interval) = self.interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+        if let Some(while_idle) = self.while_idle {
+            endpoint = endpoint.keep_alive_while_idle(while_idle);
+        }
+        endpoint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::eq;
+
+    use super::*;
+
+    #[gtest]
+    fn default_is_unset() {
+        expect_that!(KeepaliveConfig::default().is_unset(), eq(true));
+    }
+
+    #[gtest]
+    fn any_value_is_set() {
+        let keepalive = KeepaliveConfig::builder().while_idle(false).build();
+        expect_that!(keepalive.is_unset(), eq(false));
+    }
+}
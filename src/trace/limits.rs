@@ -0,0 +1,172 @@
+//! Span limits configuration.
+
+use bon::Builder;
+#[cfg(feature = "clap")]
+use clap::Args;
+use opentelemetry_sdk::trace::TracerProviderBuilder;
+
+#[cfg(feature = "clap")]
+use crate::env_vars;
+
+/// Caps on the attributes, events and links recorded per span.
+///
+/// Entries past a limit are dropped, newest first, and counted in the span's
+/// dropped counts. Unset values keep the SDK defaults of 128.
+#[must_use]
+#[derive(Clone, Copy, Debug, Default, Builder)]
+#[cfg_attr(feature = "clap", derive(Args))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SpanLimits {
+    /// Maximum number of attributes per span.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "span-max-attributes",
+            long,
+            env = env_vars::OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT,
+            help = "Maximum number of attributes per span"
+        )
+    )]
+    pub max_attributes_per_span: Option<u32>,
+
+    /// Maximum number of events per span.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "span-max-events",
+            long,
+            env = env_vars::OTEL_SPAN_EVENT_COUNT_LIMIT,
+            help = "Maximum number of events per span"
+        )
+    )]
+    pub max_events_per_span: Option<u32>,
+
+    /// Maximum number of links per span.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "span-max-links",
+            long,
+            env = env_vars::OTEL_SPAN_LINK_COUNT_LIMIT,
+            help = "Maximum number of links per span"
+        )
+    )]
+    pub max_links_per_span: Option<u32>,
+
+    /// Maximum number of attributes per span event.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "span-max-event-attributes",
+            long,
+            env = env_vars::OTEL_EVENT_ATTRIBUTE_COUNT_LIMIT,
+            help = "Maximum number of attributes per span event"
+        )
+    )]
+    pub max_attributes_per_event: Option<u32>,
+
+    /// Maximum number of attributes per span link.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            name = "span-max-link-attributes",
+            long,
+            env = env_vars::OTEL_LINK_ATTRIBUTE_COUNT_LIMIT,
+            help = "Maximum number of attributes per span link"
+        )
+    )]
+    pub max_attributes_per_link: Option<u32>,
+}
+
+impl SpanLimits {
+    /// Installs the configured limits on the tracer provider builder.
+    ///
+    /// Unset limits are left to the SDK, which reads the
+    /// `OTEL_SPAN_*_COUNT_LIMIT` variables itself.
+    pub(crate) fn apply(self, mut builder: TracerProviderBuilder) -> TracerProviderBuilder {
+        if let Some(max) = self.max_attributes_per_span {
+            builder = builder.with_max_attributes_per_span(max);
+        }
+        if let Some(max) = self.max_events_per_span {
+            builder = builder.with_max_events_per_span(max);
+        }
+        if let Some(max) = self.max_links_per_span {
+            builder = builder.with_max_links_per_span(max);
+        }
+        if let Some(max) = self.max_attributes_per_event {
+            builder = builder.with_max_attributes_per_event(max);
+        }
+        if let Some(max) = self.max_attributes_per_link {
+            builder = builder.with_max_attributes_per_link(max);
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::elements_are;
+    use googletest::matchers::eq;
+    use googletest::matchers::len;
+    use opentelemetry::KeyValue;
+    use opentelemetry::trace::Span as _;
+    use opentelemetry::trace::Tracer as _;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use opentelemetry_sdk::trace::SpanData;
+
+    use super::*;
+
+    /// Records one span with four attributes and four events under `limits`.
+    fn record_span(limits: SpanLimits) -> Vec<SpanData> {
+        let exporter = InMemorySpanExporter::default();
+        let provider = limits
+            .apply(SdkTracerProvider::builder())
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let mut span = provider.tracer("test").start("request");
+        for i in 0..4 {
+            span.set_attribute(KeyValue::new(format!("attr.{i}"), i));
+            span.add_event(format!("event {i}"), Vec::new());
+        }
+        span.end();
+        exporter.get_finished_spans().expect("spans collected")
+    }
+
+    #[gtest]
+    fn exceeding_the_attribute_limit_truncates_the_span() {
+        let spans = record_span(SpanLimits::builder().max_attributes_per_span(2).build());
+        assert_that!(spans, len(eq(1)));
+        let span = &spans[0];
+        expect_that!(
+            span.attributes,
+            elements_are![
+                eq(&KeyValue::new("attr.0", 0)),
+                eq(&KeyValue::new("attr.1", 1))
+            ]
+        );
+        expect_that!(span.dropped_attributes_count, eq(2));
+        expect_that!(span.events.len(), eq(4));
+    }
+
+    #[gtest]
+    fn exceeding_the_event_limit_drops_events() {
+        let spans = record_span(SpanLimits::builder().max_events_per_span(1).build());
+        assert_that!(spans, len(eq(1)));
+        expect_that!(spans[0].events.len(), eq(1));
+        expect_that!(spans[0].events.dropped_count, eq(3));
+    }
+
+    #[gtest]
+    fn unset_limits_keep_the_sdk_defaults() {
+        let spans = record_span(SpanLimits::default());
+        assert_that!(spans, len(eq(1)));
+        expect_that!(spans[0].attributes, len(eq(4)));
+        expect_that!(spans[0].dropped_attributes_count, eq(0));
+    }
+}
@@ -1,67 +1,298 @@
+#[cfg(feature = "http")]
+use std::collections::HashMap;
+use std::io;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+#[cfg(unix)]
+use std::path::PathBuf;
 use std::time::Duration;
 
 use bon::Builder;
-use jiff::SignedDuration;
+#[cfg(unix)]
+use hyper_util::rt::TokioIo;
+#[cfg(feature = "logs")]
+use opentelemetry_otlp::LogExporter;
+#[cfg(feature = "metrics")]
+use opentelemetry_otlp::MetricExporter;
 use opentelemetry_otlp::SpanExporter;
 use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "http")]
+use opentelemetry_otlp::WithHttpConfig;
 use opentelemetry_otlp::WithTonicConfig;
 use opentelemetry_otlp::tonic_types::metadata::MetadataMap;
 use opentelemetry_otlp::tonic_types::transport::ClientTlsConfig;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tonic::transport::Channel;
+#[cfg(unix)]
+use tonic::transport::Endpoint;
+#[cfg(unix)]
+use tonic::transport::Uri;
+#[cfg(unix)]
+use tower::service_fn;
 use url::Url;
 
+use crate::Compression;
+use crate::KeepaliveConfig;
+use crate::Protocol;
+use crate::RetryConfig;
+#[cfg(feature = "metrics")]
+use crate::Temporality;
 use crate::env_vars;
 use crate::error::Error;
 use crate::error::ErrorKind;
+#[cfg(feature = "http")]
+use crate::trace::partial_success::PartialSuccessClient;
 
 /// Default OTEL endpoint value
 const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+/// Default OTEL endpoint value for the HTTP transport
+#[cfg(feature = "http")]
+const DEFAULT_OTLP_HTTP_ENDPOINT: &str = "http://localhost:4318/v1/traces";
+/// Path of the OTLP/HTTP trace signal.
+#[cfg(feature = "http")]
+const TRACES_PATH: &str = "/v1/traces";
+/// Path of the OTLP/HTTP metric signal.
+#[cfg(all(feature = "http", feature = "metrics"))]
+const METRICS_PATH: &str = "/v1/metrics";
+/// Path of the OTLP/HTTP log signal.
+#[cfg(all(feature = "http", feature = "logs"))]
+const LOGS_PATH: &str = "/v1/logs";
+/// Scheme of the Unix domain socket endpoints.
+pub(crate) const UNIX_SCHEME: &str = "unix";
+/// Header carrying the bearer token.
+const AUTHORIZATION: &str = "authorization";
 /// Default timeout value.
-const DEFAULT_OTLP_TIMEOUT: Duration = Duration::from_secs(10);
+pub(super) const DEFAULT_OTLP_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest export timeout, beyond which a stuck collector is indistinguishable
+/// from a hung process.
+const MAX_OTLP_TIMEOUT: Duration = Duration::from_secs(60 * 60);
 
-/// Configuration for an OTLP span exporter.
+/// Configuration for an OTLP span or log exporter.
 #[must_use]
 #[derive(Clone, Debug, Builder)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct OtlpConfig {
-    /// Exporter endpoint.
+    /// Exporter endpoint. On Unix, a `unix:///path/to/socket` URL exports over
+    /// gRPC through that Unix domain socket.
     pub endpoint: Url,
 
-    /// Export timeout.
+    /// Export timeout. Zero uses the default of 10 seconds, and longer than an
+    /// hour is capped at an hour.
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
     pub timeout: Duration,
 
-    /// Additional gRPC metadata headers.
+    /// Additional headers, sent as gRPC metadata or HTTP headers.
     #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_header_values")
+    )]
     pub headers: Vec<(String, String)>,
 
+    /// Token sent as `authorization: Bearer <token>`. No authentication when unset.
+    #[builder(into)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_optional_secret")
+    )]
+    pub bearer_token: Option<SecretString>,
+
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+
+    /// Retry policy for failed span exports. No retry when unset.
+    pub retry: Option<RetryConfig>,
+
     /// Custom TLS configuration
     #[cfg_attr(feature = "serde", serde(skip))]
     pub tls_config: Option<ClientTlsConfig>,
+
+    /// Sends in plaintext when `true`, over TLS when `false`, whatever the
+    /// endpoint scheme. `None` uses TLS for `https` and `grpcs` endpoints only.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub insecure: Option<bool>,
+
+    /// HTTP/2 keepalive of the gRPC connection. Defaults to the tonic one.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub keepalive: KeepaliveConfig,
+
+    /// Aggregation temporality of exported metrics. Defaults to cumulative.
+    #[cfg(feature = "metrics")]
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub temporality: Temporality,
 }
 
 impl OtlpConfig {
     /// Builds the OTLP span exporter from this configuration.
     pub fn build_exporter(self) -> Result<SpanExporter, Error> {
+        match self.protocol {
+            Protocol::Grpc => self.build_tonic_exporter(),
+            #[cfg(feature = "http")]
+            Protocol::HttpBinary | Protocol::HttpJson => self.build_http_exporter(),
+        }
+    }
+
+    /// Builds the OTLP log exporter from this configuration.
+    ///
+    /// HTTP endpoints get `/v1/logs` appended unless they already end with it.
+    #[cfg(feature = "logs")]
+    pub fn build_log_exporter(self) -> Result<LogExporter, Error> {
+        match self.protocol {
+            Protocol::Grpc => {
+                let builder = self.configure_tonic(LogExporter::builder().with_tonic())?;
+                Ok(builder.build()?)
+            }
+            #[cfg(feature = "http")]
+            Protocol::HttpBinary | Protocol::HttpJson => Ok(self
+                .configure_http(LogExporter::builder().with_http(), LOGS_PATH)?
+                .build()?),
+        }
+    }
+
+    /// Builds the OTLP metric exporter from this configuration, with its
+    /// temporality.
+    ///
+    /// HTTP endpoints get `/v1/metrics` appended unless they already end with it.
+    #[cfg(feature = "metrics")]
+    pub fn build_metric_exporter(self) -> Result<MetricExporter, Error> {
+        match self.protocol {
+            Protocol::Grpc => {
+                let builder = MetricExporter::builder()
+                    .with_temporality(self.temporality.into())
+                    .with_tonic();
+                Ok(self.configure_tonic(builder)?.build()?)
+            }
+            #[cfg(feature = "http")]
+            Protocol::HttpBinary | Protocol::HttpJson => {
+                let builder = MetricExporter::builder()
+                    .with_temporality(self.temporality.into())
+                    .with_http();
+                Ok(self.configure_http(builder, METRICS_PATH)?.build()?)
+            }
+        }
+    }
+
+    /// Builds a span exporter using the gRPC transport.
+    fn build_tonic_exporter(self) -> Result<SpanExporter, Error> {
+        let builder = self.configure_tonic(SpanExporter::builder().with_tonic())?;
+        Ok(builder.build()?)
+    }
+
+    /// Builds a span exporter using the HTTP transport.
+    #[cfg(feature = "http")]
+    fn build_http_exporter(self) -> Result<SpanExporter, Error> {
+        let client = PartialSuccessClient::new(self.timeout)?;
+        Ok(self
+            .configure_http(SpanExporter::builder().with_http(), TRACES_PATH)?
+            .with_http_client(client)
+            .build()?)
+    }
+
+    /// Applies this configuration to a gRPC exporter builder.
+    fn configure_tonic<B>(self, builder: B) -> Result<B, Error>
+    where
+        B: WithExportConfig + WithTonicConfig,
+    {
         let metadata = self.metadata()?;
+        let endpoint = connection_endpoint(&self.endpoint, self.insecure)?;
 
-        let mut builder = SpanExporter::builder()
-            .with_tonic()
-            .with_endpoint(self.endpoint.as_ref())
+        let mut builder = builder
+            .with_endpoint(endpoint.as_str())
+            .with_timeout(export_timeout(self.timeout))
             .with_metadata(metadata);
 
-        if self.endpoint.scheme() == "https" {
-            let tls = self
-                .tls_config
-                .unwrap_or_else(|| ClientTlsConfig::default().with_enabled_roots());
+        if let Some(compression) = self.compression {
+            builder = builder.with_compression(compression.into());
+        }
+
+        #[cfg(unix)]
+        if endpoint.scheme() == UNIX_SCHEME {
+            let channel = unix_channel(&endpoint, self.timeout, self.keepalive);
+            return Ok(builder.with_channel(channel));
+        }
+
+        let tls = (endpoint.scheme() == "https").then(|| {
+            self.tls_config
+                .unwrap_or_else(|| ClientTlsConfig::default().with_enabled_roots())
+        });
+
+        if !self.keepalive.is_unset() {
+            let channel = keepalive_channel(&endpoint, self.timeout, tls, self.keepalive)?;
+            return Ok(builder.with_channel(channel));
+        }
+
+        if let Some(tls) = tls {
             builder = builder.with_tls_config(tls);
         }
 
-        Ok(builder.build()?)
+        Ok(builder)
+    }
+
+    /// Applies this configuration to an HTTP exporter builder, sending to the
+    /// `signal_path` of the endpoint.
+    #[cfg(feature = "http")]
+    fn configure_http<B>(self, builder: B, signal_path: &str) -> Result<B, Error>
+    where
+        B: WithExportConfig + WithHttpConfig,
+    {
+        if self.endpoint.scheme() == UNIX_SCHEME {
+            return Err(ErrorKind::ExporterConfig {
+                reason: format!(
+                    "endpoint `{}` is a Unix domain socket, which needs the grpc protocol",
+                    self.endpoint
+                ),
+            }
+            .into());
+        }
+        let endpoint = signal_endpoint(
+            &connection_endpoint(&self.endpoint, self.insecure)?,
+            signal_path,
+        )?;
+        let headers: HashMap<String, String> = self.all_headers().collect();
+        let mut builder = builder
+            .with_endpoint(endpoint.as_str())
+            .with_timeout(export_timeout(self.timeout))
+            .with_protocol(self.protocol.into())
+            .with_headers(headers);
+
+        if let Some(compression) = self.compression {
+            builder = builder.with_compression(compression.into());
+        }
+
+        Ok(builder)
+    }
+
+    /// Returns the configured headers followed by the bearer authorization.
+    fn all_headers(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.headers
+            .iter()
+            .cloned()
+            .chain(self.bearer_token.iter().map(|token| {
+                (
+                    AUTHORIZATION.to_owned(),
+                    format!("Bearer {}", token.expose_secret()),
+                )
+            }))
     }
 
     /// Builds the gRPC metadata map from all header sources.
     fn metadata(&self) -> Result<MetadataMap, Error> {
         let mut map = MetadataMap::with_capacity(self.headers.len());
-        for (key, val) in &self.headers {
+        for (key, val) in self.all_headers() {
             let val = val.try_into().map_err(|_err| ErrorKind::ExporterConfig {
                 reason: format!("invalid metadata value for header `{key}`"),
             })?;
@@ -77,15 +308,24 @@ impl OtlpConfig {
 
 impl Default for OtlpConfig {
     fn default() -> Self {
-        let endpoint = std::env::var(env_vars::OTEL_EXPORTER_OTLP_ENDPOINT)
+        let protocol = std::env::var(env_vars::OTEL_EXPORTER_OTLP_PROTOCOL)
             .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or_else(|| DEFAULT_OTLP_ENDPOINT.parse().expect("valid URL"));
+            .and_then(|s| s.parse::<Protocol>().ok())
+            .unwrap_or_default();
+
+        let endpoint = endpoint_from_env(env_vars::OTEL_EXPORTER_OTLP_TRACES_ENDPOINT)
+            .unwrap_or_else(|| {
+                let default = match protocol {
+                    Protocol::Grpc => DEFAULT_OTLP_ENDPOINT,
+                    #[cfg(feature = "http")]
+                    Protocol::HttpBinary | Protocol::HttpJson => DEFAULT_OTLP_HTTP_ENDPOINT,
+                };
+                default.parse().expect("valid URL")
+            });
 
         let timeout = std::env::var(env_vars::OTEL_EXPORTER_OTLP_TIMEOUT)
             .ok()
-            .and_then(|s| s.parse::<SignedDuration>().ok())
-            .and_then(|d| Duration::try_from(d).ok())
+            .and_then(|s| env_vars::parse_timeout(&s).ok())
             .unwrap_or(DEFAULT_OTLP_TIMEOUT);
 
         let headers = std::env::var(env_vars::OTEL_EXPORTER_OTLP_HEADERS)
@@ -93,23 +333,261 @@ impl Default for OtlpConfig {
             .and_then(|s| env_vars::parse_key_values(&s).ok())
             .unwrap_or_default();
 
-        Self::builder()
+        let compression = std::env::var(env_vars::OTEL_EXPORTER_OTLP_COMPRESSION)
+            .ok()
+            .and_then(|s| Compression::parse_optional(&s).ok())
+            .flatten();
+
+        let insecure = std::env::var(env_vars::OTEL_EXPORTER_OTLP_INSECURE)
+            .ok()
+            .and_then(|s| s.parse().ok());
+
+        #[cfg(feature = "metrics")]
+        let temporality =
+            std::env::var(env_vars::OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default();
+
+        let builder = Self::builder()
             .endpoint(endpoint)
             .timeout(timeout)
             .headers(headers)
-            .build()
+            .protocol(protocol)
+            .maybe_compression(compression)
+            .maybe_insecure(insecure);
+        #[cfg(feature = "metrics")]
+        let builder = builder.temporality(temporality);
+        builder.build()
     }
 }
 
+/// Merges additional headers into `target`, replacing entries with the same key.
+pub(crate) fn merge_headers(
+    target: &mut Vec<(String, String)>,
+    headers: &[(String, SecretString)],
+) {
+    for (key, val) in headers {
+        target.retain(|(existing, _)| existing != key);
+        target.push((key.clone(), val.expose_secret().to_owned()));
+    }
+}
+
+/// Returns `endpoint` with `signal_path` appended, unless its path already ends
+/// with it.
+///
+/// Trailing slashes are dropped and a repeated signal path is collapsed, so
+/// `http://host:4318/`, `http://host:4318/v1/traces` and
+/// `http://host:4318/v1/traces/v1/traces` all send to `http://host:4318/v1/traces`.
+#[cfg(feature = "http")]
+fn signal_endpoint(endpoint: &Url, signal_path: &str) -> Result<Url, Error> {
+    if endpoint.cannot_be_a_base() || endpoint.host().is_none() {
+        return Err(ErrorKind::InvalidEndpoint {
+            context: "OTLP/HTTP",
+            source: url::ParseError::EmptyHost,
+        }
+        .into());
+    }
+    let mut base = endpoint.path().trim_end_matches('/');
+    while let Some(stripped) = base.strip_suffix(signal_path) {
+        base = stripped.trim_end_matches('/');
+    }
+    let mut endpoint = endpoint.clone();
+    endpoint.set_path(&format!("{base}{signal_path}"));
+    Ok(endpoint)
+}
+
+/// Returns `timeout` capped at [`MAX_OTLP_TIMEOUT`], or the default OTLP
+/// timeout when it is zero.
+///
+/// A zero timeout would fail every export and connection attempt at once, and
+/// a huge one overflows the export deadlines.
+pub(crate) const fn export_timeout(timeout: Duration) -> Duration {
+    if timeout.is_zero() {
+        DEFAULT_OTLP_TIMEOUT
+    } else if timeout.as_secs() >= MAX_OTLP_TIMEOUT.as_secs() {
+        MAX_OTLP_TIMEOUT
+    } else {
+        timeout
+    }
+}
+
+/// Creates a lazily connected gRPC channel to `endpoint` with the given
+/// keepalive, since the exporter builder has no keepalive setting.
+///
+/// Like the exporter's own channel, it must be created within a Tokio runtime.
+fn keepalive_channel(
+    endpoint: &Url,
+    timeout: Duration,
+    tls: Option<ClientTlsConfig>,
+    keepalive: KeepaliveConfig,
+) -> Result<Channel, Error> {
+    let mut channel =
+        Channel::from_shared(endpoint.to_string()).map_err(|err| ErrorKind::ExporterConfig {
+            reason: format!("invalid gRPC endpoint `{endpoint}`: {err}"),
+        })?;
+    if let Some(tls) = tls {
+        channel = channel
+            .tls_config(tls)
+            .map_err(|err| ErrorKind::ExporterConfig {
+                reason: format!("invalid TLS configuration: {err}"),
+            })?;
+    }
+    Ok(keepalive
+        .apply(channel.timeout(export_timeout(timeout)))
+        .connect_lazy())
+}
+
+/// Creates a lazily connected gRPC channel to the Unix domain socket at the
+/// path of `endpoint`.
+#[cfg(unix)]
+fn unix_channel(endpoint: &Url, timeout: Duration, keepalive: KeepaliveConfig) -> Channel {
+    let path = socket_path(endpoint);
+    // The URI is only used for the request authority, the connector ignores it.
+    let channel = Endpoint::from_static("http://localhost").timeout(export_timeout(timeout));
+    keepalive
+        .apply(channel)
+        .connect_with_connector_lazy(service_fn(move |_: Uri| {
+            let path = path.clone();
+            async move { UnixStream::connect(path).await.map(TokioIo::new) }
+        }))
+}
+
+/// Returns the percent-decoded socket path of a `unix` endpoint.
+#[cfg(unix)]
+fn socket_path(endpoint: &Url) -> PathBuf {
+    endpoint
+        .to_file_path()
+        .unwrap_or_else(|()| PathBuf::from(endpoint.path()))
+}
+
+/// Returns `endpoint` with the `grpc` and `grpcs` schemes replaced by `http`
+/// and `https`.
+///
+/// Any other scheme than these four, and `unix` on Unix, is rejected.
+pub(crate) fn transport_endpoint(endpoint: &Url) -> Result<Url, Error> {
+    let transport = match endpoint.scheme() {
+        "http" | "https" => return Ok(endpoint.clone()),
+        #[cfg(unix)]
+        UNIX_SCHEME => return Ok(endpoint.clone()),
+        "grpc" => "http",
+        "grpcs" => "https",
+        scheme => {
+            return Err(ErrorKind::UnsupportedEndpointScheme {
+                endpoint: endpoint.clone(),
+                scheme: scheme.to_owned(),
+            }
+            .into());
+        }
+    };
+    let rest = &endpoint.as_str()[endpoint.scheme().len()..];
+    format!("{transport}{rest}").parse().map_err(|source| {
+        ErrorKind::InvalidEndpoint {
+            context: "OTLP",
+            source,
+        }
+        .into()
+    })
+}
+
+/// Returns the transport endpoint of `endpoint`, switched to `http` when
+/// `insecure` is `Some(true)` and to `https` when it is `Some(false)`.
+///
+/// The port is kept, so `https://collector` stays on port 443 in plaintext.
+fn connection_endpoint(endpoint: &Url, insecure: Option<bool>) -> Result<Url, Error> {
+    let mut transport = transport_endpoint(endpoint)?;
+    if let Some(insecure) = insecure
+        && transport.scheme() != UNIX_SCHEME
+    {
+        let port = transport.port_or_known_default();
+        let scheme = if insecure { "http" } else { "https" };
+        transport
+            .set_scheme(scheme)
+            .and_then(|()| transport.set_port(port))
+            .map_err(|()| ErrorKind::ExporterConfig {
+                reason: format!("cannot use endpoint `{endpoint}` over {scheme}"),
+            })?;
+    }
+    Ok(transport)
+}
+
+/// Rejects plaintext endpoints of backends only reachable over TLS.
+pub(crate) fn require_tls(endpoint: &Url, backend: &'static str) -> Result<(), Error> {
+    if transport_endpoint(endpoint)?.scheme() == "https" {
+        return Ok(());
+    }
+    Err(ErrorKind::PlaintextEndpoint {
+        backend,
+        endpoint: endpoint.clone(),
+    }
+    .into())
+}
+
+/// Checks that `endpoint` accepts TCP connections within `timeout`, or
+/// connections to its Unix domain socket.
+///
+/// Each resolved address is tried in turn until one connects.
+pub(crate) fn check_reachable(endpoint: &Url, timeout: Duration) -> Result<(), Error> {
+    let unreachable = |source| ErrorKind::ExporterUnreachable {
+        endpoint: endpoint.clone(),
+        source,
+    };
+    let transport = transport_endpoint(endpoint)?;
+    #[cfg(unix)]
+    if transport.scheme() == UNIX_SCHEME {
+        return std::os::unix::net::UnixStream::connect(socket_path(&transport))
+            .map(drop)
+            .map_err(|err| unreachable(err).into());
+    }
+    let (Some(host), Some(port)) = (transport.host_str(), transport.port_or_known_default()) else {
+        return Err(ErrorKind::ExporterConfig {
+            reason: format!("endpoint `{endpoint}` has no host or port"),
+        }
+        .into());
+    };
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, "no address resolved");
+    let timeout = export_timeout(timeout);
+    for addr in (host, port).to_socket_addrs().map_err(unreachable)? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(_) => return Ok(()),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(unreachable(last_err).into())
+}
+
+/// Reads an exporter endpoint from the environment.
+///
+/// The signal-specific variable (e.g. `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`) takes
+/// precedence over the generic `OTEL_EXPORTER_OTLP_ENDPOINT`.
+pub(crate) fn endpoint_from_env(signal_var: &str) -> Option<Url> {
+    select_endpoint(
+        std::env::var(signal_var).ok(),
+        std::env::var(env_vars::OTEL_EXPORTER_OTLP_ENDPOINT).ok(),
+    )
+}
+
+/// Picks the signal-specific endpoint over the generic one, ignoring
+/// unparsable values.
+fn select_endpoint(signal: Option<String>, generic: Option<String>) -> Option<Url> {
+    signal
+        .and_then(|s| s.parse().ok())
+        .or_else(|| generic.and_then(|s| s.parse().ok()))
+}
+
 #[cfg(test)]
 mod tests {
+    use googletest::assert_that;
     use googletest::expect_that;
     use googletest::gtest;
     use googletest::matchers::anything;
+    use googletest::matchers::contains_substring;
     use googletest::matchers::eq;
     use googletest::matchers::err;
+    use googletest::matchers::none;
     use googletest::matchers::ok;
     use googletest::matchers::some;
+    use rstest::rstest;
 
     use super::*;
 
@@ -125,6 +603,39 @@ mod tests {
         expect_that!(result, ok(anything()));
     }
 
+    #[tokio::test]
+    #[gtest]
+    async fn can_create_a_compressed_span_exporter() {
+        let config = OtlpConfig::builder()
+            .endpoint("http://test.example".parse().expect("to be valid"))
+            .timeout(Duration::ZERO)
+            .compression(Compression::Gzip)
+            .build();
+
+        let result: Result<SpanExporter, _> = config.build_exporter();
+        expect_that!(result, ok(anything()));
+    }
+
+    #[rstest]
+    #[case("http://test.example")]
+    #[case("https://test.example")]
+    #[tokio::test]
+    async fn can_create_a_span_exporter_with_keepalive(#[case] endpoint: &str) {
+        let config = OtlpConfig::builder()
+            .endpoint(endpoint.parse().expect("to be valid"))
+            .timeout(Duration::ZERO)
+            .keepalive(
+                KeepaliveConfig::builder()
+                    .interval(Duration::from_secs(30))
+                    .while_idle(true)
+                    .build(),
+            )
+            .build();
+
+        let result: Result<SpanExporter, _> = config.build_exporter();
+        assert_that!(result, ok(anything()));
+    }
+
     #[gtest]
     fn metadata_contains_headers() {
         let config = OtlpConfig::builder()
@@ -136,6 +647,27 @@ mod tests {
         expect_that!(metadata.get("x-api-key"), some(eq("test")));
     }
 
+    #[gtest]
+    fn metadata_contains_bearer_token() {
+        let config = OtlpConfig::builder()
+            .endpoint("http://test.example".parse().expect("to be valid"))
+            .timeout(Duration::ZERO)
+            .bearer_token("s3cr3t")
+            .build();
+        let metadata = config.metadata().expect("valid metadata");
+        expect_that!(metadata.get("authorization"), some(eq("Bearer s3cr3t")));
+    }
+
+    #[gtest]
+    fn metadata_without_bearer_token_has_no_authorization() {
+        let config = OtlpConfig::builder()
+            .endpoint("http://test.example".parse().expect("to be valid"))
+            .timeout(Duration::ZERO)
+            .build();
+        let metadata = config.metadata().expect("valid metadata");
+        expect_that!(metadata.get("authorization"), none());
+    }
+
     #[gtest]
     fn metadata_rejects_invalid_header_value() {
         let config = OtlpConfig::builder()
@@ -147,10 +679,354 @@ mod tests {
         expect_that!(result, err(anything()));
     }
 
+    #[gtest]
+    fn metadata_rejects_invalid_header_name() {
+        let config = OtlpConfig::builder()
+            .endpoint("http://localhost:4317".parse().expect("to be valid"))
+            .timeout(Duration::ZERO)
+            .headers(vec![("invalid key".to_owned(), "value".to_owned())])
+            .build();
+        expect_that!(config.metadata(), err(anything()));
+    }
+
+    #[gtest]
+    fn merged_headers_replace_existing_keys() {
+        let mut headers = vec![
+            ("authorization".to_owned(), "old".to_owned()),
+            ("x-team".to_owned(), "core".to_owned()),
+        ];
+        merge_headers(
+            &mut headers,
+            &[("authorization".to_owned(), SecretString::from("Bearer new"))],
+        );
+        expect_that!(
+            headers,
+            googletest::matchers::unordered_elements_are![
+                eq(&("x-team".to_owned(), "core".to_owned())),
+                eq(&("authorization".to_owned(), "Bearer new".to_owned()))
+            ]
+        );
+    }
+
     #[gtest]
     fn default_config_has_spec_values() {
         let config = OtlpConfig::default();
         expect_that!(config.endpoint.as_str(), eq("http://localhost:4317/"));
         expect_that!(config.timeout, eq(Duration::from_secs(10)));
+        expect_that!(config.protocol, eq(Protocol::Grpc));
+        expect_that!(config.compression, none());
+    }
+
+    #[rstest]
+    #[case(
+        Some("http://traces:4317"),
+        Some("http://generic:4317"),
+        Some("http://traces:4317/")
+    )]
+    #[case(None, Some("http://generic:4317"), Some("http://generic:4317/"))]
+    #[case(
+        Some("not a url"),
+        Some("http://generic:4317"),
+        Some("http://generic:4317/")
+    )]
+    #[case(None, None, None)]
+    fn signal_endpoint_takes_precedence(
+        #[case] signal: Option<&str>,
+        #[case] generic: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        let endpoint = select_endpoint(signal.map(str::to_owned), generic.map(str::to_owned));
+        assert_that!(endpoint.as_ref().map(Url::as_str), eq(expected));
+    }
+
+    #[cfg(feature = "http")]
+    #[gtest]
+    fn can_create_an_http_span_exporter() {
+        let config = OtlpConfig::builder()
+            .endpoint(
+                "http://test.example/v1/traces"
+                    .parse()
+                    .expect("to be valid"),
+            )
+            .timeout(Duration::ZERO)
+            .headers(vec![("x-api-key".to_owned(), "test".to_owned())])
+            .protocol(Protocol::HttpBinary)
+            .build();
+
+        let result: Result<SpanExporter, _> = config.build_exporter();
+        expect_that!(result, ok(anything()));
+    }
+
+    #[cfg(feature = "http")]
+    #[rstest]
+    #[case("http://collector:4318", "http://collector:4318/v1/traces")]
+    #[case("http://collector:4318/", "http://collector:4318/v1/traces")]
+    #[case("http://collector:4318/v1/traces", "http://collector:4318/v1/traces")]
+    #[case("http://collector:4318/v1/traces/", "http://collector:4318/v1/traces")]
+    #[case(
+        "http://collector:4318/v1/traces/v1/traces",
+        "http://collector:4318/v1/traces"
+    )]
+    #[case(
+        "https://gateway.example/otlp/",
+        "https://gateway.example/otlp/v1/traces"
+    )]
+    #[case(
+        "https://gateway.example/otlp?tenant=a",
+        "https://gateway.example/otlp/v1/traces?tenant=a"
+    )]
+    fn signal_path_is_appended_once(#[case] endpoint: &str, #[case] expected: &str) {
+        let endpoint: Url = endpoint.parse().expect("to be valid");
+        let normalized = signal_endpoint(&endpoint, TRACES_PATH).expect("a base URL");
+        assert_that!(normalized.as_str(), eq(expected));
+    }
+
+    #[cfg(feature = "http")]
+    #[rstest]
+    #[case("mailto:collector@example.com")]
+    #[case("unix:/var/run/otel.sock")]
+    fn endpoint_without_host_is_rejected(#[case] endpoint: &str) {
+        let endpoint: Url = endpoint.parse().expect("to be valid");
+        assert_that!(
+            signal_endpoint(&endpoint, TRACES_PATH).map_err(|err| err.to_string()),
+            err(contains_substring("invalid OTLP/HTTP endpoint: empty host"))
+        );
+    }
+
+    #[cfg(feature = "logs")]
+    #[tokio::test]
+    #[gtest]
+    async fn can_create_a_log_exporter() {
+        let config = OtlpConfig::builder()
+            .endpoint("http://test.example".parse().expect("to be valid"))
+            .timeout(Duration::ZERO)
+            .build();
+
+        expect_that!(config.build_log_exporter(), ok(anything()));
+    }
+
+    #[gtest]
+    fn listening_endpoint_is_reachable() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bindable port");
+        let port = listener.local_addr().expect("bound address").port();
+        let endpoint = format!("http://127.0.0.1:{port}")
+            .parse()
+            .expect("to be valid");
+        expect_that!(
+            check_reachable(&endpoint, Duration::from_secs(1)),
+            ok(anything())
+        );
+    }
+
+    #[cfg(unix)]
+    #[gtest]
+    fn listening_unix_socket_is_reachable() {
+        let path = std::env::temp_dir().join(format!("owiwi-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _listener = std::os::unix::net::UnixListener::bind(&path).expect("bindable socket");
+        let endpoint = format!("unix://{}", path.display())
+            .parse()
+            .expect("to be valid");
+        expect_that!(
+            check_reachable(&endpoint, Duration::from_secs(1)),
+            ok(anything())
+        );
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    #[gtest]
+    async fn can_create_a_span_exporter_over_a_unix_socket() {
+        let config = OtlpConfig::builder()
+            .endpoint(
+                "unix:///run/otel/collector.sock"
+                    .parse()
+                    .expect("to be valid"),
+            )
+            .timeout(Duration::ZERO)
+            .build();
+
+        let result: Result<SpanExporter, _> = config.build_exporter();
+        expect_that!(result, ok(anything()));
+    }
+
+    #[cfg(feature = "http")]
+    #[gtest]
+    fn unix_socket_requires_grpc() {
+        let config = OtlpConfig::builder()
+            .endpoint(
+                "unix:///run/otel/collector.sock"
+                    .parse()
+                    .expect("to be valid"),
+            )
+            .timeout(Duration::ZERO)
+            .protocol(Protocol::HttpBinary)
+            .build();
+
+        let result: Result<SpanExporter, _> = config.build_exporter();
+        expect_that!(
+            result.map_err(|err| err.to_string()),
+            err(contains_substring("needs the grpc protocol"))
+        );
+    }
+
+    #[gtest]
+    fn closed_endpoint_is_unreachable() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("bindable port")
+            .port();
+        let endpoint = format!("http://127.0.0.1:{port}")
+            .parse()
+            .expect("to be valid");
+        let result = check_reachable(&endpoint, Duration::from_secs(1));
+        expect_that!(
+            result.as_ref().map_err(Error::is_exporter_unreachable),
+            err(eq(true))
+        );
+    }
+
+    #[rstest]
+    #[case("http://collector:4317", "http://collector:4317/")]
+    #[case("https://collector:4317", "https://collector:4317/")]
+    #[case("grpc://collector:4317", "http://collector:4317/")]
+    #[case("grpcs://collector:4317/otlp", "https://collector:4317/otlp")]
+    fn grpc_schemes_map_to_http(#[case] endpoint: &str, #[case] expected: &str) {
+        let endpoint = endpoint.parse().expect("to be valid");
+        assert_that!(
+            transport_endpoint(&endpoint).map(String::from),
+            ok(eq(expected))
+        );
+    }
+
+    #[gtest]
+    fn unknown_scheme_is_rejected() {
+        let endpoint = "ftp://collector:4317".parse().expect("to be valid");
+        expect_that!(
+            transport_endpoint(&endpoint).map_err(|err| err.to_string()),
+            err(contains_substring("unsupported scheme `ftp`"))
+        );
+    }
+
+    #[rstest]
+    #[case("https://collector:4317", None, "https://collector:4317/")]
+    #[case("grpc://collector:4317", None, "http://collector:4317/")]
+    #[case("https://collector:4317", Some(true), "http://collector:4317/")]
+    #[case("grpcs://collector", Some(true), "http://collector:443/")]
+    #[case("http://collector:4317", Some(false), "https://collector:4317/")]
+    #[case("http://collector", Some(false), "https://collector:80/")]
+    #[cfg_attr(
+        unix,
+        case("unix:///run/otel.sock", Some(true), "unix:///run/otel.sock")
+    )]
+    fn insecure_overrides_the_scheme(
+        #[case] endpoint: &str,
+        #[case] insecure: Option<bool>,
+        #[case] expected: &str,
+    ) {
+        let endpoint = endpoint.parse().expect("to be valid");
+        assert_that!(
+            connection_endpoint(&endpoint, insecure).map(String::from),
+            ok(eq(expected))
+        );
+    }
+
+    #[rstest]
+    #[case("https://api.example.com", true)]
+    #[case("grpcs://api.example.com", true)]
+    #[case("http://api.example.com", false)]
+    #[case("grpc://api.example.com", false)]
+    fn tls_only_backends_reject_plaintext(#[case] endpoint: &str, #[case] accepted: bool) {
+        let endpoint = endpoint.parse().expect("to be valid");
+        assert_that!(require_tls(&endpoint, "Example").is_ok(), eq(accepted));
+    }
+
+    #[rstest]
+    #[case(Duration::ZERO, DEFAULT_OTLP_TIMEOUT)]
+    #[case(Duration::from_millis(1), Duration::from_millis(1))]
+    #[case(Duration::MAX, MAX_OTLP_TIMEOUT)]
+    fn zero_timeout_uses_the_default(#[case] timeout: Duration, #[case] expected: Duration) {
+        assert_that!(export_timeout(timeout), eq(expected));
+    }
+
+    /// Starts an OTLP/HTTP collector accepting every export and returns its
+    /// endpoint.
+    #[cfg(feature = "http")]
+    fn start_http_collector() -> Url {
+        use std::io::BufRead as _;
+        use std::io::Read as _;
+        use std::io::Write as _;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bindable port");
+        let endpoint = format!("http://{}", listener.local_addr().expect("bound address"))
+            .parse()
+            .expect("to be valid");
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = io::BufReader::new(stream);
+                let mut content_length = 0;
+                let mut line = String::new();
+                // Headers end with an empty line, two bytes with its CRLF.
+                while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap_or_default();
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+                let _ = reader.get_mut().write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                );
+            }
+        });
+        endpoint
+    }
+
+    #[cfg(feature = "http")]
+    #[rstest]
+    #[case(Duration::ZERO)]
+    #[case(Duration::MAX)]
+    fn extreme_timeouts_export_spans(#[case] timeout: Duration) {
+        use opentelemetry::trace::Span as _;
+        use opentelemetry::trace::Tracer as _;
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_sdk::trace::SdkTracerProvider;
+
+        let config = OtlpConfig::builder()
+            .endpoint(start_http_collector())
+            .timeout(timeout)
+            .protocol(Protocol::HttpBinary)
+            .build();
+        let exporter: SpanExporter = config.build_exporter().expect("exporter to be built");
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .build();
+        let tracer = provider.tracer("test");
+
+        tracer.start("first").end();
+        assert_that!(provider.force_flush(), ok(anything()));
+        tracer.start("second").end();
+        assert_that!(provider.force_flush(), ok(anything()));
+        let _ = provider.shutdown();
+    }
+
+    #[rstest]
+    #[case(Protocol::Grpc)]
+    #[cfg_attr(feature = "http", case(Protocol::HttpBinary))]
+    #[tokio::test]
+    async fn extreme_timeouts_build_an_exporter(
+        #[case] protocol: Protocol,
+        #[values(Duration::ZERO, Duration::MAX)] timeout: Duration,
+    ) {
+        let config = OtlpConfig::builder()
+            .endpoint("http://test.example".parse().expect("to be valid"))
+            .timeout(timeout)
+            .protocol(protocol)
+            .build();
+        assert_that!(config.build_exporter(), ok(anything()));
     }
 }
@@ -0,0 +1,197 @@
+//! Reporting of the spans rejected by OTLP/HTTP collectors.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use opentelemetry_http::Bytes;
+use opentelemetry_http::HttpClient;
+use opentelemetry_http::HttpError;
+use opentelemetry_http::Request;
+use opentelemetry_http::Response;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceResponse;
+use prost::Message as _;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::trace::otlp::export_timeout;
+
+/// HTTP client of the OTLP/HTTP span exporter, warning when the collector
+/// accepts only part of an export.
+///
+/// The SDK exporter discards the response, so a partial success would
+/// otherwise look like a full one.
+#[derive(Debug)]
+pub(crate) struct PartialSuccessClient {
+    inner: reqwest::blocking::Client,
+}
+
+impl PartialSuccessClient {
+    /// Creates a client whose requests time out after the export timeout of
+    /// `timeout`, see [`export_timeout`].
+    ///
+    /// The blocking client is built on its own thread, since building it
+    /// within a Tokio runtime panics.
+    pub(crate) fn new(timeout: Duration) -> Result<Self, Error> {
+        let timeout = export_timeout(timeout);
+        let inner = std::thread::spawn(move || {
+            reqwest::blocking::Client::builder()
+                .timeout(timeout)
+                .build()
+        })
+        .join()
+        .map_err(|_| ErrorKind::ExporterConfig {
+            reason: "the OTLP/HTTP client could not be built".to_owned(),
+        })?
+        .map_err(|err| ErrorKind::ExporterConfig {
+            reason: format!("invalid OTLP/HTTP client: {err}"),
+        })?;
+        Ok(Self { inner })
+    }
+}
+
+#[async_trait]
+impl HttpClient for PartialSuccessClient {
+    async fn send_bytes(&self, request: Request<Bytes>) -> Result<Response<Bytes>, HttpError> {
+        let response = self.inner.send_bytes(request).await?;
+        if let Some(partial) = PartialSuccess::from_response(&response) {
+            tracing::warn!(
+                rejected_spans = partial.rejected_spans,
+                error = %partial.error_message,
+                "OTLP collector rejected part of the exported spans"
+            );
+        }
+        Ok(response)
+    }
+}
+
+/// Partial success of an OTLP trace export.
+#[derive(Debug, PartialEq, Eq)]
+struct PartialSuccess {
+    /// Number of spans the collector rejected.
+    rejected_spans: i64,
+    /// Reason given by the collector.
+    error_message: String,
+}
+
+impl PartialSuccess {
+    /// Reads the partial success of an export response, in protobuf or JSON
+    /// as per its content type.
+    ///
+    /// Returns `None` for a full success or an unreadable body.
+    fn from_response(response: &Response<Bytes>) -> Option<Self> {
+        let json = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+        let partial = if json {
+            Self::from_json(response.body())?
+        } else {
+            Self::from_protobuf(response.body())?
+        };
+        (partial.rejected_spans > 0 || !partial.error_message.is_empty()).then_some(partial)
+    }
+
+    fn from_protobuf(body: &[u8]) -> Option<Self> {
+        let partial = ExportTraceServiceResponse::decode(body)
+            .ok()?
+            .partial_success?;
+        Some(Self {
+            rejected_spans: partial.rejected_spans,
+            error_message: partial.error_message,
+        })
+    }
+
+    /// Reads a JSON response, where the 64-bit count may be a string.
+    fn from_json(body: &[u8]) -> Option<Self> {
+        let response: Value = serde_json::from_slice(body).ok()?;
+        let partial = response.get("partialSuccess")?;
+        let rejected_spans = match partial.get("rejectedSpans") {
+            Some(Value::String(count)) => count.parse().ok()?,
+            Some(count) => count.as_i64()?,
+            None => 0,
+        };
+        let error_message = partial
+            .get("errorMessage")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned();
+        Some(Self {
+            rejected_spans,
+            error_message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::eq;
+    use googletest::matchers::none;
+    use googletest::matchers::some;
+    use opentelemetry_proto::tonic::collector::trace::v1::ExportTracePartialSuccess;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn response(content_type: &str, body: Vec<u8>) -> Response<Bytes> {
+        Response::builder()
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(Bytes::from(body))
+            .expect("valid response")
+    }
+
+    #[gtest]
+    fn protobuf_partial_success_is_read() {
+        let body = ExportTraceServiceResponse {
+            partial_success: Some(ExportTracePartialSuccess {
+                rejected_spans: 3,
+                error_message: "too many attributes".to_owned(),
+            }),
+        }
+        .encode_to_vec();
+        expect_that!(
+            PartialSuccess::from_response(&response("application/x-protobuf", body)),
+            some(eq(&PartialSuccess {
+                rejected_spans: 3,
+                error_message: "too many attributes".to_owned(),
+            }))
+        );
+    }
+
+    #[rstest]
+    #[case(
+        r#"{"partialSuccess":{"rejectedSpans":"2","errorMessage":"invalid span"}}"#,
+        2
+    )]
+    #[case(
+        r#"{"partialSuccess":{"rejectedSpans":2,"errorMessage":"invalid span"}}"#,
+        2
+    )]
+    #[case(r#"{"partialSuccess":{"errorMessage":"invalid span"}}"#, 0)]
+    fn json_partial_success_is_read(#[case] body: &str, #[case] rejected_spans: i64) {
+        let partial =
+            PartialSuccess::from_response(&response("application/json", body.as_bytes().to_vec()));
+        assert_that!(
+            partial,
+            some(eq(&PartialSuccess {
+                rejected_spans,
+                error_message: "invalid span".to_owned(),
+            }))
+        );
+    }
+
+    #[rstest]
+    #[case("application/json", b"{}".to_vec())]
+    #[case("application/json", br#"{"partialSuccess":{}}"#.to_vec())]
+    #[case("application/x-protobuf", Vec::new())]
+    #[case("application/x-protobuf", b"not protobuf".to_vec())]
+    fn full_successes_are_ignored(#[case] content_type: &str, #[case] body: Vec<u8>) {
+        assert_that!(
+            PartialSuccess::from_response(&response(content_type, body)),
+            none()
+        );
+    }
+}
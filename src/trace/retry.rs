@@ -0,0 +1,185 @@
+//! Retry of transient span export failures.
+
+use std::time::Duration;
+
+use bon::Builder;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkError;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanExporter;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+
+/// Exponential backoff policy for failed span exports.
+///
+/// Timeouts and transport failures are retried, the delay doubling after each
+/// attempt up to [`Self::max_backoff`].
+#[must_use]
+#[derive(Clone, Copy, Debug, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct RetryConfig {
+    /// Maximum number of retries after the first failed attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryConfig {
+    /// Checks that the initial backoff does not exceed the maximum backoff.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.initial_backoff > self.max_backoff {
+            return Err(ErrorKind::InvalidRetryConfig {
+                initial_backoff: self.initial_backoff,
+                max_backoff: self.max_backoff,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Returns the delay before the retry following `attempt`, counted from zero.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1_u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Span exporter retrying transient failures of the wrapped exporter.
+#[derive(Debug)]
+pub(crate) struct RetryingExporter<E> {
+    inner: E,
+    config: RetryConfig,
+}
+
+impl<E> RetryingExporter<E> {
+    /// Wraps `inner` with the given retry policy.
+    pub(crate) const fn new(inner: E, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for RetryingExporter<E> {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        for attempt in 0..self.config.max_retries {
+            match self.inner.export(batch.clone()).await {
+                Err(OTelSdkError::Timeout(_) | OTelSdkError::InternalFailure(_)) => {
+                    // The batch processor drives exports on its own thread.
+                    std::thread::sleep(self.config.backoff(attempt));
+                }
+                result => return result,
+            }
+        }
+        self.inner.export(batch).await
+    }
+
+    fn shutdown_with_timeout(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::atomic::Ordering;
+
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::anything;
+    use googletest::matchers::eq;
+    use googletest::matchers::err;
+    use googletest::matchers::ok;
+    use rstest::rstest;
+
+    use super::*;
+
+    /// Exporter failing a fixed number of times before succeeding.
+    #[derive(Debug)]
+    struct FlakyExporter {
+        failures: u32,
+        calls: Arc<AtomicU32>,
+    }
+
+    impl SpanExporter for FlakyExporter {
+        async fn export(&self, _batch: Vec<SpanData>) -> OTelSdkResult {
+            let call = self.calls.fetch_add(1, Ordering::Relaxed);
+            if call < self.failures {
+                Err(OTelSdkError::InternalFailure(String::from("unavailable")))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn retrying(
+        failures: u32,
+        max_retries: u32,
+    ) -> (RetryingExporter<FlakyExporter>, Arc<AtomicU32>) {
+        let calls = Arc::new(AtomicU32::new(0));
+        let config = RetryConfig::builder()
+            .max_retries(max_retries)
+            .initial_backoff(Duration::ZERO)
+            .max_backoff(Duration::ZERO)
+            .build();
+        let inner = FlakyExporter {
+            failures,
+            calls: Arc::clone(&calls),
+        };
+        (RetryingExporter::new(inner, config), calls)
+    }
+
+    #[tokio::test]
+    #[gtest]
+    async fn transient_failures_are_retried() {
+        let (exporter, calls) = retrying(2, 3);
+        expect_that!(exporter.export(Vec::new()).await, ok(anything()));
+        expect_that!(calls.load(Ordering::Relaxed), eq(3));
+    }
+
+    #[tokio::test]
+    #[gtest]
+    async fn retries_stop_at_the_limit() {
+        let (exporter, calls) = retrying(5, 2);
+        expect_that!(exporter.export(Vec::new()).await, err(anything()));
+        expect_that!(calls.load(Ordering::Relaxed), eq(3));
+    }
+
+    #[rstest]
+    #[case(0, Duration::from_millis(100))]
+    #[case(1, Duration::from_millis(200))]
+    #[case(3, Duration::from_millis(500))]
+    #[case(40, Duration::from_millis(500))]
+    fn backoff_doubles_up_to_the_maximum(#[case] attempt: u32, #[case] expected: Duration) {
+        let config = RetryConfig::builder()
+            .max_retries(3)
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(500))
+            .build();
+        assert_that!(config.backoff(attempt), eq(expected));
+    }
+
+    #[gtest]
+    fn initial_backoff_above_maximum_is_rejected() {
+        let config = RetryConfig::builder()
+            .max_retries(1)
+            .initial_backoff(Duration::from_secs(2))
+            .max_backoff(Duration::from_secs(1))
+            .build();
+        expect_that!(config.validate(), err(anything()));
+    }
+}
@@ -0,0 +1,148 @@
+//! Sampler keeping the spans flagged by an attribute.
+
+use opentelemetry::Context;
+use opentelemetry::Key;
+use opentelemetry::KeyValue;
+use opentelemetry::Value;
+use opentelemetry::trace::Link;
+use opentelemetry::trace::SamplingDecision;
+use opentelemetry::trace::SamplingResult;
+use opentelemetry::trace::SpanKind;
+use opentelemetry::trace::TraceContextExt as _;
+use opentelemetry::trace::TraceId;
+use opentelemetry::trace::TraceState;
+use opentelemetry_sdk::trace::Sampler;
+use opentelemetry_sdk::trace::ShouldSample;
+
+use crate::env_vars;
+
+/// Samples every span whose `attribute` is `true`, deferring to `fallback`
+/// for the others.
+///
+/// Spans start when first entered, so the attribute must be set when the
+/// span is created, or recorded before it is entered. Children of a kept
+/// span follow it when the fallback is parent-based.
+#[derive(Clone, Debug)]
+pub(crate) struct AttributeSampler {
+    attribute: Key,
+    fallback: Sampler,
+}
+
+impl AttributeSampler {
+    /// Creates the sampler, with `fallback` defaulting to `OTEL_TRACES_SAMPLER`
+    /// and then to the SDK default, `parentbased_always_on`.
+    pub(crate) fn new(attribute: impl Into<Key>, fallback: Option<Sampler>) -> Self {
+        Self {
+            attribute: attribute.into(),
+            fallback: fallback.unwrap_or_else(default_sampler),
+        }
+    }
+
+    /// Returns whether `attributes` flag the span.
+    fn is_flagged(&self, attributes: &[KeyValue]) -> bool {
+        attributes.iter().any(|kv| {
+            kv.key == self.attribute
+                && match &kv.value {
+                    Value::Bool(flag) => *flag,
+                    Value::String(flag) => flag.as_str() == "true",
+                    _ => false,
+                }
+        })
+    }
+}
+
+impl ShouldSample for AttributeSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        if !self.is_flagged(attributes) {
+            return self.fallback.should_sample(
+                parent_context,
+                trace_id,
+                name,
+                span_kind,
+                attributes,
+                links,
+            );
+        }
+        SamplingResult {
+            decision: SamplingDecision::RecordAndSample,
+            attributes: Vec::new(),
+            trace_state: parent_context.map_or_else(TraceState::default, |cx| {
+                cx.span().span_context().trace_state().clone()
+            }),
+        }
+    }
+}
+
+/// Returns the sampler named by `OTEL_TRACES_SAMPLER`, or the SDK default.
+///
+/// Like the SDK, an invalid value falls back to the default.
+fn default_sampler() -> Sampler {
+    std::env::var(env_vars::OTEL_TRACES_SAMPLER)
+        .ok()
+        .and_then(|name| {
+            let arg = std::env::var(env_vars::OTEL_TRACES_SAMPLER_ARG).ok();
+            super::parse_sampler(&name, arg.as_deref()).ok()
+        })
+        .unwrap_or_else(|| Sampler::ParentBased(Box::new(Sampler::AlwaysOn)))
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn decision(sampler: &AttributeSampler, attributes: &[KeyValue]) -> SamplingDecision {
+        sampler
+            .should_sample(
+                None,
+                TraceId::from(42),
+                "request",
+                &SpanKind::Internal,
+                attributes,
+                &[],
+            )
+            .decision
+    }
+
+    #[rstest]
+    #[case(KeyValue::new("error", true))]
+    #[case(KeyValue::new("error", "true"))]
+    fn flagged_spans_survive_a_zero_ratio(#[case] attribute: KeyValue) {
+        let sampler = AttributeSampler::new("error", Some(Sampler::TraceIdRatioBased(0.0)));
+        assert_that!(
+            decision(&sampler, &[attribute]),
+            eq(&SamplingDecision::RecordAndSample)
+        );
+    }
+
+    #[rstest]
+    #[case(&[])]
+    #[case(&[KeyValue::new("error", false)])]
+    #[case(&[KeyValue::new("failed", true)])]
+    fn other_spans_use_the_fallback(#[case] attributes: &[KeyValue]) {
+        let sampler = AttributeSampler::new("error", Some(Sampler::TraceIdRatioBased(0.0)));
+        assert_that!(decision(&sampler, attributes), eq(&SamplingDecision::Drop));
+    }
+
+    #[gtest]
+    fn attribute_is_configurable() {
+        let sampler = AttributeSampler::new("keep", Some(Sampler::AlwaysOff));
+        expect_that!(
+            decision(&sampler, &[KeyValue::new("keep", true)]),
+            eq(&SamplingDecision::RecordAndSample)
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Parsing of the `backend@endpoint` shorthand of [`TraceExporter`].
+
+use std::str::FromStr;
+
+#[cfg(any(
+    feature = "axiom",
+    feature = "datadog",
+    feature = "honeycomb",
+    feature = "signoz",
+    feature = "tempo"
+))]
+use secrecy::SecretString;
+use url::Url;
+
+#[cfg(feature = "axiom")]
+use super::AxiomConfig;
+#[cfg(feature = "datadog")]
+use super::DatadogConfig;
+#[cfg(feature = "honeycomb")]
+use super::HoneycombConfig;
+use super::OtlpConfig;
+#[cfg(feature = "signoz")]
+use super::SigNozConfig;
+#[cfg(feature = "tempo")]
+use super::TempoConfig;
+use super::TraceExporter;
+#[cfg(feature = "uptrace")]
+use super::UptraceConfig;
+#[cfg(any(
+    feature = "axiom",
+    feature = "datadog",
+    feature = "honeycomb",
+    feature = "signoz",
+    feature = "tempo",
+    feature = "uptrace"
+))]
+use super::otlp::DEFAULT_OTLP_TIMEOUT;
+#[cfg(unix)]
+use super::otlp::UNIX_SCHEME;
+use crate::error::Error;
+use crate::error::ErrorKind;
+
+impl FromStr for TraceExporter {
+    type Err = Error;
+
+    /// Parses a backend from a single string, for configuration that only
+    /// has string values.
+    ///
+    /// Accepted shapes:
+    ///
+    /// - `console` and `console-json`.
+    /// - An OTLP endpoint URL, e.g. `http://collector:4317`. The `otel` and
+    ///   `otels` schemes stand for `http` and `https`. On Unix, a
+    ///   `unix:///path/to/socket` URL exports through that socket.
+    /// - `<backend>@<endpoint>`, with the credentials as query parameters:
+    ///   `honeycomb@https://api.honeycomb.io?key=...`. Datadog and SigNoz
+    ///   also take `key`, Axiom `token` and `dataset`, Tempo `user` and
+    ///   `password`. Uptrace takes its DSN as the endpoint, and `otlp` a
+    ///   plain endpoint.
+    ///
+    /// Credential parameters are removed from the endpoint. Vendor backends
+    /// use the default timeout, gRPC and no compression.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('@') {
+            Some((name, endpoint)) if is_backend_name(name) => backend(name, endpoint),
+            _ => match value {
+                #[cfg(feature = "console")]
+                "console" => Ok(Self::Console),
+                #[cfg(feature = "console")]
+                "console-json" => Ok(Self::ConsoleJson),
+                url => otlp_url(url),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for TraceExporter {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Backend names of the `<backend>@<endpoint>` form, with their display names.
+const BACKEND_LABELS: &[(&str, &str)] = &[
+    ("otlp", "OTLP"),
+    ("axiom", "Axiom"),
+    ("datadog", "Datadog"),
+    ("honeycomb", "Honeycomb"),
+    ("signoz", "SigNoz"),
+    ("tempo", "Tempo"),
+];
+
+/// Whether `name` can be a backend name rather than URL user information.
+fn is_backend_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Parses a bare OTLP endpoint URL.
+fn otlp_url(value: &str) -> Result<TraceExporter, Error> {
+    let url = parse_url(value, "OTLP")?;
+    let endpoint = match url.scheme() {
+        "http" | "https" | "grpc" | "grpcs" => url,
+        #[cfg(unix)]
+        UNIX_SCHEME => url,
+        "otel" => parse_url(&format!("http:{}", &value["otel:".len()..]), "OTLP")?,
+        "otels" => parse_url(&format!("https:{}", &value["otels:".len()..]), "OTLP")?,
+        scheme => return Err(ErrorKind::UnsupportedTracesCollector(scheme.to_owned()).into()),
+    };
+    Ok(TraceExporter::Otlp(OtlpConfig {
+        endpoint,
+        ..OtlpConfig::default()
+    }))
+}
+
+/// Parses the endpoint of the named backend.
+fn backend(name: &str, endpoint: &str) -> Result<TraceExporter, Error> {
+    #[cfg(feature = "uptrace")]
+    if name == "uptrace" {
+        return Ok(TraceExporter::Uptrace(
+            UptraceConfig::builder()
+                .dsn(endpoint.into())
+                .timeout(DEFAULT_OTLP_TIMEOUT)
+                .build(),
+        ));
+    }
+    let label = BACKEND_LABELS
+        .iter()
+        .find_map(|&(backend, label)| (backend == name).then_some(label))
+        .ok_or_else(|| ErrorKind::UnsupportedTracesCollector(name.to_owned()))?;
+    #[cfg_attr(
+        not(any(
+            feature = "axiom",
+            feature = "datadog",
+            feature = "honeycomb",
+            feature = "signoz",
+            feature = "tempo"
+        )),
+        allow(unused_mut, reason = "only vendor backends take credentials")
+    )]
+    let mut endpoint = Endpoint::parse(endpoint, label)?;
+    let exporter = match name {
+        "otlp" => TraceExporter::Otlp(OtlpConfig {
+            endpoint: endpoint.finish(),
+            ..OtlpConfig::default()
+        }),
+        #[cfg(feature = "axiom")]
+        "axiom" => TraceExporter::Axiom(
+            AxiomConfig::builder()
+                .token(endpoint.secret(name, "token")?)
+                .dataset(endpoint.param(name, "dataset")?)
+                .endpoint(endpoint.finish())
+                .timeout(DEFAULT_OTLP_TIMEOUT)
+                .build(),
+        ),
+        #[cfg(feature = "datadog")]
+        "datadog" => TraceExporter::Datadog(
+            DatadogConfig::builder()
+                .api_key(endpoint.secret(name, "key")?)
+                .endpoint(endpoint.finish())
+                .timeout(DEFAULT_OTLP_TIMEOUT)
+                .build(),
+        ),
+        #[cfg(feature = "honeycomb")]
+        "honeycomb" => TraceExporter::Honeycomb(
+            HoneycombConfig::builder()
+                .api_key(endpoint.secret(name, "key")?)
+                .endpoint(endpoint.finish())
+                .timeout(DEFAULT_OTLP_TIMEOUT)
+                .build(),
+        ),
+        #[cfg(feature = "signoz")]
+        "signoz" => TraceExporter::SigNoz(
+            SigNozConfig::builder()
+                .access_token(endpoint.secret(name, "key")?)
+                .endpoint(endpoint.finish())
+                .timeout(DEFAULT_OTLP_TIMEOUT)
+                .build(),
+        ),
+        #[cfg(feature = "tempo")]
+        "tempo" => TraceExporter::Tempo(
+            TempoConfig::builder()
+                .username(endpoint.param(name, "user")?)
+                .password(endpoint.secret(name, "password")?)
+                .endpoint(endpoint.finish())
+                .timeout(DEFAULT_OTLP_TIMEOUT)
+                .build(),
+        ),
+        other => return Err(ErrorKind::UnsupportedTracesCollector(other.to_owned()).into()),
+    };
+    Ok(exporter)
+}
+
+/// Endpoint URL whose query parameters are taken out one by one.
+struct Endpoint {
+    url: Url,
+    params: Vec<(String, String)>,
+}
+
+impl Endpoint {
+    fn parse(value: &str, backend: &'static str) -> Result<Self, Error> {
+        let url = parse_url(value, backend)?;
+        let params = url.query_pairs().into_owned().collect();
+        Ok(Self { url, params })
+    }
+
+    /// Removes the `key` query parameter required by `backend`.
+    #[cfg(any(
+        feature = "axiom",
+        feature = "datadog",
+        feature = "honeycomb",
+        feature = "signoz",
+        feature = "tempo"
+    ))]
+    fn param(&mut self, backend: &str, key: &str) -> Result<String, Error> {
+        let index = self
+            .params
+            .iter()
+            .position(|(name, _)| name == key)
+            .ok_or_else(|| ErrorKind::ExporterConfig {
+                reason: format!("missing `{key}` query parameter for {backend}"),
+            })?;
+        Ok(self.params.remove(index).1)
+    }
+
+    /// Removes the `key` query parameter required by `backend`, as a secret.
+    #[cfg(any(
+        feature = "axiom",
+        feature = "datadog",
+        feature = "honeycomb",
+        feature = "signoz",
+        feature = "tempo"
+    ))]
+    fn secret(&mut self, backend: &str, key: &str) -> Result<SecretString, Error> {
+        self.param(backend, key).map(SecretString::from)
+    }
+
+    /// Returns the URL with the remaining query parameters.
+    fn finish(mut self) -> Url {
+        if self.params.is_empty() {
+            self.url.set_query(None);
+        } else {
+            self.url
+                .query_pairs_mut()
+                .clear()
+                .extend_pairs(&self.params);
+        }
+        self.url
+    }
+}
+
+/// Parses the endpoint URL of `backend`, keeping the input out of the error
+/// since it may hold credentials.
+fn parse_url(value: &str, backend: &'static str) -> Result<Url, Error> {
+    value.parse().map_err(|source| {
+        ErrorKind::InvalidEndpoint {
+            context: backend,
+            source,
+        }
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::eq;
+    use googletest::matchers::not;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("http://collector:4317", "http://collector:4317/")]
+    #[case("grpcs://collector:4317", "grpcs://collector:4317")]
+    #[case("otel://collector:4317", "http://collector:4317/")]
+    #[case("otels://collector:4317", "https://collector:4317/")]
+    #[case(
+        "otlp@https://collector:4318?debug=1",
+        "https://collector:4318/?debug=1"
+    )]
+    #[cfg_attr(unix, case("unix:///run/otel.sock", "unix:///run/otel.sock"))]
+    #[cfg_attr(unix, case("otlp@unix:///run/otel.sock", "unix:///run/otel.sock"))]
+    fn otlp_urls_are_parsed(#[case] value: &str, #[case] expected: &str) {
+        let exporter = TraceExporter::try_from(value).expect("valid shorthand");
+        assert_that!(
+            exporter
+                .otlp_target()
+                .map(|(endpoint, _)| endpoint.as_str()),
+            eq(Some(expected))
+        );
+    }
+
+    #[rstest]
+    #[case("ftp://collector:21")]
+    #[case("zipkin@http://collector:9411")]
+    fn unknown_schemes_are_unsupported(#[case] value: &str) {
+        let err = value.parse::<TraceExporter>().expect_err("unsupported");
+        assert_that!(
+            err.to_string(),
+            contains_substring("unsupported trace collector")
+        );
+    }
+
+    #[cfg(feature = "console")]
+    #[gtest]
+    fn console_names_are_parsed() {
+        use googletest::matchers::ok;
+        use googletest::matchers::pat;
+
+        expect_that!(
+            "console".parse::<TraceExporter>(),
+            ok(pat!(TraceExporter::Console))
+        );
+        expect_that!(
+            "console-json".parse::<TraceExporter>(),
+            ok(pat!(TraceExporter::ConsoleJson))
+        );
+    }
+
+    #[cfg(feature = "honeycomb")]
+    #[gtest]
+    fn credentials_are_taken_from_the_query() {
+        use secrecy::ExposeSecret as _;
+
+        let exporter: TraceExporter = "honeycomb@https://api.honeycomb.io:443?key=s3cr3t"
+            .parse()
+            .expect("valid shorthand");
+        let TraceExporter::Honeycomb(config) = exporter else {
+            panic!("expected Honeycomb, got {exporter:?}");
+        };
+        expect_that!(config.endpoint.as_str(), eq("https://api.honeycomb.io/"));
+        expect_that!(config.api_key.expose_secret(), eq("s3cr3t"));
+    }
+
+    #[cfg(feature = "tempo")]
+    #[gtest]
+    fn missing_credentials_are_reported_without_the_url() {
+        let err = "tempo@https://tempo.example.com?password=s3cr3t"
+            .parse::<TraceExporter>()
+            .expect_err("missing user");
+        expect_that!(err.to_string(), contains_substring("`user`"));
+        expect_that!(err.to_string(), not(contains_substring("s3cr3t")));
+    }
+
+    #[gtest]
+    fn invalid_urls_are_reported_without_the_input() {
+        let err = "otlp@s3cr3t"
+            .parse::<TraceExporter>()
+            .expect_err("invalid URL");
+        expect_that!(err.to_string(), contains_substring("invalid OTLP endpoint"));
+        expect_that!(err.to_string(), not(contains_substring("s3cr3t")));
+    }
+
+    #[cfg(feature = "honeycomb")]
+    #[gtest]
+    fn invalid_urls_name_the_backend() {
+        let err = "honeycomb@https://"
+            .parse::<TraceExporter>()
+            .expect_err("invalid URL");
+        expect_that!(
+            err.to_string(),
+            contains_substring("invalid Honeycomb endpoint: empty host")
+        );
+    }
+}
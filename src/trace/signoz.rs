@@ -0,0 +1,85 @@
+//! SigNoz configuration.
+
+use std::time::Duration;
+
+use bon::Builder;
+use secrecy::SecretString;
+use url::Url;
+
+use crate::Compression;
+use crate::OtlpConfig;
+use crate::OtlpTokenConfig;
+use crate::Protocol;
+
+/// Configuration for [SigNoz](https://signoz.io) trace export.
+#[derive(Debug, Clone, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SigNozConfig {
+    /// Exporter endpoint.
+    pub endpoint: Url,
+    /// SigNoz Cloud ingestion key.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
+    pub access_token: SecretString,
+    /// Export timeout, see [`OtlpConfig::timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
+    pub timeout: Duration,
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+}
+
+impl From<SigNozConfig> for OtlpTokenConfig {
+    fn from(config: SigNozConfig) -> Self {
+        Self {
+            timeout: config.timeout,
+            protocol: config.protocol,
+            compression: config.compression,
+            ..Self::signoz(config.endpoint, config.access_token)
+        }
+    }
+}
+
+impl From<SigNozConfig> for OtlpConfig {
+    fn from(config: SigNozConfig) -> Self {
+        OtlpTokenConfig::from(config).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains;
+    use googletest::matchers::eq;
+
+    use super::*;
+
+    #[gtest]
+    fn access_token_is_sent_as_header() {
+        let config = SigNozConfig::builder()
+            .endpoint(
+                "https://ingest.us.signoz.cloud:443"
+                    .parse()
+                    .expect("valid URL"),
+            )
+            .access_token("signoz-secret".into())
+            .timeout(Duration::from_secs(5))
+            .build();
+        expect_that!(
+            OtlpConfig::from(config).headers,
+            contains(eq(&(
+                "signoz-access-token".to_owned(),
+                "signoz-secret".to_owned()
+            )))
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Grafana Tempo configuration.
+
+use std::time::Duration;
+
+use bon::Builder;
+use secrecy::SecretString;
+use url::Url;
+
+use crate::Compression;
+use crate::OtlpConfig;
+use crate::OtlpTokenConfig;
+use crate::Protocol;
+
+/// Configuration for [Grafana Tempo](https://grafana.com/oss/tempo/) trace
+/// export with HTTP basic authentication, as used by Grafana Cloud.
+#[derive(Debug, Clone, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TempoConfig {
+    /// Exporter endpoint.
+    pub endpoint: Url,
+    /// Basic auth user, the instance ID on Grafana Cloud.
+    #[builder(into)]
+    pub username: String,
+    /// Basic auth password, an access token on Grafana Cloud.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
+    pub password: SecretString,
+    /// Export timeout, see [`OtlpConfig::timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
+    pub timeout: Duration,
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+}
+
+impl From<TempoConfig> for OtlpTokenConfig {
+    fn from(config: TempoConfig) -> Self {
+        Self {
+            timeout: config.timeout,
+            protocol: config.protocol,
+            compression: config.compression,
+            ..Self::tempo(config.endpoint, &config.username, &config.password)
+        }
+    }
+}
+
+impl From<TempoConfig> for OtlpConfig {
+    fn from(config: TempoConfig) -> Self {
+        OtlpTokenConfig::from(config).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::eq;
+    use googletest::matchers::not;
+
+    use super::*;
+
+    fn config() -> TempoConfig {
+        TempoConfig::builder()
+            .endpoint(
+                "https://tempo-prod.grafana.net:443"
+                    .parse()
+                    .expect("valid URL"),
+            )
+            .username("123456")
+            .password("glc-secret".into())
+            .timeout(Duration::from_secs(5))
+            .build()
+    }
+
+    #[gtest]
+    fn credentials_are_sent_as_basic_auth() {
+        let otlp = OtlpConfig::from(config());
+        expect_that!(
+            otlp.headers,
+            contains(eq(&(
+                "authorization".to_owned(),
+                "Basic MTIzNDU2OmdsYy1zZWNyZXQ=".to_owned()
+            )))
+        );
+    }
+
+    #[gtest]
+    fn password_is_redacted_from_debug() {
+        expect_that!(
+            format!("{:?}", config()),
+            not(contains_substring("glc-secret"))
+        );
+    }
+}
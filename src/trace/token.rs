@@ -0,0 +1,183 @@
+//! OTLP configuration authenticated by a token header.
+
+use std::time::Duration;
+
+#[cfg(feature = "tempo")]
+use base64::Engine as _;
+#[cfg(feature = "tempo")]
+use base64::engine::general_purpose::STANDARD;
+use bon::Builder;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use url::Url;
+
+use super::otlp::DEFAULT_OTLP_TIMEOUT;
+use crate::Compression;
+use crate::OtlpConfig;
+use crate::Protocol;
+
+/// Header carrying the Axiom API token.
+const AXIOM_HEADER: &str = "authorization";
+/// Header carrying the Datadog API key.
+const DATADOG_HEADER: &str = "dd-api-key";
+/// Header carrying the Honeycomb API key.
+const HONEYCOMB_HEADER: &str = "x-honeycomb-team";
+/// Header carrying the Lightstep access token.
+const LIGHTSTEP_HEADER: &str = "lightstep-access-token";
+/// Header carrying the SigNoz ingestion key.
+const SIGNOZ_HEADER: &str = "signoz-access-token";
+/// Header carrying the Tempo basic auth credentials.
+#[cfg(feature = "tempo")]
+const TEMPO_HEADER: &str = "authorization";
+/// Header carrying the Uptrace DSN.
+const UPTRACE_HEADER: &str = "uptrace-dsn";
+
+/// Configuration for an OTLP backend authenticated by a single token header.
+///
+/// The presets fill in the header name of known vendors, with the default
+/// OTLP timeout, gRPC and no compression.
+#[must_use]
+#[derive(Debug, Clone, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct OtlpTokenConfig {
+    /// Exporter endpoint.
+    pub endpoint: Url,
+    /// Name of the header carrying the token.
+    #[builder(into)]
+    pub header_name: String,
+    /// Token sent in the header.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
+    pub header_value: SecretString,
+    /// Export timeout, see [`OtlpConfig::timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
+    pub timeout: Duration,
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+}
+
+impl OtlpTokenConfig {
+    /// [Axiom](https://axiom.co), sending `authorization: Bearer <token>`.
+    ///
+    /// `AxiomConfig` adds the dataset header on top of this preset.
+    pub fn axiom(endpoint: Url, token: &SecretString) -> Self {
+        let bearer = format!("Bearer {}", token.expose_secret());
+        Self::preset(endpoint, AXIOM_HEADER, bearer.into())
+    }
+
+    /// [Datadog](https://www.datadoghq.com) OTLP intake, sending `dd-api-key`.
+    pub fn datadog(endpoint: Url, api_key: SecretString) -> Self {
+        Self::preset(endpoint, DATADOG_HEADER, api_key)
+    }
+
+    /// [Honeycomb](https://honeycomb.io), sending `x-honeycomb-team`.
+    pub fn honeycomb(endpoint: Url, api_key: SecretString) -> Self {
+        Self::preset(endpoint, HONEYCOMB_HEADER, api_key)
+    }
+
+    /// [Lightstep](https://lightstep.com), also known as ServiceNow Cloud
+    /// Observability, sending `lightstep-access-token`.
+    pub fn lightstep(endpoint: Url, access_token: SecretString) -> Self {
+        Self::preset(endpoint, LIGHTSTEP_HEADER, access_token)
+    }
+
+    /// [SigNoz](https://signoz.io), sending `signoz-access-token`.
+    pub fn signoz(endpoint: Url, access_token: SecretString) -> Self {
+        Self::preset(endpoint, SIGNOZ_HEADER, access_token)
+    }
+
+    /// [Grafana Tempo](https://grafana.com/oss/tempo/), sending
+    /// `authorization: Basic <credentials>`.
+    #[cfg(feature = "tempo")]
+    pub fn tempo(endpoint: Url, username: &str, password: &SecretString) -> Self {
+        let credentials = STANDARD.encode(format!("{username}:{}", password.expose_secret()));
+        Self::preset(
+            endpoint,
+            TEMPO_HEADER,
+            format!("Basic {credentials}").into(),
+        )
+    }
+
+    /// [Uptrace](https://uptrace.dev), sending `uptrace-dsn`.
+    ///
+    /// `UptraceConfig` derives the endpoint from the DSN.
+    pub fn uptrace(endpoint: Url, dsn: SecretString) -> Self {
+        Self::preset(endpoint, UPTRACE_HEADER, dsn)
+    }
+
+    /// Creates a configuration with default transport settings.
+    fn preset(endpoint: Url, header_name: &str, header_value: SecretString) -> Self {
+        Self::builder()
+            .endpoint(endpoint)
+            .header_name(header_name)
+            .header_value(header_value)
+            .timeout(DEFAULT_OTLP_TIMEOUT)
+            .build()
+    }
+}
+
+impl From<OtlpTokenConfig> for OtlpConfig {
+    fn from(config: OtlpTokenConfig) -> Self {
+        OtlpConfig::builder()
+            .endpoint(config.endpoint)
+            .timeout(config.timeout)
+            .protocol(config.protocol)
+            .maybe_compression(config.compression)
+            .headers(vec![(
+                config.header_name,
+                config.header_value.expose_secret().to_owned(),
+            )])
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::elements_are;
+    use googletest::matchers::eq;
+    use googletest::matchers::not;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn endpoint() -> Url {
+        "https://ingest.example.com:443".parse().expect("valid URL")
+    }
+
+    #[rstest]
+    #[case(OtlpTokenConfig::datadog, "dd-api-key")]
+    #[case(OtlpTokenConfig::honeycomb, "x-honeycomb-team")]
+    #[case(OtlpTokenConfig::lightstep, "lightstep-access-token")]
+    #[case(OtlpTokenConfig::signoz, "signoz-access-token")]
+    #[case(OtlpTokenConfig::uptrace, "uptrace-dsn")]
+    fn presets_send_the_vendor_header(
+        #[case] preset: fn(Url, SecretString) -> OtlpTokenConfig,
+        #[case] header: &str,
+    ) {
+        let otlp = OtlpConfig::from(preset(endpoint(), "s3cr3t".into()));
+        assert_that!(
+            otlp.headers,
+            elements_are![eq(&(header.to_owned(), "s3cr3t".to_owned()))]
+        );
+        assert_that!(otlp.timeout, eq(DEFAULT_OTLP_TIMEOUT));
+    }
+
+    #[gtest]
+    fn token_is_redacted_from_debug() {
+        let config = OtlpTokenConfig::lightstep(endpoint(), "s3cr3t".into());
+        expect_that!(format!("{config:?}"), not(contains_substring("s3cr3t")));
+    }
+}
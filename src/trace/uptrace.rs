@@ -0,0 +1,177 @@
+//! Uptrace configuration.
+
+use std::time::Duration;
+
+use bon::Builder;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use url::Url;
+
+use crate::Compression;
+use crate::OtlpConfig;
+use crate::OtlpTokenConfig;
+use crate::Protocol;
+use crate::error::Error;
+use crate::error::ErrorKind;
+
+/// DSN query parameter holding the OTLP/gRPC port.
+const GRPC_PORT_PARAM: &str = "grpc";
+/// OTLP/gRPC port used when the DSN names none.
+const DEFAULT_GRPC_PORT: u16 = 4317;
+
+/// Configuration for [Uptrace](https://uptrace.dev) trace export.
+///
+/// The endpoint is derived from the DSN, e.g.
+/// `https://<token>@api.uptrace.dev?grpc=4317`: gRPC uses the port of the
+/// `grpc` query parameter, defaulting to 4317, and HTTP the port of the DSN.
+#[derive(Debug, Clone, Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct UptraceConfig {
+    /// Project DSN, embedding the project token.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::redact::serialize_secret")
+    )]
+    pub dsn: SecretString,
+    /// Export timeout, see [`OtlpConfig::timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::trace::de::deserialize_timeout")
+    )]
+    pub timeout: Duration,
+    /// Transport protocol. Defaults to gRPC.
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub protocol: Protocol,
+    /// Payload compression. Defaults to uncompressed.
+    pub compression: Option<Compression>,
+}
+
+impl TryFrom<UptraceConfig> for OtlpConfig {
+    type Error = Error;
+
+    /// Parses the DSN into the endpoint and the `uptrace-dsn` header.
+    ///
+    /// Error messages never include the DSN, which holds the project token.
+    fn try_from(config: UptraceConfig) -> Result<Self, Error> {
+        let endpoint = dsn_endpoint(config.dsn.expose_secret(), &config.protocol)?;
+        Ok(OtlpTokenConfig {
+            timeout: config.timeout,
+            protocol: config.protocol,
+            compression: config.compression,
+            ..OtlpTokenConfig::uptrace(endpoint, config.dsn)
+        }
+        .into())
+    }
+}
+
+/// Derives the OTLP endpoint for `protocol` from a DSN.
+fn dsn_endpoint(dsn: &str, protocol: &Protocol) -> Result<Url, Error> {
+    let dsn = Url::parse(dsn).map_err(|err| invalid_dsn(&err.to_string()))?;
+    if dsn.username().is_empty() {
+        return Err(invalid_dsn("missing project token"));
+    }
+    if dsn.host_str().is_none_or(str::is_empty) {
+        return Err(invalid_dsn("missing host"));
+    }
+    let port = match protocol {
+        Protocol::Grpc => {
+            let param = dsn
+                .query_pairs()
+                .find(|(key, _)| key == GRPC_PORT_PARAM)
+                .map(|(_, port)| port.parse::<u16>());
+            match param {
+                Some(Ok(port)) => Some(port),
+                Some(Err(_)) => return Err(invalid_dsn("invalid gRPC port")),
+                None => Some(DEFAULT_GRPC_PORT),
+            }
+        }
+        #[cfg(feature = "http")]
+        Protocol::HttpBinary | Protocol::HttpJson => dsn.port(),
+    };
+
+    let mut endpoint = dsn;
+    endpoint.set_query(None);
+    endpoint.set_fragment(None);
+    endpoint.set_path("");
+    endpoint
+        .set_username("")
+        .and_then(|()| endpoint.set_password(None))
+        .and_then(|()| endpoint.set_port(port))
+        .map_err(|()| invalid_dsn("unsupported scheme"))?;
+    Ok(endpoint)
+}
+
+/// Builds the error for an unusable DSN.
+fn invalid_dsn(reason: &str) -> Error {
+    ErrorKind::ExporterConfig {
+        reason: format!("invalid Uptrace DSN: {reason}"),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::assert_that;
+    use googletest::expect_that;
+    use googletest::gtest;
+    use googletest::matchers::contains;
+    use googletest::matchers::contains_substring;
+    use googletest::matchers::eq;
+    use googletest::matchers::not;
+    use rstest::rstest;
+
+    use super::*;
+
+    const DSN: &str = "https://s3cr3t@api.uptrace.dev?grpc=4317";
+
+    fn config(dsn: &str) -> UptraceConfig {
+        UptraceConfig::builder()
+            .dsn(dsn.into())
+            .timeout(Duration::from_secs(5))
+            .build()
+    }
+
+    #[gtest]
+    fn dsn_is_sent_as_header() {
+        let otlp = OtlpConfig::try_from(config(DSN)).expect("valid DSN");
+        expect_that!(
+            otlp.headers,
+            contains(eq(&("uptrace-dsn".to_owned(), DSN.to_owned())))
+        );
+        expect_that!(otlp.endpoint.as_str(), eq("https://api.uptrace.dev:4317/"));
+    }
+
+    #[rstest]
+    #[case("http://token@localhost:14318?grpc=14317", "http://localhost:14317/")]
+    #[case("https://token@uptrace.internal", "https://uptrace.internal:4317/")]
+    fn grpc_endpoint_uses_the_grpc_port(#[case] dsn: &str, #[case] expected: &str) {
+        let endpoint = dsn_endpoint(dsn, &Protocol::Grpc).expect("valid DSN");
+        assert_that!(endpoint.as_str(), eq(expected));
+    }
+
+    #[cfg(feature = "http")]
+    #[rstest]
+    #[case("http://token@localhost:14318?grpc=14317", "http://localhost:14318/")]
+    #[case(DSN, "https://api.uptrace.dev/")]
+    fn http_endpoint_uses_the_dsn_port(#[case] dsn: &str, #[case] expected: &str) {
+        let endpoint = dsn_endpoint(dsn, &Protocol::HttpBinary).expect("valid DSN");
+        assert_that!(endpoint.as_str(), eq(expected));
+    }
+
+    #[rstest]
+    #[case("not a url s3cr3t")]
+    #[case("https://api.uptrace.dev?grpc=4317&token=s3cr3t")]
+    #[case("https://s3cr3t@api.uptrace.dev?grpc=port")]
+    fn invalid_dsn_is_rejected_without_leaking_it(#[case] dsn: &str) {
+        let err = OtlpConfig::try_from(config(dsn)).expect_err("invalid DSN");
+        assert_that!(err.to_string(), contains_substring("invalid Uptrace DSN"));
+        assert_that!(err.to_string(), not(contains_substring("s3cr3t")));
+    }
+
+    #[gtest]
+    fn dsn_is_redacted_from_debug() {
+        let config = config(DSN);
+        expect_that!(format!("{config:?}"), not(contains_substring("s3cr3t")));
+    }
+}
@@ -0,0 +1,23 @@
+//! Fully disabled initialization tests.
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use tracing::subscriber::NoSubscriber;
+
+#[gtest]
+fn disabled_installs_a_no_op_subscriber_without_a_runtime() {
+    let guard = Owiwi::builder()
+        .service_name("disabled")
+        .disabled(true)
+        .build()
+        .try_init()
+        .expect("subscriber to be installed");
+    expect_that!(
+        tracing::dispatcher::get_default(|dispatch| dispatch.is::<NoSubscriber>()),
+        eq(true)
+    );
+    expect_that!(guard.service_name(), eq("disabled"));
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,26 @@
+//! Disabled error layer integration test.
+
+#![cfg(feature = "testing")]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+use tracing_error::SpanTrace;
+use tracing_error::SpanTraceStatus;
+
+#[gtest]
+fn span_traces_are_not_captured_without_the_error_layer() {
+    let guard = Owiwi::builder()
+        .service_name("error-layer")
+        .error_layer(false)
+        .build()
+        .try_init_with_exporter(InMemoryCollector::default())
+        .expect("subscriber to be installed");
+
+    let status = tracing::error_span!("request").in_scope(|| SpanTrace::capture().status());
+
+    expect_that!(status, eq(&SpanTraceStatus::UNSUPPORTED));
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,31 @@
+//! Export filter integration test.
+
+#![cfg(feature = "testing")]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::elements_are;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn export_directives_drop_noisy_targets() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("export-filter")
+        .tracing_directives(vec!["info".parse().expect("valid directive")])
+        .export_directives(vec![
+            "info".parse().expect("valid directive"),
+            "hyper=off".parse().expect("valid directive"),
+        ])
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    tracing::info_span!(target: "hyper", "connection").in_scope(|| {});
+    tracing::info_span!("request").in_scope(|| {});
+
+    expect_that!(collector.span_names(), elements_are![eq("request")]);
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,24 @@
+//! Global subscriber initialization tests.
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+
+#[gtest]
+fn second_initialization_is_reported() {
+    let guard = Owiwi::builder()
+        .service_name("init")
+        .no_telemetry(true)
+        .build()
+        .try_init()
+        .expect("subscriber to be installed");
+    expect_that!(guard.service_name(), eq("init"));
+
+    let second = Owiwi::builder().no_telemetry(true).build().try_init();
+    expect_that!(
+        second.err().map(|err| err.is_already_initialized()),
+        eq(Some(true))
+    );
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,22 @@
+//! Global initialization after a scoped subscriber test.
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+
+#[gtest]
+fn scoped_subscribers_do_not_block_initialization() {
+    tracing::subscriber::with_default(tracing_subscriber::registry(), || {
+        tracing::info!("scoped");
+    });
+
+    let guard = Owiwi::builder()
+        .service_name("scoped")
+        .no_telemetry(true)
+        .build()
+        .try_init()
+        .expect("subscriber to be installed");
+    expect_that!(guard.service_name(), eq("scoped"));
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,26 @@
+//! Initialization inside a tokio runtime test.
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+
+#[tokio::test(flavor = "multi_thread")]
+#[gtest]
+async fn otlp_initializes_inside_a_runtime() {
+    let guard = Owiwi::builder()
+        .service_name("init-async")
+        .build()
+        .try_init()
+        .expect("subscriber to be installed");
+    expect_that!(guard.service_name(), eq("init-async"));
+
+    let second = Owiwi::builder()
+        .service_name("init-async")
+        .build()
+        .try_init();
+    expect_that!(
+        second.err().map(|err| err.is_already_initialized()),
+        eq(Some(true))
+    );
+}
@@ -0,0 +1,41 @@
+//! Additional layers integration test.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use tracing::Event;
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::Layer;
+
+/// Layer counting the events of this test it sees, ignoring those of the
+/// exporter's runtime threads.
+struct CountingLayer(Arc<AtomicUsize>);
+
+impl<S: Subscriber> Layer<S> for CountingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() == module_path!() {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[gtest]
+async fn user_layers_see_events() {
+    let events = Arc::new(AtomicUsize::new(0));
+    let _guard = Owiwi::builder()
+        .service_name("layers")
+        .build()
+        .try_init_with_layers(vec![Box::new(CountingLayer(Arc::clone(&events)))])
+        .expect("subscriber to be installed");
+
+    tracing::info!("counted");
+
+    expect_that!(events.load(Ordering::Relaxed), eq(1));
+}
@@ -0,0 +1,31 @@
+//! `log` bridge integration test.
+
+#![cfg(all(feature = "testing", feature = "log"))]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::elements_are;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn log_records_are_recorded_as_span_events() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("log-bridge")
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    tracing::info_span!("request").in_scope(|| log::info!("from the log crate"));
+
+    let events: Vec<String> = collector
+        .spans()
+        .into_iter()
+        .flat_map(|span| span.events.events)
+        .map(|event| event.name.into_owned())
+        .collect();
+    expect_that!(events, elements_are![eq("from the log crate")]);
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,34 @@
+//! `log` bridge integration test with an application logger.
+
+#![cfg(all(feature = "testing", feature = "log"))]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::anything;
+use googletest::matchers::ok;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+struct AppLogger;
+
+impl log::Log for AppLogger {
+    fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, _record: &log::Record<'_>) {}
+
+    fn flush(&self) {}
+}
+
+#[gtest]
+fn an_application_logger_is_kept() {
+    log::set_logger(&AppLogger).expect("logger to be set");
+
+    let result = Owiwi::builder()
+        .service_name("log-bridge")
+        .build()
+        .try_init_with_exporter(InMemoryCollector::default());
+
+    expect_that!(result, ok(anything()));
+}
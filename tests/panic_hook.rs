@@ -0,0 +1,54 @@
+//! Panic hook integration test.
+
+#![cfg(feature = "testing")]
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use googletest::matchers::some;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn panics_are_recorded_before_the_previous_hook() {
+    // The previous hook runs before unwinding, while the outer span is open.
+    let collector = InMemoryCollector::default();
+    let exported = Arc::new(Mutex::new(None));
+    let previous = (collector.clone(), Arc::clone(&exported));
+    std::panic::set_hook(Box::new(move |_| {
+        let (collector, exported) = &previous;
+        *exported.lock().expect("not poisoned") = Some(panic_message(collector));
+    }));
+
+    let guard = Owiwi::builder()
+        .service_name("panic-hook")
+        .install_panic_hook(true)
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    let result = std::panic::catch_unwind(|| {
+        tracing::info_span!("request").in_scope(|| panic!("boom"));
+    });
+
+    expect_that!(result.is_err(), eq(true));
+    expect_that!(
+        *exported.lock().expect("not poisoned"),
+        some(some(eq("boom")))
+    );
+    guard.shutdown().expect("providers to shut down");
+}
+
+/// Returns the panic message recorded in the exported spans.
+fn panic_message(collector: &InMemoryCollector) -> Option<String> {
+    collector
+        .spans()
+        .iter()
+        .flat_map(|span| span.events.iter())
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key.as_str() == "panic.message")
+        .map(|attribute| attribute.value.to_string())
+}
@@ -0,0 +1,64 @@
+//! Parent-based sampling integration test.
+
+#![cfg(feature = "testing")]
+
+use std::collections::HashMap;
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::elements_are;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+/// Handles a request carrying the given `traceparent` header.
+fn handle_request(name: &'static str, traceparent: Option<&str>) {
+    let headers: HashMap<String, String> = traceparent
+        .map(|value| (String::from("traceparent"), value.to_owned()))
+        .into_iter()
+        .collect();
+    let parent =
+        opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(&headers));
+    let span = tracing::info_span!("request", otel.name = name);
+    let _ = span.set_parent(parent);
+    span.in_scope(|| tracing::info_span!("child").in_scope(|| {}));
+}
+
+#[gtest]
+fn children_follow_the_upstream_sampling_decision() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("parent-based")
+        .sample_parent_based(0.0)
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    handle_request(
+        "sampled",
+        Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+    );
+    handle_request(
+        "not_sampled",
+        Some("00-4bf92f3577b34da6a3ce929d0e0e4737-00f067aa0ba902b8-00"),
+    );
+    handle_request("root", None);
+
+    expect_that!(
+        collector.span_names(),
+        elements_are![eq("child"), eq("sampled")]
+    );
+    expect_that!(
+        collector
+            .spans()
+            .iter()
+            .map(|span| span.span_context.trace_id().to_string())
+            .collect::<Vec<_>>(),
+        elements_are![
+            eq("4bf92f3577b34da6a3ce929d0e0e4736"),
+            eq("4bf92f3577b34da6a3ce929d0e0e4736")
+        ]
+    );
+    guard.shutdown().expect("providers to shut down");
+}
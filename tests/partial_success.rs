@@ -0,0 +1,116 @@
+//! OTLP/HTTP partial success integration test.
+
+#![cfg(feature = "http")]
+
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::contains_substring;
+use googletest::matchers::elements_are;
+use owiwi::OtlpConfig;
+use owiwi::Owiwi;
+use owiwi::Protocol;
+use owiwi::TraceExporter;
+use tracing::Event;
+use tracing::Level;
+use tracing::Subscriber;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::Layer;
+
+/// Collector response accepting all but two spans.
+const PARTIAL_SUCCESS: &str =
+    r#"{"partialSuccess":{"rejectedSpans":"2","errorMessage":"span name too long"}}"#;
+
+/// Starts a collector answering every export with [`PARTIAL_SUCCESS`] and
+/// returns its address.
+fn start_collector() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("listener to bind");
+    let address = listener.local_addr().expect("listener address");
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().unwrap_or_default();
+                }
+                line.clear();
+            }
+            if line != "\r\n" {
+                continue;
+            }
+            let mut body = vec![0; content_length];
+            let _ = reader.read_exact(&mut body);
+            let _ = write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{PARTIAL_SUCCESS}",
+                PARTIAL_SUCCESS.len()
+            );
+        }
+    });
+    format!("http://{address}")
+}
+
+/// Layer recording the messages of the warnings it sees.
+struct WarningLayer(Arc<Mutex<Vec<String>>>);
+
+impl<S: Subscriber> Layer<S> for WarningLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if *event.metadata().level() == Level::WARN {
+            let mut visitor = MessageVisitor::default();
+            event.record(&mut visitor);
+            self.0.lock().expect("warnings lock").push(visitor.0);
+        }
+    }
+}
+
+/// Visitor formatting every field of an event.
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push_str(&format!("{}={value:?} ", field.name()));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[gtest]
+async fn rejected_spans_are_reported() {
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let exporter = OtlpConfig::builder()
+        .endpoint(start_collector().parse().expect("valid URL"))
+        .timeout(Duration::from_secs(5))
+        .protocol(Protocol::HttpJson)
+        .build();
+    let guard = Owiwi::builder()
+        .service_name("partial-success")
+        .traces(TraceExporter::Otlp(exporter))
+        .build()
+        .try_init_with_layers(vec![Box::new(WarningLayer(Arc::clone(&warnings)))])
+        .expect("subscriber to be installed");
+
+    tracing::info_span!("request").in_scope(|| {});
+    guard.flush().expect("spans to be exported");
+
+    expect_that!(
+        *warnings.lock().expect("warnings lock"),
+        elements_are![contains_substring("rejected_spans=2")]
+    );
+}
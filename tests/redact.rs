@@ -0,0 +1,45 @@
+//! Field redaction integration test.
+
+#![cfg(feature = "testing")]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::contains;
+use googletest::matchers::eq;
+use googletest::matchers::not;
+use opentelemetry::KeyValue;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn exported_spans_are_redacted() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("redact")
+        .redact_fields(vec!["password".to_owned(), "*_token".to_owned()])
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    tracing::info_span!("login", api_token = "span-secret", user = "ada").in_scope(|| {
+        tracing::info!(password = "hunter2", "signed in");
+    });
+
+    let spans = collector.spans();
+    let span = spans.first().expect("one exported span");
+    expect_that!(
+        span.attributes,
+        contains(eq(&KeyValue::new("api_token", "[REDACTED]")))
+    );
+    expect_that!(span.attributes, contains(eq(&KeyValue::new("user", "ada"))));
+    let event = span.events.first().expect("one span event");
+    expect_that!(
+        event.attributes,
+        contains(eq(&KeyValue::new("password", "[REDACTED]")))
+    );
+    expect_that!(
+        event.attributes,
+        not(contains(eq(&KeyValue::new("password", "hunter2"))))
+    );
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,37 @@
+//! Resource attributes integration test.
+
+#![cfg(feature = "testing")]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use googletest::matchers::some;
+use opentelemetry::Key;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn exported_spans_carry_the_service_attributes() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("resource")
+        .service_namespace("shop")
+        .service_version("1.2.3")
+        .service_instance_id("worker-7")
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    tracing::info_span!("request").in_scope(|| {});
+
+    let attribute = |key: &'static str| {
+        collector
+            .resource()
+            .and_then(|resource| resource.get(&Key::new(key)))
+            .map(|value| value.to_string())
+    };
+    expect_that!(attribute("service.namespace"), some(eq("shop")));
+    expect_that!(attribute("service.version"), some(eq("1.2.3")));
+    expect_that!(attribute("service.instance.id"), some(eq("worker-7")));
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,29 @@
+//! Attribute-based sampling integration test.
+
+#![cfg(feature = "testing")]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::elements_are;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn error_spans_survive_a_zero_ratio() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("sampling")
+        .traces_sampler("traceidratio")
+        .traces_sampler_arg("0")
+        .always_sample_attribute("error")
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    tracing::info_span!("succeeded").in_scope(|| {});
+    tracing::info_span!("failed", error = true).in_scope(|| {});
+
+    expect_that!(collector.span_names(), elements_are![eq("failed")]);
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,40 @@
+//! Span status helper integration test.
+
+#![cfg(feature = "testing")]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::elements_are;
+use googletest::matchers::eq;
+use opentelemetry::trace::Status;
+use owiwi::Owiwi;
+use owiwi::SpanExt as _;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn results_set_the_span_status() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("span-ext")
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    let succeeded = tracing::info_span!("succeeded");
+    succeeded.record_result(&Ok::<(), &str>(()));
+    drop(succeeded);
+    let failed = tracing::info_span!("failed");
+    failed.record_result(&Err::<(), _>("connection refused"));
+    drop(failed);
+
+    let statuses: Vec<Status> = collector
+        .spans()
+        .into_iter()
+        .map(|span| span.status)
+        .collect();
+    expect_that!(
+        statuses,
+        elements_are![eq(&Status::Ok), eq(&Status::error("connection refused"))]
+    );
+    guard.shutdown().expect("providers to shut down");
+}
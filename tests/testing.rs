@@ -0,0 +1,27 @@
+//! In-memory collector integration test.
+
+#![cfg(feature = "testing")]
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::elements_are;
+use googletest::matchers::eq;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn info_span_is_captured() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("testing")
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    tracing::info_span!("captured").in_scope(|| {
+        tracing::info!("inside span");
+    });
+
+    expect_that!(collector.span_names(), elements_are![eq("captured")]);
+    guard.shutdown().expect("providers to shut down");
+}
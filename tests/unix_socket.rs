@@ -0,0 +1,85 @@
+//! OTLP over a Unix domain socket integration test.
+
+#![cfg(unix)]
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use googletest::matchers::ok;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Response;
+use owiwi::OtlpConfig;
+use owiwi::Owiwi;
+use owiwi::TraceExporter;
+use tokio::net::UnixListener;
+
+/// gRPC method of OTLP span exports.
+const EXPORT_PATH: &str = "/opentelemetry.proto.collector.trace.v1.TraceService/Export";
+
+/// Empty gRPC message, an `ExportTraceServiceResponse` with no partial
+/// success.
+const EMPTY_MESSAGE: [u8; 5] = [0; 5];
+
+/// Answers every gRPC request with a success, sending its path to `paths`.
+async fn serve(listener: UnixListener, paths: mpsc::Sender<String>) {
+    // The reachability check connects first, without sending anything.
+    while let Ok((stream, _)) = listener.accept().await {
+        let Ok(mut connection) = h2::server::handshake(stream).await else {
+            continue;
+        };
+        while let Some(Ok((request, mut respond))) = connection.accept().await {
+            let _ = paths.send(request.uri().path().to_owned());
+            let response = Response::builder()
+                .header("content-type", "application/grpc")
+                .body(())
+                .expect("valid response");
+            let Ok(mut body) = respond.send_response(response, false) else {
+                continue;
+            };
+            let _ = body.send_data(Bytes::from_static(&EMPTY_MESSAGE), false);
+            let mut trailers = HeaderMap::new();
+            trailers.insert("grpc-status", HeaderValue::from_static("0"));
+            let _ = body.send_trailers(trailers);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[gtest]
+async fn spans_are_exported_through_the_socket() {
+    // The space is percent-encoded in the endpoint URL.
+    let path = std::env::temp_dir().join(format!("owiwi collector-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).expect("bindable socket");
+    let (paths_tx, paths_rx) = mpsc::channel();
+    tokio::spawn(serve(listener, paths_tx));
+
+    let exporter = OtlpConfig::builder()
+        .endpoint(
+            format!("unix://{}", path.display())
+                .parse()
+                .expect("valid URL"),
+        )
+        .timeout(Duration::from_secs(5))
+        .build();
+    let guard = Owiwi::builder()
+        .service_name("unix-socket")
+        .traces(TraceExporter::Otlp(exporter))
+        .build()
+        .try_init()
+        .expect("subscriber to be installed");
+
+    tracing::error_span!("request").in_scope(|| {});
+    expect_that!(guard.flush(), ok(eq(&())));
+
+    expect_that!(
+        paths_rx.recv_timeout(Duration::from_secs(5)),
+        ok(eq(EXPORT_PATH))
+    );
+    let _ = std::fs::remove_file(path);
+}
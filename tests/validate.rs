@@ -0,0 +1,24 @@
+//! Configuration validation tests.
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::anything;
+use googletest::matchers::eq;
+use googletest::matchers::ok;
+use owiwi::Owiwi;
+
+#[gtest]
+fn validation_leaves_the_global_subscriber_unset() {
+    let config = || {
+        Owiwi::builder()
+            .service_name("validate")
+            .no_telemetry(true)
+            .build()
+    };
+    expect_that!(config().validate(), ok(anything()));
+    expect_that!(config().validate(), ok(anything()));
+    expect_that!(tracing::dispatcher::has_been_set(), eq(false));
+
+    let guard = config().try_init().expect("subscriber to be installed");
+    guard.shutdown().expect("providers to shut down");
+}
@@ -0,0 +1,37 @@
+//! AWS X-Ray ID generation integration test.
+
+#![cfg(all(feature = "testing", feature = "xray"))]
+
+use std::time::SystemTime;
+
+use googletest::expect_that;
+use googletest::gtest;
+use googletest::matchers::eq;
+use googletest::matchers::le;
+use googletest::matchers::len;
+use owiwi::Owiwi;
+use owiwi::testing::InMemoryCollector;
+
+#[gtest]
+fn trace_ids_start_with_the_epoch() {
+    let collector = InMemoryCollector::default();
+    let guard = Owiwi::builder()
+        .service_name("xray")
+        .xray(true)
+        .build()
+        .try_init_with_exporter(collector.clone())
+        .expect("subscriber to be installed");
+
+    tracing::info_span!("captured").in_scope(|| {});
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("clock after the epoch")
+        .as_secs();
+    let spans = collector.spans();
+    expect_that!(spans, len(eq(1)));
+    let trace_id = format!("{:032x}", spans[0].span_context.trace_id());
+    let epoch = u64::from_str_radix(&trace_id[..8], 16).expect("hexadecimal trace ID");
+    expect_that!(now.abs_diff(epoch), le(5));
+    guard.shutdown().expect("providers to shut down");
+}